@@ -0,0 +1,125 @@
+//! PCM input of the `spectrum` effect: `/ws/audio` and UDP datagrams.
+//!
+//! Both carry raw signed 16-bit little-endian mono samples at
+//! `audio.sample_rate`. WAV files are only read from `audio.dir`.
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::IntoResponse,
+};
+use sp_core::AudioConfig;
+use sp_effects::{audio::AudioInput, EffectRegistration, SpectrumEffect};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::state::AppState;
+
+/// Audio pushed over the network, shared with the `spectrum` effect.
+pub struct AudioStream {
+    dir: PathBuf,
+    input: AudioInput,
+}
+
+impl AudioStream {
+    /// Create an empty stream as configured.
+    pub fn new(config: &AudioConfig) -> Self {
+        Self {
+            dir: PathBuf::from(&config.dir),
+            input: AudioInput::new(config.sample_rate),
+        }
+    }
+
+    /// Registration of the `spectrum` effect reading this stream and
+    /// playing files from the audio directory.
+    pub fn registration(&self) -> EffectRegistration {
+        SpectrumEffect::registration(Some(self.dir.clone()), Some(self.input.clone()))
+    }
+
+    /// Append raw PCM samples.
+    pub fn push(&self, pcm: &[u8]) {
+        self.input.push_pcm(pcm);
+    }
+
+    /// Bind `audio.bind:audio.udp_port` and spawn the task feeding its
+    /// datagrams to the stream until `shutdown` flips.
+    pub async fn spawn_udp(
+        state: Arc<AppState>,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> sp_core::Result<JoinHandle<()>> {
+        let config = &state.config.audio;
+        let socket = UdpSocket::bind((config.bind.as_str(), config.udp_port)).await?;
+        info!(addr = %socket.local_addr()?, "Receiving PCM audio over UDP");
+
+        Ok(tokio::spawn(async move {
+            let mut packet = vec![0u8; 65536];
+            loop {
+                tokio::select! {
+                    received = socket.recv(&mut packet) => match received {
+                        Ok(n) => state.audio.push(&packet[..n]),
+                        Err(e) => warn!(error = %e, "Audio UDP receive failed"),
+                    },
+                    _ = shutdown.changed() => break,
+                }
+            }
+        }))
+    }
+}
+
+/// `/ws/audio`: binary messages are appended as PCM; nothing is sent back.
+pub async fn audio_ws(
+    State(state): State<Arc<AppState>>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| audio_socket(socket, state))
+}
+
+async fn audio_socket(mut socket: WebSocket, state: Arc<AppState>) {
+    while let Some(Ok(msg)) = socket.recv().await {
+        match msg {
+            Message::Binary(pcm) => state.audio.push(&pcm),
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::Color;
+    use sp_effects::{EffectConfig, EffectParams};
+    use sp_renderer::Framebuffer;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pushed_pcm_feeds_spectrum() {
+        let stream = AudioStream::new(&AudioConfig::default());
+        let mut effect = stream.registration().create().unwrap();
+        let mut params = EffectParams::default();
+        params.extra.insert("mode".into(), "vu".into());
+        effect.init(&EffectConfig {
+            width: 16,
+            height: 4,
+            params,
+        });
+
+        let mut fb = Framebuffer::new(16, 4);
+        effect.tick(&mut fb, Duration::from_millis(16));
+        assert!(fb.data().iter().all(|c| *c == Color::BLACK));
+
+        // A half-scale square wave lights most of the VU meter
+        let pcm: Vec<u8> = (0..2048)
+            .flat_map(|i| if i % 2 == 0 { 16384i16 } else { -16384 }.to_le_bytes())
+            .collect();
+        stream.push(&pcm);
+        effect.tick(&mut fb, Duration::from_millis(16));
+        assert_ne!(fb.get(sp_core::Point::new(8, 0)), Some(Color::BLACK));
+    }
+}
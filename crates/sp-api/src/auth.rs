@@ -0,0 +1,211 @@
+//! Bearer-token authentication.
+//!
+//! Tokens and their scopes come from `[server.auth]`. With no token
+//! configured every request is let through, as before.
+
+use axum::{
+    extract::{MatchedPath, Query, Request, State},
+    http::{header, Method},
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+use sp_core::Scope;
+use std::sync::Arc;
+
+use crate::{inbound, state::AppState, validation::ApiError};
+
+/// Name of the token that authenticated a request, for per-client limits.
+#[derive(Debug, Clone)]
+pub struct TokenName(pub String);
+
+/// Scope a request needs, or `None` for public routes.
+fn required_scope(method: &Method, path: &str) -> Option<Scope> {
+    match path {
+        "/health" => None,
+        // WLED tools discover devices without credentials
+        "/json/info" => None,
+        // Parameter patches, frames and audio arrive over the socket after a GET upgrade
+        "/ws/effect" | "/ws/frames" | "/ws/audio" => Some(Scope::Control),
+        // Webhook URLs and secrets are sensitive even to read
+        _ if path.starts_with("/api/webhooks") => Some(Scope::Admin),
+        _ if method == Method::GET || method == Method::HEAD => Some(Scope::Read),
+        _ if path.starts_with("/api/scripts") => Some(Scope::Admin),
+        _ => Some(Scope::Control),
+    }
+}
+
+/// Whether `?token=` is accepted on `path`: only WebSocket and SSE clients,
+/// which cannot set headers, may put their token in the URL.
+fn takes_query_token(path: &str) -> bool {
+    path.starts_with("/ws/") || path == "/api/events"
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// Token from `Authorization: Bearer ...`, or a percent-decoded `?token=` on
+/// the routes allowing it.
+fn presented_token(request: &Request) -> Option<String> {
+    let header = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if let Some(token) = header {
+        return Some(token.to_string());
+    }
+
+    if !takes_query_token(request.uri().path()) {
+        return None;
+    }
+    Query::<TokenQuery>::try_from_uri(request.uri())
+        .ok()?
+        .0
+        .token
+}
+
+/// Middleware rejecting requests without a token granting the route's scope.
+pub async fn require_token(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let auth = &state.config.server.auth;
+    if !auth.enabled() {
+        return Ok(next.run(request).await);
+    }
+
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(request.uri().path(), MatchedPath::as_str);
+    let Some(required) = required_scope(request.method(), path) else {
+        return Ok(next.run(request).await);
+    };
+    // Signed inbound hooks are checked against their HMAC secret instead
+    if inbound::is_signed(&state, request.uri().path()) {
+        return Ok(next.run(request).await);
+    }
+
+    let token = presented_token(&request)
+        .ok_or_else(|| ApiError::Unauthorized("Missing bearer token".to_string()))?;
+    let entry = auth
+        .find(token.trim())
+        .ok_or_else(|| ApiError::Unauthorized("Invalid token".to_string()))?;
+
+    if !entry.allows(required) {
+        tracing::warn!(token = %entry.name, path, "Token lacks required scope");
+        return Err(ApiError::Forbidden(format!(
+            "Token '{}' lacks the '{}' scope",
+            entry.name,
+            required.as_str()
+        )));
+    }
+
+    let name = TokenName(entry.name.clone());
+    request.extensions_mut().insert(name);
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_router, Renderer};
+    use axum::{body::Body, http::StatusCode};
+    use sp_core::{Config, TokenConfig};
+    use sp_effects::EffectManager;
+    use sp_hub75::MockDriver;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(required_scope(&Method::GET, "/health"), None);
+        assert_eq!(required_scope(&Method::GET, "/json/info"), None);
+        assert_eq!(
+            required_scope(&Method::GET, "/api/effect"),
+            Some(Scope::Read)
+        );
+        assert_eq!(
+            required_scope(&Method::POST, "/api/effect"),
+            Some(Scope::Control)
+        );
+        assert_eq!(
+            required_scope(&Method::GET, "/ws/effect"),
+            Some(Scope::Control)
+        );
+        assert_eq!(
+            required_scope(&Method::GET, "/ws/frames"),
+            Some(Scope::Control)
+        );
+        assert_eq!(
+            required_scope(&Method::GET, "/ws/audio"),
+            Some(Scope::Control)
+        );
+        assert_eq!(
+            required_scope(&Method::DELETE, "/api/scripts/:name"),
+            Some(Scope::Admin)
+        );
+        assert_eq!(
+            required_scope(&Method::GET, "/api/webhooks"),
+            Some(Scope::Admin)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_router_enforces_scopes() {
+        let mut config = Config::default();
+        config.server.auth.tokens.push(TokenConfig {
+            name: "viewer".to_string(),
+            token: "viewer-token-0123".to_string(),
+            scopes: vec![Scope::Read],
+        });
+
+        // Rejected requests never reach the render thread, so it isn't spawned
+        let manager = EffectManager::new(config.panel.width, config.panel.height);
+        let (_renderer, handle) = Renderer::new(manager, Box::new(MockDriver::new()));
+        let router = create_router(crate::AppState::new(config, handle));
+
+        let send = |method: &str, uri: &str, token: Option<&str>| {
+            let mut request = Request::builder().method(method).uri(uri);
+            if let Some(token) = token {
+                request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+            }
+            router.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        let response = send("GET", "/health", None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = send("GET", "/api/brightness", None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(response.headers().contains_key(header::WWW_AUTHENTICATE));
+
+        let response = send("GET", "/api/brightness", Some("wrong-token-00000"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = send("GET", "/api/brightness", Some("viewer-token-0123"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = send("POST", "/api/effect/stop", Some("viewer-token-0123"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Tokens in the URL are only taken by streaming routes, decoded
+        let response = send("GET", "/api/brightness?token=viewer-token-0123", None)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = send("GET", "/api/events?token=viewer%2Dtoken%2D0123", None)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
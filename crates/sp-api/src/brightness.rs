@@ -0,0 +1,145 @@
+//! Runtime panel brightness with smooth ramps.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+/// Maximum panel brightness.
+pub const MAX_BRIGHTNESS: u8 = 100;
+
+/// Current panel brightness, optionally ramping towards a target.
+#[derive(Debug, Clone)]
+pub struct Brightness {
+    current: u8,
+    ramp: Option<Ramp>,
+}
+
+#[derive(Debug, Clone)]
+struct Ramp {
+    from: u8,
+    target: u8,
+    start: Instant,
+    duration: Duration,
+}
+
+/// Serializable brightness state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct BrightnessStatus {
+    pub brightness: u8,
+    pub target: u8,
+    pub ramping: bool,
+}
+
+impl Brightness {
+    /// Create a brightness state at a fixed level.
+    pub fn new(level: u8) -> Self {
+        Self {
+            current: level.min(MAX_BRIGHTNESS),
+            ramp: None,
+        }
+    }
+
+    /// Brightness currently applied to the panel.
+    pub fn current(&self) -> u8 {
+        self.current
+    }
+
+    /// Brightness the panel is heading to.
+    pub fn target(&self) -> u8 {
+        self.ramp.as_ref().map_or(self.current, |r| r.target)
+    }
+
+    /// Whether a ramp is in progress.
+    pub fn is_ramping(&self) -> bool {
+        self.ramp.is_some()
+    }
+
+    /// Serializable snapshot.
+    pub fn status(&self) -> BrightnessStatus {
+        BrightnessStatus {
+            brightness: self.current,
+            target: self.target(),
+            ramping: self.is_ramping(),
+        }
+    }
+
+    /// Jump to a level immediately, cancelling any ramp.
+    pub fn set(&mut self, level: u8) {
+        self.current = level.min(MAX_BRIGHTNESS);
+        self.ramp = None;
+    }
+
+    /// Ramp linearly from the current level to `target` over `duration`.
+    pub fn ramp_to(&mut self, target: u8, duration: Duration) {
+        let target = target.min(MAX_BRIGHTNESS);
+        if duration.is_zero() || target == self.current {
+            self.set(target);
+            return;
+        }
+
+        self.ramp = Some(Ramp {
+            from: self.current,
+            target,
+            start: Instant::now(),
+            duration,
+        });
+    }
+
+    /// Advance the ramp to `now`. Returns the new level if it changed.
+    pub fn step(&mut self, now: Instant) -> Option<u8> {
+        let ramp = self.ramp.as_ref()?;
+
+        let elapsed = now.saturating_duration_since(ramp.start);
+        let t = (elapsed.as_secs_f32() / ramp.duration.as_secs_f32()).min(1.0);
+        let from = f32::from(ramp.from);
+        let level = (from + (f32::from(ramp.target) - from) * t).round() as u8;
+
+        if t >= 1.0 {
+            self.ramp = None;
+        }
+
+        if level == self.current {
+            return None;
+        }
+        self.current = level;
+        Some(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_clamps() {
+        let mut b = Brightness::new(150);
+        assert_eq!(b.current(), 100);
+
+        b.set(40);
+        assert_eq!(b.current(), 40);
+        assert!(!b.is_ramping());
+    }
+
+    #[test]
+    fn test_ramp() {
+        let mut b = Brightness::new(0);
+        b.ramp_to(100, Duration::from_secs(1));
+        assert_eq!(b.target(), 100);
+        assert!(b.is_ramping());
+
+        let start = b.ramp.as_ref().unwrap().start;
+        assert_eq!(b.step(start + Duration::from_millis(500)), Some(50));
+        assert_eq!(b.step(start + Duration::from_millis(500)), None);
+        assert_eq!(b.step(start + Duration::from_secs(2)), Some(100));
+        assert!(!b.is_ramping());
+        assert_eq!(b.step(start + Duration::from_secs(3)), None);
+    }
+
+    #[test]
+    fn test_zero_duration_jumps() {
+        let mut b = Brightness::new(80);
+        b.ramp_to(20, Duration::ZERO);
+        assert_eq!(b.current(), 20);
+        assert!(!b.is_ramping());
+    }
+}
@@ -0,0 +1,365 @@
+//! Persistent canvas drawn over HTTP: `POST /api/canvas/ops` and `GET /api/canvas`.
+//!
+//! Drawing operations accumulate on the canvas, which the `canvas` effect
+//! shows. The canvas keeps its pixels while other effects run.
+
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use sp_core::{Color, Error, Point};
+use sp_effects::{EffectParams, EffectRegistration, LiveInput, LiveInputEffect};
+use sp_renderer::Framebuffer;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{
+    state::AppState,
+    validation::{ApiError, ValidatedJson},
+};
+
+/// Effect showing the canvas.
+pub const EFFECT_NAME: &str = "canvas";
+
+/// Most operations accepted in one request.
+const MAX_OPS: u64 = 1000;
+
+/// Coordinates are limited to this distance from the origin. Rectangles are
+/// clipped to the canvas, so only lines can cost more than the canvas size.
+const MAX_COORD: i32 = 4096;
+
+/// Longest text drawn by one operation, in characters.
+const MAX_TEXT: usize = 256;
+
+/// One drawing operation; colors are `{"r", "g", "b"}`, points `{"x", "y"}`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum CanvasOp {
+    /// Set one pixel.
+    Pixel {
+        #[schema(value_type = Object)]
+        at: Point,
+        #[schema(value_type = Object)]
+        color: Color,
+    },
+    /// Straight line, both ends included.
+    Line {
+        #[schema(value_type = Object)]
+        from: Point,
+        #[schema(value_type = Object)]
+        to: Point,
+        #[schema(value_type = Object)]
+        color: Color,
+    },
+    /// Rectangle with its top-left corner at `at`, filled by default.
+    Rect {
+        #[schema(value_type = Object)]
+        at: Point,
+        width: u32,
+        height: u32,
+        #[schema(value_type = Object)]
+        color: Color,
+        #[serde(default = "default_true")]
+        filled: bool,
+    },
+    /// Paint the whole canvas.
+    Fill {
+        #[schema(value_type = Object)]
+        color: Color,
+    },
+    /// 5x7 text with its top-left corner at `at`, clipped at the edges.
+    Text {
+        #[schema(value_type = Object)]
+        at: Point,
+        text: String,
+        #[schema(value_type = Object)]
+        color: Color,
+    },
+    /// Turn every pixel off.
+    Clear,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl CanvasOp {
+    /// Check coordinates and sizes before anything is drawn.
+    fn check(&self) -> std::result::Result<(), &'static str> {
+        let near = |p: &Point| p.x.abs() <= MAX_COORD && p.y.abs() <= MAX_COORD;
+        let fits = match self {
+            Self::Pixel { at, .. } => near(at),
+            Self::Line { from, to, .. } => near(from) && near(to),
+            Self::Rect {
+                at, width, height, ..
+            } => near(at) && *width <= MAX_COORD as u32 && *height <= MAX_COORD as u32,
+            Self::Text { at, text, .. } => {
+                if text.chars().count() > MAX_TEXT {
+                    return Err("text is longer than 256 characters");
+                }
+                near(at)
+            }
+            Self::Fill { .. } | Self::Clear => true,
+        };
+        if !fits {
+            return Err("coordinates and sizes must be within 4096 pixels");
+        }
+        Ok(())
+    }
+
+    fn draw(&self, fb: &mut Framebuffer) {
+        match self {
+            Self::Pixel { at, color } => fb.set(*at, *color),
+            Self::Line { from, to, color } => {
+                // Skip lines that can't cross the canvas
+                let (width, height) = (fb.width() as i32, fb.height() as i32);
+                let off = (from.x < 0 && to.x < 0)
+                    || (from.y < 0 && to.y < 0)
+                    || (from.x >= width && to.x >= width)
+                    || (from.y >= height && to.y >= height);
+                if !off {
+                    fb.draw_line(*from, *to, *color);
+                }
+            }
+            Self::Rect {
+                at,
+                width,
+                height,
+                color,
+                filled,
+            } => {
+                if *width == 0 || *height == 0 {
+                    return;
+                }
+                if *filled {
+                    fb.fill_rect(at.x, at.y, *width, *height, *color);
+                } else {
+                    let (right, bottom) = (at.x + *width as i32 - 1, at.y + *height as i32 - 1);
+                    fb.draw_hline(at.y, at.x, right, *color);
+                    fb.draw_hline(bottom, at.x, right, *color);
+                    fb.draw_vline(at.x, at.y, bottom, *color);
+                    fb.draw_vline(right, at.y, bottom, *color);
+                }
+            }
+            Self::Fill { color } => fb.fill(*color),
+            Self::Text { at, text, color } => fb.draw_text(at.x, at.y, text, *color),
+            Self::Clear => fb.clear(),
+        }
+    }
+}
+
+/// Pixels drawn through the canvas API, shared with the `canvas` effect.
+pub struct Canvas {
+    input: LiveInput,
+}
+
+impl Canvas {
+    /// Create a black canvas the size of the panel.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            input: LiveInput::new(width, height),
+        }
+    }
+
+    /// Registration of the `canvas` effect.
+    pub fn registration(&self) -> EffectRegistration {
+        LiveInputEffect::registration(
+            EFFECT_NAME,
+            "Pixels drawn through the canvas API",
+            self.input.clone(),
+        )
+    }
+
+    /// Apply `ops` in order; none is applied if any is invalid.
+    pub fn apply(&self, ops: &[CanvasOp]) -> sp_core::Result<()> {
+        for (i, op) in ops.iter().enumerate() {
+            op.check()
+                .map_err(|message| Error::invalid_param(format!("ops[{i}]"), message))?;
+        }
+        self.input.write(|fb| ops.iter().for_each(|op| op.draw(fb)));
+        Ok(())
+    }
+
+    /// A copy of the current pixels.
+    pub fn snapshot(&self) -> Framebuffer {
+        self.input.snapshot()
+    }
+}
+
+/// Encode `fb` as an 8-bit RGB PNG.
+fn encode_png(fb: &Framebuffer) -> std::result::Result<Vec<u8>, png::EncodingError> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, fb.width(), fb.height());
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&fb.to_rgb_bytes())?;
+    writer.finish()?;
+    Ok(out)
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CanvasRequest {
+    /// Applied in order.
+    #[validate(length(min = 1, max = MAX_OPS))]
+    pub ops: Vec<CanvasOp>,
+    /// Switch the panel to the `canvas` effect.
+    #[serde(default = "default_true")]
+    pub show: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/canvas/ops",
+    tag = "canvas",
+    request_body = CanvasRequest,
+    responses(
+        (status = 200, description = "Operations drawn on the canvas", body = Object),
+        (status = 400, description = "Invalid operation, nothing drawn", body = ErrorResponse)
+    )
+)]
+pub async fn draw(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<CanvasRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (applied, show) = (req.ops.len(), req.show);
+    // Drawing a full batch is CPU-bound; keep it off the async workers
+    let canvas_state = Arc::clone(&state);
+    tokio::task::spawn_blocking(move || canvas_state.canvas.apply(&req.ops))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Canvas task failed: {e}")))??;
+
+    if show {
+        let current = state
+            .renderer
+            .call(|manager| manager.current_effect().map(String::from))
+            .await?;
+        if current.as_deref() != Some(EFFECT_NAME) {
+            state
+                .renderer
+                .set_effect(EFFECT_NAME, EffectParams::default())
+                .await?;
+        }
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "applied": applied
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/canvas",
+    tag = "canvas",
+    responses((status = 200, description = "Canvas as a PNG image", content_type = "image/png"))
+)]
+pub async fn get_canvas(State(state): State<Arc<AppState>>) -> Result<Response, ApiError> {
+    let png = encode_png(&state.canvas.snapshot())
+        .map_err(|e| ApiError::Internal(format!("Failed to encode canvas: {e}")))?;
+    Ok(([(header::CONTENT_TYPE, "image/png")], png).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_router, test_util};
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use serde_json::json;
+    use sp_core::Config;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_ops() {
+        assert!(sp_effects::RESERVED_NAMES.contains(&EFFECT_NAME));
+        let canvas = Canvas::new(16, 8);
+        let ops: Vec<CanvasOp> = serde_json::from_value(json!([
+            { "op": "fill", "color": { "r": 0, "g": 0, "b": 255 } },
+            { "op": "rect", "at": { "x": 1, "y": 1 }, "width": 4, "height": 3,
+              "color": { "r": 255, "g": 0, "b": 0 }, "filled": false },
+            { "op": "pixel", "at": { "x": 15, "y": 7 }, "color": { "r": 0, "g": 255, "b": 0 } },
+            { "op": "text", "at": { "x": 8, "y": 0 }, "text": "I",
+              "color": { "r": 255, "g": 255, "b": 255 } },
+        ]))
+        .unwrap();
+        canvas.apply(&ops).unwrap();
+
+        let fb = canvas.snapshot();
+        assert_eq!(fb.get(Point::new(0, 0)), Some(Color::BLUE));
+        assert_eq!(fb.get(Point::new(4, 3)), Some(Color::RED));
+        assert_eq!(fb.get(Point::new(2, 2)), Some(Color::BLUE));
+        assert_eq!(fb.get(Point::new(15, 7)), Some(Color::GREEN));
+        assert!(fb.data().contains(&Color::WHITE));
+
+        // A bad op anywhere leaves the canvas untouched
+        let ops: Vec<CanvasOp> = serde_json::from_value(json!([
+            { "op": "clear" },
+            { "op": "line", "from": { "x": 0, "y": 0 }, "to": { "x": 100000, "y": 0 },
+              "color": { "r": 255, "g": 0, "b": 0 } },
+        ]))
+        .unwrap();
+        assert!(canvas.apply(&ops).is_err());
+        assert_eq!(canvas.snapshot().get(Point::new(0, 0)), Some(Color::BLUE));
+    }
+
+    #[test]
+    fn test_largest_batch_is_cheap() {
+        let canvas = Canvas::new(64, 32);
+        let color = json!({ "r": 255, "g": 0, "b": 0 });
+        let ops: Vec<CanvasOp> = (0..MAX_OPS as i32)
+            .map(|i| {
+                let op = if i % 2 == 0 {
+                    json!({ "op": "rect", "at": { "x": -MAX_COORD, "y": -MAX_COORD },
+                            "width": MAX_COORD, "height": MAX_COORD, "color": color })
+                } else {
+                    json!({ "op": "line", "from": { "x": -MAX_COORD, "y": -MAX_COORD },
+                            "to": { "x": MAX_COORD, "y": MAX_COORD }, "color": color })
+                };
+                serde_json::from_value(op).unwrap()
+            })
+            .collect();
+
+        let started = std::time::Instant::now();
+        canvas.apply(&ops).unwrap();
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_draw_and_png() {
+        let config = Config::default();
+        let (state, _render) = test_util::start(config);
+        let router = create_router(Arc::clone(&state));
+
+        let body = json!({
+            "ops": [{ "op": "pixel", "at": { "x": 3, "y": 2 }, "color": { "r": 255, "g": 0, "b": 0 } }]
+        });
+        let request = Request::post("/api/canvas/ops")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let current = state
+            .renderer
+            .call(|m| m.current_effect().map(String::from))
+            .await
+            .unwrap();
+        assert_eq!(current.as_deref(), Some(EFFECT_NAME));
+
+        let request = Request::get("/api/canvas").body(Body::empty()).unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+        let png = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let mut reader = png::Decoder::new(&png[..]).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        let offset = (2 * 64 + 3) * 3;
+        assert_eq!(&pixels[offset..offset + 3], &[255, 0, 0]);
+    }
+}
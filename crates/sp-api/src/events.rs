@@ -0,0 +1,147 @@
+//! Broadcast bus of panel state changes.
+//!
+//! The render thread publishes an [`Event`] whenever the effect, brightness
+//! or driver health changes; webhooks and other integrations subscribe.
+
+use serde::Serialize;
+use sp_effects::EffectParams;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+use crate::{brightness::BrightnessStatus, notify::Priority};
+
+/// Events buffered per subscriber before the slowest one starts lagging.
+const CAPACITY: usize = 256;
+
+/// What happened.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    /// An effect started or its parameters changed.
+    EffectChanged {
+        effect: String,
+        params: EffectParams,
+    },
+    /// An effect was stopped or replaced.
+    EffectFinished { effect: String },
+    /// Brightness was set, or a ramp completed.
+    BrightnessChanged {
+        #[serde(flatten)]
+        status: BrightnessStatus,
+    },
+    /// The driver started failing, or recovered.
+    DriverHealth {
+        healthy: bool,
+        error: Option<String>,
+    },
+    /// Text was sent to the panel.
+    TextShown { text: String },
+    /// A notification came on screen.
+    NotificationShown {
+        id: u64,
+        priority: Priority,
+        text: Option<String>,
+    },
+}
+
+impl EventKind {
+    /// Every event type, as serialized in `type`.
+    pub const NAMES: [&'static str; 6] = [
+        "effect_changed",
+        "effect_finished",
+        "brightness_changed",
+        "driver_health",
+        "text_shown",
+        "notification_shown",
+    ];
+
+    /// Serialized `type` of this event.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::EffectChanged { .. } => Self::NAMES[0],
+            Self::EffectFinished { .. } => Self::NAMES[1],
+            Self::BrightnessChanged { .. } => Self::NAMES[2],
+            Self::DriverHealth { .. } => Self::NAMES[3],
+            Self::TextShown { .. } => Self::NAMES[4],
+            Self::NotificationShown { .. } => Self::NAMES[5],
+        }
+    }
+}
+
+/// An event with its sequence number and time.
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub id: u64,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// Fan-out of events to any number of subscribers.
+#[derive(Debug)]
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+    next_id: AtomicU64,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            sender: broadcast::channel(CAPACITY).0,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Publish an event; dropped if nobody is subscribed.
+    pub fn publish(&self, kind: EventKind) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        let event = Event {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            timestamp,
+            kind,
+        };
+        let _ = self.sender.send(event);
+    }
+
+    /// Receive events published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_and_serialize() {
+        let bus = EventBus::new();
+        bus.publish(EventKind::TextShown {
+            text: "dropped".to_string(),
+        });
+
+        let mut events = bus.subscribe();
+        bus.publish(EventKind::EffectFinished {
+            effect: "fire".to_string(),
+        });
+
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.id, 2);
+        assert_eq!(event.kind.name(), "effect_finished");
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "effect_finished");
+        assert_eq!(json["effect"], "fire");
+        assert!(json["timestamp"].as_u64().unwrap() > 0);
+    }
+}
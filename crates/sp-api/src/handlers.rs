@@ -1,36 +1,56 @@
 //! HTTP request handlers.
 
 use axum::{
-    extract::State,
+    extract::{
+        rejection::JsonRejection,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde::{Deserialize, Serialize};
-use sp_effects::{available_effects, EffectParams};
+use sp_effects::{CompiledScript, EffectParams};
 use std::sync::Arc;
+use std::time::Duration;
+use utoipa::ToSchema;
 use validator::Validate;
 
-use crate::{state::AppState, validation::ApiError};
+use crate::{
+    brightness::BrightnessStatus,
+    events::EventKind,
+    notify::{Icon, Notification, NotificationsStatus, NotifyReceipt, NotifyStyle, Priority},
+    render::TextCommand,
+    state::AppState,
+    validation::{ApiError, ValidatedJson},
+    webhooks::{Delivery, Webhook},
+};
 
 // ============================================================================
 // Health Check
 // ============================================================================
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct HealthResponse {
     pub status: &'static str,
     pub uptime_secs: u64,
     pub panel: PanelInfo,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct PanelInfo {
     pub width: u32,
     pub height: u32,
     pub brightness: u8,
 }
 
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "system",
+    responses((status = 200, description = "Service is up", body = HealthResponse))
+)]
 pub async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "ok",
@@ -38,7 +58,7 @@ pub async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse>
         panel: PanelInfo {
             width: state.config.panel.width,
             height: state.config.panel.height,
-            brightness: state.config.panel.brightness,
+            brightness: state.renderer.brightness().brightness,
         },
     })
 }
@@ -47,26 +67,38 @@ pub async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse>
 // Effects
 // ============================================================================
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct EffectRequest {
+    #[validate(length(min = 1, max = 64))]
     pub name: String,
+    /// `intensity`, `speed`, `color` and effect-specific params (see `/api/effects/{name}`).
     #[serde(default)]
+    #[schema(value_type = Object)]
     pub params: EffectParams,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct EffectResponse {
     pub success: bool,
     pub effect: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/effect",
+    tag = "effects",
+    request_body = EffectRequest,
+    responses(
+        (status = 200, description = "Effect started", body = EffectResponse),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 404, description = "Unknown effect", body = ErrorResponse)
+    )
+)]
 pub async fn set_effect(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<EffectRequest>,
+    ValidatedJson(req): ValidatedJson<EffectRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let mut manager = state.effect_manager.write().await;
-
-    manager.set_effect(&req.name, req.params)?;
+    state.renderer.set_effect(&req.name, req.params).await?;
 
     Ok((
         StatusCode::OK,
@@ -77,39 +109,346 @@ pub async fn set_effect(
     ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/effect",
+    tag = "effects",
+    responses((status = 200, description = "Current and available effects", body = Object))
+)]
 pub async fn get_current_effect(
     State(state): State<Arc<AppState>>,
-) -> Json<serde_json::Value> {
-    let manager = state.effect_manager.read().await;
-    let current = manager.current_effect().map(String::from);
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let (current, available) = state
+        .renderer
+        .call(|manager| {
+            (
+                manager.current_effect().map(String::from),
+                manager.available_effects(),
+            )
+        })
+        .await?;
 
-    Json(serde_json::json!({
+    Ok(Json(serde_json::json!({
         "current": current,
-        "available": available_effects(),
-    }))
+        "available": available,
+    })))
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ParamsResponse {
+    pub success: bool,
+    pub effect: String,
+    #[schema(value_type = Object)]
+    pub params: EffectParams,
+}
+
+/// Merge partial params into the running effect, hot-applying when supported.
+async fn apply_param_patch(
+    state: &AppState,
+    patch: serde_json::Map<String, serde_json::Value>,
+) -> Result<ParamsResponse, ApiError> {
+    let update = state
+        .renderer
+        .update_params(patch)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("No active effect".to_string()))?;
+
+    Ok(ParamsResponse {
+        success: true,
+        effect: update.effect,
+        params: update.params,
+    })
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/effect",
+    tag = "effects",
+    request_body(content = Object, description = "Partial params; `null` resets a key"),
+    responses(
+        (status = 200, description = "Merged params", body = ParamsResponse),
+        (status = 400, description = "Invalid parameters or no active effect", body = ErrorResponse)
+    )
+)]
+pub async fn patch_effect(
+    State(state): State<Arc<AppState>>,
+    payload: Result<Json<serde_json::Map<String, serde_json::Value>>, JsonRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Json(patch) = payload?;
+    let response = apply_param_patch(&state, patch).await?;
+    Ok(Json(response))
+}
+
+/// WebSocket variant of `PATCH /api/effect` for continuous slider drags.
+///
+/// Each text message is a partial params object; each reply is the
+/// resulting `ParamsResponse` or an `ErrorResponse`.
+pub async fn effect_params_ws(
+    State(state): State<Arc<AppState>>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| params_socket(socket, state))
 }
 
+async fn params_socket(mut socket: WebSocket, state: Arc<AppState>) {
+    while let Some(Ok(msg)) = socket.recv().await {
+        let text = match msg {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let reply = match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&text)
+        {
+            Ok(patch) => match apply_param_patch(&state, patch).await {
+                Ok(response) => serde_json::to_string(&response),
+                Err(e) => serde_json::to_string(&e.into_response_body()),
+            },
+            Err(e) => {
+                serde_json::to_string(&ApiError::BadRequest(e.to_string()).into_response_body())
+            }
+        };
+
+        let Ok(reply) = reply else { continue };
+        if socket.send(Message::Text(reply)).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Live preview: streams each new frame as packed RGB bytes.
+pub async fn preview_ws(
+    State(state): State<Arc<AppState>>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| preview_socket(socket, state))
+}
+
+async fn preview_socket(mut socket: WebSocket, state: Arc<AppState>) {
+    let period = Duration::from_secs(1) / state.config.panel.target_fps.max(1);
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut last_sequence = None;
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let sequence = state.frames.sequence();
+                if last_sequence == Some(sequence) {
+                    continue;
+                }
+                last_sequence = Some(sequence);
+
+                let frame = state.frames.latest().to_rgb_bytes();
+                if socket.send(Message::Binary(frame)).await.is_err() {
+                    break;
+                }
+            }
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/effects",
+    tag = "effects",
+    responses((status = 200, description = "Registered effects with their params", body = Object))
+)]
+pub async fn list_effects(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let effects = state
+        .renderer
+        .call(|manager| manager.registry().infos())
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "effects": effects,
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/effects/{name}",
+    tag = "effects",
+    params(("name" = String, Path, description = "Effect name")),
+    responses(
+        (status = 200, description = "Effect params as JSON Schema", body = Object),
+        (status = 404, description = "Unknown effect", body = ErrorResponse)
+    )
+)]
+pub async fn get_effect_schema(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let lookup = name.clone();
+    let registration = state
+        .renderer
+        .call(move |manager| manager.registry().get(&lookup).cloned())
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Effect not found: {name}")))?;
+
+    Ok(Json(serde_json::json!({
+        "name": registration.name,
+        "description": registration.description,
+        "origin": registration.origin,
+        "schema": registration.schema.to_json_schema(),
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/effect/stop",
+    tag = "effects",
+    responses((status = 200, description = "Effect stopped", body = Object))
+)]
 pub async fn stop_effect(
     State(state): State<Arc<AppState>>,
-) -> Json<serde_json::Value> {
-    let mut manager = state.effect_manager.write().await;
-    manager.stop();
+) -> Result<Json<serde_json::Value>, ApiError> {
+    state.renderer.stop().await?;
 
-    Json(serde_json::json!({
+    Ok(Json(serde_json::json!({
         "success": true,
         "message": "Effect stopped"
-    }))
+    })))
+}
+
+// ============================================================================
+// Scripts
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ScriptRequest {
+    #[validate(length(min = 1, max = 32))]
+    pub name: String,
+    #[validate(length(min = 1, max = 65536))]
+    pub source: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/scripts",
+    tag = "scripts",
+    responses((status = 200, description = "Stored scripts", body = Object))
+)]
+pub async fn list_scripts(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let scripts = state
+        .renderer
+        .call(|manager| manager.scripts().list())
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "scripts": scripts,
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/scripts",
+    tag = "scripts",
+    request_body = ScriptRequest,
+    responses(
+        (status = 201, description = "Script compiled and registered", body = Object),
+        (status = 400, description = "Invalid script", body = ErrorResponse)
+    )
+)]
+pub async fn upload_script(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<ScriptRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Compile here so a large script doesn't stall the render thread
+    let (name, source) = (req.name.clone(), req.source);
+    let script = tokio::task::spawn_blocking(move || CompiledScript::compile(&name, &source))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Script compilation failed: {e}")))??;
+    state
+        .renderer
+        .call(move |manager| manager.insert_compiled_script(script))
+        .await??;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "success": true,
+            "name": req.name
+        })),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/scripts/{name}",
+    tag = "scripts",
+    params(("name" = String, Path, description = "Script name")),
+    responses(
+        (status = 200, description = "Script source", body = Object),
+        (status = 404, description = "Unknown script", body = ErrorResponse)
+    )
+)]
+pub async fn get_script(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let lookup = name.clone();
+    let source = state
+        .renderer
+        .call(move |manager| manager.scripts().source(&lookup).map(String::from))
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Script not found: {name}")))?;
+
+    Ok(Json(serde_json::json!({
+        "name": name,
+        "source": source
+    })))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/scripts/{name}",
+    tag = "scripts",
+    params(("name" = String, Path, description = "Script name")),
+    responses(
+        (status = 200, description = "Script removed", body = Object),
+        (status = 404, description = "Unknown script", body = ErrorResponse)
+    )
+)]
+pub async fn delete_script(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let remove = name.clone();
+    let removed = state
+        .renderer
+        .call(move |manager| manager.remove_script(&remove))
+        .await?;
+
+    if !removed {
+        return Err(ApiError::NotFound(format!("Script not found: {name}")));
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "name": name
+    })))
 }
 
 // ============================================================================
 // Text Display
 // ============================================================================
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct TextRequest {
     #[validate(length(min = 1, max = 256))]
     pub text: String,
     #[serde(default = "default_color")]
+    /// RGB as `[r, g, b]`.
+    #[schema(value_type = Vec<u8>)]
     pub color: [u8; 3],
     #[serde(default)]
     pub scroll: bool,
@@ -126,17 +465,29 @@ fn default_speed() -> u32 {
     50
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/text",
+    tag = "display",
+    request_body = TextRequest,
+    responses(
+        (status = 200, description = "Text displayed", body = Object),
+        (status = 400, description = "Invalid request", body = ErrorResponse)
+    )
+)]
 pub async fn display_text(
-    State(_state): State<Arc<AppState>>,
-    Json(req): Json<TextRequest>,
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<TextRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    // TODO: Implement text rendering with embedded font
-    tracing::info!(
-        text = %req.text,
-        color = ?req.color,
-        scroll = req.scroll,
-        "Display text requested"
-    );
+    state
+        .renderer
+        .show_text(TextCommand {
+            text: req.text.clone(),
+            color: req.color,
+            scroll: req.scroll,
+            speed: req.speed,
+        })
+        .await?;
 
     Ok((
         StatusCode::OK,
@@ -151,26 +502,304 @@ pub async fn display_text(
 // Brightness
 // ============================================================================
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct BrightnessRequest {
     #[validate(range(min = 0, max = 100))]
     pub brightness: u8,
+    /// Ramp duration; the change is immediate when absent or zero.
+    #[serde(default)]
+    #[validate(range(max = 60000))]
+    pub duration_ms: u64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/brightness",
+    tag = "display",
+    responses((status = 200, description = "Current brightness", body = BrightnessStatus))
+)]
+pub async fn get_brightness(State(state): State<Arc<AppState>>) -> Json<BrightnessStatus> {
+    Json(state.renderer.brightness())
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/brightness",
+    tag = "display",
+    request_body = BrightnessRequest,
+    responses(
+        (status = 200, description = "Brightness set or ramp started", body = Object),
+        (status = 400, description = "Invalid request", body = ErrorResponse)
+    )
+)]
 pub async fn set_brightness(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<BrightnessRequest>,
+    ValidatedJson(req): ValidatedJson<BrightnessRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let mut driver = state.driver.write().await;
-    driver
-        .set_brightness(req.brightness)
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
-
+    let status = state
+        .renderer
+        .set_brightness(req.brightness, Duration::from_millis(req.duration_ms))
+        .await?;
     Ok((
         StatusCode::OK,
         Json(serde_json::json!({
             "success": true,
-            "brightness": req.brightness
+            "brightness": status.brightness,
+            "target": status.target,
+            "ramping": status.ramping
         })),
     ))
 }
+
+// ============================================================================
+// Notifications
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct NotifyRequest {
+    #[validate(length(min = 1, max = 256))]
+    pub text: Option<String>,
+    pub icon: Option<Icon>,
+    #[serde(default = "default_color")]
+    /// RGB as `[r, g, b]`.
+    #[schema(value_type = Vec<u8>)]
+    pub color: [u8; 3],
+    #[serde(default)]
+    pub style: NotifyStyle,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default = "default_notify_duration")]
+    #[validate(range(min = 100, max = 600000))]
+    pub duration_ms: u64,
+}
+
+fn default_notify_duration() -> u64 {
+    5000
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/notify",
+    tag = "notifications",
+    request_body = NotifyRequest,
+    responses(
+        (status = 200, description = "Notification shown or queued", body = NotifyReceipt),
+        (status = 400, description = "Invalid request or queue full", body = ErrorResponse)
+    )
+)]
+pub async fn notify(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<NotifyRequest>,
+) -> Result<Json<NotifyReceipt>, ApiError> {
+    if req.text.is_none() && req.icon.is_none() {
+        return Err(ApiError::invalid_field("text", "text or icon is required"));
+    }
+
+    let receipt = state
+        .renderer
+        .notify(Notification {
+            id: 0,
+            text: req.text,
+            icon: req.icon,
+            color: req.color,
+            style: req.style,
+            priority: req.priority,
+            duration_ms: req.duration_ms,
+        })
+        .await?;
+
+    Ok(Json(receipt))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notify/queue",
+    tag = "notifications",
+    responses((status = 200, description = "Visible and waiting notifications", body = NotificationsStatus))
+)]
+pub async fn get_notify_queue(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<NotificationsStatus>, ApiError> {
+    Ok(Json(state.renderer.notifications().await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/notify/queue",
+    tag = "notifications",
+    responses((status = 200, description = "Waiting notifications dropped", body = Object))
+)]
+pub async fn clear_notify_queue(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let cleared = state.renderer.clear_notifications().await?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "cleared": cleared
+    })))
+}
+
+// ============================================================================
+// Webhooks
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct WebhookRequest {
+    #[validate(url)]
+    pub url: String,
+    /// Key for the `X-Super-Pixeled-Signature` HMAC-SHA256 header.
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Event types to deliver; all when empty.
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl WebhookRequest {
+    fn check_events(&self) -> Result<(), ApiError> {
+        match self
+            .events
+            .iter()
+            .find(|name| !EventKind::NAMES.contains(&name.as_str()))
+        {
+            Some(unknown) => Err(ApiError::invalid_field(
+                "events",
+                format!("unknown event type {unknown}"),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A webhook as returned by the API; the secret is never echoed back.
+#[derive(Serialize, ToSchema)]
+pub struct WebhookResponse {
+    pub id: u64,
+    pub url: String,
+    pub events: Vec<String>,
+    pub signed: bool,
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(hook: Webhook) -> Self {
+        Self {
+            id: hook.id,
+            url: hook.url,
+            events: hook.events,
+            signed: hook.secret.is_some(),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    responses((status = 200, description = "Registered webhooks", body = Vec<WebhookResponse>))
+)]
+pub async fn list_webhooks(State(state): State<Arc<AppState>>) -> Json<Vec<WebhookResponse>> {
+    Json(state.webhooks.list().into_iter().map(Into::into).collect())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    request_body = WebhookRequest,
+    responses(
+        (status = 201, description = "Webhook registered", body = WebhookResponse),
+        (status = 400, description = "Invalid webhook", body = ErrorResponse)
+    )
+)]
+pub async fn create_webhook(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<WebhookRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    req.check_events()?;
+    let hook = state.webhooks.add(req.url, req.secret, req.events);
+
+    Ok((StatusCode::CREATED, Json(WebhookResponse::from(hook))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = u64, Path, description = "Webhook id")),
+    responses(
+        (status = 200, description = "Webhook", body = WebhookResponse),
+        (status = 404, description = "Unknown webhook", body = ErrorResponse)
+    )
+)]
+pub async fn get_webhook(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<u64>,
+) -> Result<Json<WebhookResponse>, ApiError> {
+    let hook = state
+        .webhooks
+        .get(id)
+        .ok_or_else(|| ApiError::NotFound(format!("Webhook not found: {id}")))?;
+
+    Ok(Json(hook.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = u64, Path, description = "Webhook id")),
+    request_body = WebhookRequest,
+    responses(
+        (status = 200, description = "Webhook updated", body = WebhookResponse),
+        (status = 400, description = "Invalid webhook", body = ErrorResponse),
+        (status = 404, description = "Unknown webhook", body = ErrorResponse)
+    )
+)]
+pub async fn update_webhook(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<u64>,
+    ValidatedJson(req): ValidatedJson<WebhookRequest>,
+) -> Result<Json<WebhookResponse>, ApiError> {
+    req.check_events()?;
+    let hook = state
+        .webhooks
+        .update(id, req.url, req.secret, req.events)
+        .ok_or_else(|| ApiError::NotFound(format!("Webhook not found: {id}")))?;
+
+    Ok(Json(hook.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = u64, Path, description = "Webhook id")),
+    responses(
+        (status = 200, description = "Webhook removed", body = Object),
+        (status = 404, description = "Unknown webhook", body = ErrorResponse)
+    )
+)]
+pub async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<u64>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !state.webhooks.remove(id) {
+        return Err(ApiError::NotFound(format!("Webhook not found: {id}")));
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "id": id
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/deliveries",
+    tag = "webhooks",
+    responses((status = 200, description = "Recent deliveries, newest first", body = Vec<Delivery>))
+)]
+pub async fn list_deliveries(State(state): State<Arc<AppState>>) -> Json<Vec<Delivery>> {
+    Json(state.webhooks.deliveries())
+}
@@ -0,0 +1,532 @@
+//! Inbound webhook adapters: `/hooks/github` and `/hooks/{name}`.
+//!
+//! Each hook in `[hooks.<name>]` maps payloads to panel actions through
+//! ordered rules. The first matching rule starts an effect, shows text or
+//! flashes a color, and may restore the previous effect after a while.
+//! Rule params are checked by [`validate_hooks`] at startup.
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sp_core::{HookRule, InboundHookConfig};
+use sp_effects::{EffectParams, EffectRegistry};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use crate::{render::TextCommand, state::AppState, validation::ApiError};
+
+/// Hook served at `/hooks/github`.
+const GITHUB: &str = "github";
+
+/// Effect used for color flashes.
+const FLASH_EFFECT: &str = "solid";
+
+/// Scroll speed of hook text, in pixels per second.
+const TEXT_SPEED: u32 = 50;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HookResponse {
+    pub hook: String,
+    /// Index of the applied rule, if any matched.
+    pub rule: Option<usize>,
+}
+
+/// Whether `path` is a hook authenticated by its signature instead of a token.
+pub(crate) fn is_signed(state: &AppState, path: &str) -> bool {
+    path.strip_prefix("/hooks/")
+        .and_then(|name| state.config.hooks.get(name))
+        .is_some_and(|hook| hook.secret.is_some())
+}
+
+/// Check `X-Hub-Signature-256: sha256=<hex>` against the body's HMAC.
+fn verify_signature(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), ApiError> {
+    let signature = headers
+        .get("x-hub-signature-256")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("sha256="))
+        .and_then(decode_hex)
+        .ok_or_else(|| ApiError::Unauthorized("Missing or malformed signature".to_string()))?;
+
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts any key length");
+    mac.update(body);
+    mac.verify_slice(&signature)
+        .map_err(|_| ApiError::Unauthorized("Invalid signature".to_string()))
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Whether `actual` contains everything in `expected`.
+fn matches(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|actual| matches(value, actual))),
+        (Value::Array(options), actual) if !actual.is_array() => {
+            options.iter().any(|option| matches(option, actual))
+        }
+        _ => expected == actual,
+    }
+}
+
+fn rule_matches(rule: &HookRule, event: Option<&str>, payload: &Value) -> bool {
+    let event_matches = rule
+        .event
+        .as_deref()
+        .map_or(true, |want| Some(want) == event);
+    event_matches && matches(&Value::Object(rule.when.clone()), payload)
+}
+
+/// Fill `{{dotted.path}}` placeholders from the payload; missing ones are left empty.
+fn render_template(template: &str, payload: &Value) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+
+        let path = rest[start + 2..start + end].trim();
+        let value = path.split('.').try_fold(payload, |value, key| match value {
+            Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+            _ => value.get(key),
+        });
+        match value {
+            Some(Value::String(text)) => out.push_str(text),
+            Some(Value::Null) | None => {}
+            Some(other) => out.push_str(&other.to_string()),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Effect started by a rule without text.
+fn rule_effect(rule: &HookRule) -> &str {
+    rule.effect.as_deref().unwrap_or(FLASH_EFFECT)
+}
+
+/// Params of a rule's effect, with its color if set.
+fn rule_params(rule: &HookRule) -> serde_json::Result<EffectParams> {
+    let mut params = rule.params.clone();
+    if let Some(color) = rule.color {
+        params.insert("color".to_string(), color.iter().copied().collect());
+    }
+    serde_json::from_value(Value::Object(params))
+}
+
+/// Check that every effect rule in `[hooks]` has valid params, and that they
+/// fit the schema of their effect when `registry` knows it. Effects added
+/// later (scripts, live inputs) are only checked when a rule is applied.
+pub fn validate_hooks(
+    hooks: &HashMap<String, InboundHookConfig>,
+    registry: &EffectRegistry,
+) -> sp_core::Result<()> {
+    for (name, hook) in hooks {
+        for (index, rule) in hook.rules.iter().enumerate() {
+            if rule.text.is_some() {
+                continue;
+            }
+            let field = format!("hooks.{name}.rules[{index}].params");
+            let params = rule_params(rule)
+                .map_err(|e| sp_core::Error::invalid_param(&field, e.to_string()))?;
+            if let Some(registration) = registry.get(rule_effect(rule)) {
+                registration
+                    .schema
+                    .validate(&params)
+                    .map_err(|e| sp_core::Error::invalid_param(&field, e.to_string()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Effect shown before a hook action, with params.
+type Previous = (Option<String>, EffectParams);
+
+/// The effect to put back when the last timed hook action expires.
+///
+/// A timed action replacing another one still on the panel inherits its
+/// effect to restore, so overlapping hooks always return to the effect shown
+/// before the first one.
+#[derive(Default)]
+pub struct HookRestore {
+    /// Revision of the action on the panel and the effect it replaced.
+    pending: Mutex<Option<(u64, Previous)>>,
+}
+
+impl HookRestore {
+    /// Take the pending restore, returning its effect if the action it
+    /// belongs to is still the one at `revision`.
+    fn take(&self, revision: u64) -> Option<Previous> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        if !matches!(&*pending, Some((applied, _)) if *applied == revision) {
+            return None;
+        }
+        pending.take().map(|(_, previous)| previous)
+    }
+
+    fn set(&self, applied: u64, previous: Previous) {
+        *self.pending.lock().unwrap_or_else(|e| e.into_inner()) = Some((applied, previous));
+    }
+}
+
+/// Apply a rule's action, scheduling the restore if it has a duration.
+async fn apply(state: &Arc<AppState>, rule: &HookRule, payload: &Value) -> Result<(), ApiError> {
+    let renderer = &state.renderer;
+    let (current, revision) = renderer
+        .call(|manager| {
+            let effect = manager.current_effect().map(String::from);
+            ((effect, manager.params().clone()), manager.revision())
+        })
+        .await?;
+    let previous = state.hook_restore.take(revision).unwrap_or(current);
+
+    if let Some(text) = &rule.text {
+        renderer
+            .show_text(TextCommand {
+                text: render_template(text, payload),
+                color: rule.color.unwrap_or([255, 255, 255]),
+                scroll: rule.scroll,
+                speed: TEXT_SPEED,
+            })
+            .await?;
+    } else {
+        let params = rule_params(rule)
+            .map_err(|e| ApiError::Internal(format!("Invalid hook params: {e}")))?;
+        renderer.set_effect(rule_effect(rule), params).await?;
+    }
+
+    if let Some(secs) = rule.duration_secs {
+        let applied = renderer.call(|manager| manager.revision()).await?;
+        state.hook_restore.set(applied, previous);
+        let state = Arc::clone(state);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(secs)).await;
+            restore(&state, applied).await;
+        });
+    }
+    Ok(())
+}
+
+/// Put back the effect from before the hook action at revision `applied`,
+/// unless something changed the panel meanwhile.
+async fn restore(state: &AppState, applied: u64) {
+    let renderer = &state.renderer;
+    let Ok(revision) = renderer.call(|manager| manager.revision()).await else {
+        return;
+    };
+    let Some(previous) = state.hook_restore.take(applied) else {
+        tracing::debug!("Another hook action replaced this one, not restoring");
+        return;
+    };
+    if revision != applied {
+        tracing::debug!("Panel changed since the hook fired, not restoring");
+        return;
+    }
+
+    let result = match previous {
+        (Some(effect), params) => renderer.set_effect(effect, params).await,
+        (None, _) => renderer.stop().await,
+    };
+    if let Err(e) = result {
+        tracing::warn!(error = %e, "Failed to restore effect after hook");
+    }
+}
+
+/// Verify, decode and dispatch a payload to the first matching rule.
+async fn handle(
+    state: Arc<AppState>,
+    name: String,
+    hook: &InboundHookConfig,
+    event: Option<&str>,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<Json<HookResponse>, ApiError> {
+    if let Some(secret) = &hook.secret {
+        verify_signature(secret, headers, body)?;
+    }
+    let payload: Value = serde_json::from_slice(body)
+        .map_err(|e| ApiError::BadRequest(format!("Expected a JSON payload: {e}")))?;
+
+    let rule = hook
+        .rules
+        .iter()
+        .position(|rule| rule_matches(rule, event, &payload));
+    if let Some(index) = rule {
+        tracing::info!(hook = %name, rule = index, event, "Inbound hook matched");
+        apply(&state, &hook.rules[index], &payload).await?;
+    }
+
+    Ok(Json(HookResponse { hook: name, rule }))
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn find_hook<'a>(state: &'a AppState, name: &str) -> Result<&'a InboundHookConfig, ApiError> {
+    state
+        .config
+        .hooks
+        .get(name)
+        .ok_or_else(|| ApiError::NotFound(format!("Hook not found: {name}")))
+}
+
+#[utoipa::path(
+    post,
+    path = "/hooks/github",
+    tag = "hooks",
+    request_body(content = Object, description = "GitHub webhook payload"),
+    params(
+        ("X-GitHub-Event" = String, Header, description = "GitHub event name"),
+        ("X-Hub-Signature-256" = Option<String>, Header, description = "`sha256=<hex>` HMAC of the body")
+    ),
+    responses(
+        (status = 200, description = "Payload handled", body = HookResponse),
+        (status = 401, description = "Bad signature", body = ErrorResponse),
+        (status = 404, description = "Hook not configured", body = ErrorResponse)
+    )
+)]
+pub async fn github_hook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<HookResponse>, ApiError> {
+    let hook = find_hook(&state, GITHUB)?.clone();
+    let event = header(&headers, "x-github-event");
+    handle(state, GITHUB.to_string(), &hook, event, &headers, &body).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/hooks/{name}",
+    tag = "hooks",
+    request_body(content = Object, description = "Any JSON payload"),
+    params(
+        ("name" = String, Path, description = "Hook name from `[hooks.<name>]`"),
+        ("X-Event" = Option<String>, Header, description = "Event name matched by rules"),
+        ("X-Hub-Signature-256" = Option<String>, Header, description = "`sha256=<hex>` HMAC of the body")
+    ),
+    responses(
+        (status = 200, description = "Payload handled", body = HookResponse),
+        (status = 401, description = "Bad signature", body = ErrorResponse),
+        (status = 404, description = "Hook not configured", body = ErrorResponse)
+    )
+)]
+pub async fn named_hook(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<HookResponse>, ApiError> {
+    let hook = find_hook(&state, &name)?.clone();
+    let event = header(&headers, "x-event");
+    handle(state, name, &hook, event, &headers, &body).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_router, test_util, webhooks::sign};
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use serde_json::json;
+    use sp_core::Config;
+    use sp_effects::EffectManager;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_matching_and_templates() {
+        let payload = json!({
+            "action": "completed",
+            "workflow_run": { "name": "CI", "conclusion": "failure", "run_number": 12 },
+            "labels": ["bug"],
+        });
+        let rule = |when: Value| HookRule {
+            event: Some("workflow_run".to_string()),
+            when: when.as_object().unwrap().clone(),
+            ..Default::default()
+        };
+
+        let failure = rule(json!({ "workflow_run": { "conclusion": ["failure", "timed_out"] } }));
+        assert!(rule_matches(&failure, Some("workflow_run"), &payload));
+        assert!(!rule_matches(&failure, Some("push"), &payload));
+        assert!(!rule_matches(&failure, None, &payload));
+
+        let success = rule(json!({ "workflow_run": { "conclusion": "success" } }));
+        assert!(!rule_matches(&success, Some("workflow_run"), &payload));
+        assert!(rule_matches(
+            &rule(json!({ "labels": ["bug"] })),
+            Some("workflow_run"),
+            &payload
+        ));
+
+        assert_eq!(
+            render_template(
+                "{{ workflow_run.name }} #{{workflow_run.run_number}} {{labels.0}}{{missing}}",
+                &payload
+            ),
+            "CI #12 bug"
+        );
+        assert_eq!(render_template("no {{ end", &payload), "no {{ end");
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let body = br#"{"zen":"Keep it logically awesome."}"#;
+        let mut headers = HeaderMap::new();
+        assert!(verify_signature("s3cret", &headers, body).is_err());
+
+        headers.insert("x-hub-signature-256", sign("s3cret", body).parse().unwrap());
+        assert!(verify_signature("s3cret", &headers, body).is_ok());
+        assert!(verify_signature("other", &headers, body).is_err());
+        assert!(verify_signature("s3cret", &headers, b"{}").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_github_hook_flashes_and_restores() {
+        let mut config = Config::default();
+        config.hooks.insert(
+            GITHUB.to_string(),
+            InboundHookConfig {
+                secret: Some("s3cret".to_string()),
+                rules: vec![HookRule {
+                    event: Some("pull_request".to_string()),
+                    when: json!({ "action": "opened" }).as_object().unwrap().clone(),
+                    color: Some([0, 0, 255]),
+                    duration_secs: Some(1),
+                    ..Default::default()
+                }],
+            },
+        );
+
+        let (state, _render) = test_util::start(config);
+        let router = create_router(Arc::clone(&state));
+
+        let send = |event: &str, body: &'static str, signature: String| {
+            let request = Request::post("/hooks/github")
+                .header("x-github-event", event)
+                .header("x-hub-signature-256", signature)
+                .body(Body::from(body))
+                .unwrap();
+            router.clone().oneshot(request)
+        };
+        let current = || {
+            state
+                .renderer
+                .call(|m| m.current_effect().map(String::from))
+        };
+
+        let body = r#"{"action": "opened"}"#;
+        let response = send("pull_request", body, sign("wrong", body.as_bytes()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = send("push", body, sign("s3cret", body.as_bytes()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(current().await.unwrap(), None);
+
+        let response = send("pull_request", body, sign("s3cret", body.as_bytes()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(current().await.unwrap().as_deref(), Some(FLASH_EFFECT));
+
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert_eq!(current().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_hooks_restore_first_effect() {
+        let mut config = Config::default();
+        config.hooks.insert(
+            "ci".to_string(),
+            InboundHookConfig {
+                secret: None,
+                rules: vec![HookRule {
+                    color: Some([255, 0, 0]),
+                    duration_secs: Some(1),
+                    ..Default::default()
+                }],
+            },
+        );
+
+        let (state, _render) = test_util::start(config);
+        let router = create_router(Arc::clone(&state));
+
+        state
+            .renderer
+            .set_effect("fire", EffectParams::default())
+            .await
+            .unwrap();
+        let fire = || {
+            let request = Request::post("/hooks/ci").body(Body::from("{}")).unwrap();
+            router.clone().oneshot(request)
+        };
+        let current = || {
+            state
+                .renderer
+                .call(|m| m.current_effect().map(String::from))
+        };
+
+        // The second flash lands while the first one is still shown
+        assert_eq!(fire().await.unwrap().status(), StatusCode::OK);
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(fire().await.unwrap().status(), StatusCode::OK);
+        tokio::time::sleep(Duration::from_millis(700)).await;
+        assert_eq!(current().await.unwrap().as_deref(), Some(FLASH_EFFECT));
+
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert_eq!(current().await.unwrap().as_deref(), Some("fire"));
+    }
+
+    #[test]
+    fn test_validate_hooks() {
+        let manager = EffectManager::new(64, 32);
+        let registry = manager.registry();
+        let hooks = |params: Value| {
+            let rule = HookRule {
+                effect: Some("fire".to_string()),
+                params: params.as_object().unwrap().clone(),
+                ..Default::default()
+            };
+            HashMap::from([(
+                GITHUB.to_string(),
+                InboundHookConfig {
+                    secret: None,
+                    rules: vec![rule],
+                },
+            )])
+        };
+
+        assert!(validate_hooks(&hooks(json!({ "intensity": 0.5 })), registry).is_ok());
+        // Not an EffectParams
+        let err = validate_hooks(&hooks(json!({ "intensity": "high" })), registry).unwrap_err();
+        assert!(err.to_string().contains("hooks.github.rules[0].params"));
+        // Outside the schema of fire
+        assert!(validate_hooks(&hooks(json!({ "intensity": 2.0 })), registry).is_err());
+    }
+}
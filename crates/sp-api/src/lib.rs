@@ -2,10 +2,42 @@
 //!
 //! Provides REST endpoints for controlling the LED panel.
 
+mod audio;
+mod auth;
+mod brightness;
+mod canvas;
+mod events;
 mod handlers;
+mod inbound;
+mod live;
+mod metrics;
+mod notify;
+mod openapi;
+mod ratelimit;
+mod render;
 mod routes;
+mod sse;
 mod state;
+mod stream;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 mod validation;
+mod webhooks;
 
-pub use routes::create_router;
+pub use audio::AudioStream;
+pub use brightness::{Brightness, BrightnessStatus, MAX_BRIGHTNESS};
+pub use canvas::{Canvas, CanvasOp, CanvasRequest};
+pub use events::{Event, EventBus, EventKind};
+pub use handlers::{BrightnessRequest, EffectRequest, NotifyRequest, TextRequest};
+pub use inbound::{validate_hooks, HookRestore};
+pub use live::LiveOverride;
+pub use metrics::Metrics;
+pub use notify::{Icon, Notification, NotificationsStatus, NotifyReceipt, NotifyStyle, Priority};
+pub use openapi::ApiDoc;
+pub use ratelimit::RateLimiter;
+pub use render::{ParamsUpdate, RenderCommand, RenderHandle, Renderer, TextCommand};
+pub use routes::{create_router, create_router_with};
+pub use sse::PanelSnapshot;
 pub use state::AppState;
+pub use stream::{FrameFormat, FrameRequest, FrameStream};
+pub use webhooks::{Delivery, Webhook, Webhooks};
@@ -0,0 +1,197 @@
+//! Temporary takeover of the panel by a live pixel stream.
+//!
+//! Network inputs (DMX, DDP, pushed frames) switch to their live effect when
+//! data starts and hand the panel back once it stops. An effect started by
+//! anyone else meanwhile wins: the stream then waits for its own end before
+//! it may take over again.
+
+use sp_core::Result;
+use sp_effects::{EffectParams, LiveInput, LiveInputEffect};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::{events::EventKind, state::AppState};
+
+/// What the stream is doing with the panel.
+enum Mode {
+    /// No data is flowing.
+    Idle,
+    /// Showing the live effect; `fallback` applies when the stream stops.
+    Live { fallback: Fallback },
+    /// Another effect was started while live; wait for the stream to stop.
+    Yielded,
+}
+
+/// What to show when the stream stops.
+enum Fallback {
+    Effect(String, EffectParams),
+    Stop,
+    /// The live effect was already selected; leave it.
+    Keep,
+}
+
+/// Switches the panel to a live effect while a stream runs, and back.
+pub struct LiveOverride {
+    state: Arc<AppState>,
+    effect: String,
+    mode: Mode,
+}
+
+impl LiveOverride {
+    pub fn new(state: Arc<AppState>, effect: impl Into<String>) -> Self {
+        Self {
+            state,
+            effect: effect.into(),
+            mode: Mode::Idle,
+        }
+    }
+
+    /// Register the live effect, showing `input`.
+    ///
+    /// Receivers use names from [`sp_effects::RESERVED_NAMES`], which scripts
+    /// and plugins can't take, so this only replaces an earlier registration.
+    pub async fn register(&self, description: &str, input: LiveInput) -> Result<()> {
+        let registration = LiveInputEffect::registration(&self.effect, description, input);
+        self.state
+            .renderer
+            .call(move |manager| manager.registry_mut().register_or_replace(registration))
+            .await
+    }
+
+    /// Whether the live effect is showing because of the stream.
+    pub fn is_live(&self) -> bool {
+        matches!(self.mode, Mode::Live { .. })
+    }
+
+    /// Data arrived: switch to the live effect unless already done or yielded.
+    pub async fn activate(&mut self) {
+        if !matches!(self.mode, Mode::Idle) {
+            return;
+        }
+        let renderer = &self.state.renderer;
+        let current = renderer
+            .call(|manager| {
+                let name = manager.current_effect()?.to_string();
+                Some((name, manager.params().clone()))
+            })
+            .await
+            .ok()
+            .flatten();
+        let fallback = match current {
+            Some((name, _)) if name == self.effect => Fallback::Keep,
+            Some((name, params)) => Fallback::Effect(name, params),
+            None => Fallback::Stop,
+        };
+
+        if !matches!(fallback, Fallback::Keep) {
+            let started = renderer
+                .set_effect(&self.effect, EffectParams::default())
+                .await;
+            if let Err(e) = started {
+                warn!(effect = %self.effect, error = %e, "Failed to start live effect");
+                self.mode = Mode::Yielded;
+                return;
+            }
+        }
+        info!(effect = %self.effect, "Live input started");
+        self.mode = Mode::Live { fallback };
+    }
+
+    /// Yield to effects started by anyone else while live.
+    pub fn observe(&mut self, kind: &EventKind) {
+        if let EventKind::EffectChanged { effect, .. } = kind {
+            if *effect != self.effect && self.is_live() {
+                info!(
+                    %effect,
+                    live = %self.effect,
+                    "Effect changed during live input, yielding until it stops"
+                );
+                self.mode = Mode::Yielded;
+            }
+        }
+    }
+
+    /// The stream stopped: restore what was showing before it started.
+    ///
+    /// Returns whether the live effect was showing.
+    pub async fn release(&mut self) -> bool {
+        let mode = std::mem::replace(&mut self.mode, Mode::Idle);
+        let Mode::Live { fallback } = mode else {
+            return false;
+        };
+        info!(effect = %self.effect, "Live input stopped");
+
+        let renderer = &self.state.renderer;
+        let result = match fallback {
+            Fallback::Effect(name, params) => renderer.set_effect(name, params).await,
+            Fallback::Stop => renderer.stop().await,
+            Fallback::Keep => Ok(()),
+        };
+        if let Err(e) = result {
+            warn!(effect = %self.effect, error = %e, "Failed to restore effect after live input");
+        }
+        true
+    }
+
+    /// The stream stopped but its effect stays up, holding its pixels.
+    ///
+    /// Returns whether the live effect was showing.
+    pub fn settle(&mut self) -> bool {
+        let mode = std::mem::replace(&mut self.mode, Mode::Idle);
+        if !matches!(mode, Mode::Live { .. }) {
+            return false;
+        }
+        info!(effect = %self.effect, "Live input stopped, keeping its effect");
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+    use sp_core::Config;
+
+    #[tokio::test]
+    async fn test_takeover_yield_and_restore() {
+        let config = Config::default();
+        let (state, _render) = test_util::start(config);
+        let current = || {
+            state
+                .renderer
+                .call(|manager| manager.current_effect().map(String::from))
+        };
+
+        let mut live = LiveOverride::new(Arc::clone(&state), "live");
+        live.register("Test input", LiveInput::new(64, 32))
+            .await
+            .unwrap();
+        state
+            .renderer
+            .set_effect("fire", EffectParams::default())
+            .await
+            .unwrap();
+
+        live.activate().await;
+        assert!(live.is_live());
+        assert_eq!(current().await.unwrap().as_deref(), Some("live"));
+        assert!(live.release().await);
+        assert_eq!(current().await.unwrap().as_deref(), Some("fire"));
+
+        // Someone else's effect wins until the stream stops
+        let mut events = state.events.subscribe();
+        live.activate().await;
+        state
+            .renderer
+            .set_effect("solid", EffectParams::default())
+            .await
+            .unwrap();
+        while let Ok(event) = events.try_recv() {
+            live.observe(&event.kind);
+        }
+        assert!(!live.is_live());
+        live.activate().await;
+        assert!(!live.release().await);
+        assert_eq!(current().await.unwrap().as_deref(), Some("solid"));
+    }
+}
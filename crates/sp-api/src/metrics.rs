@@ -0,0 +1,234 @@
+//! Prometheus metrics for the render loop and HTTP API.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use prometheus::{
+    exponential_buckets, Encoder, Gauge, Histogram, HistogramOpts, HistogramVec, IntCounter,
+    IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{state::AppState, validation::ApiError};
+
+/// Window over which the achieved FPS is averaged.
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Telemetry collected by the render loop and the router.
+pub struct Metrics {
+    registry: Registry,
+    frames_rendered: IntCounter,
+    frames_late: IntCounter,
+    driver_errors: IntCounter,
+    fps: Gauge,
+    tick_duration: Histogram,
+    display_duration: Histogram,
+    current_effect: IntGaugeVec,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+    fps_window: Mutex<FpsWindow>,
+    effect_label: Mutex<Option<String>>,
+}
+
+struct FpsWindow {
+    start: Instant,
+    frames: u32,
+}
+
+impl Metrics {
+    /// Create and register all metrics.
+    pub fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("super_pixeled".to_string()), None)?;
+
+        // 0.1 ms .. ~105 ms
+        let frame_buckets = exponential_buckets(0.0001, 2.0, 11)?;
+
+        let frames_rendered = IntCounter::new("frames_rendered_total", "Frames rendered")?;
+        let frames_late =
+            IntCounter::new("frames_late_total", "Frames that missed their deadline")?;
+        let driver_errors = IntCounter::new("driver_errors_total", "Driver display errors")?;
+        let fps = Gauge::new("fps", "Achieved frames per second")?;
+        let tick_duration = Histogram::with_opts(
+            HistogramOpts::new("tick_duration_seconds", "Effect tick duration")
+                .buckets(frame_buckets.clone()),
+        )?;
+        let display_duration = Histogram::with_opts(
+            HistogramOpts::new("display_duration_seconds", "Driver display duration")
+                .buckets(frame_buckets),
+        )?;
+        let current_effect = IntGaugeVec::new(
+            Opts::new("current_effect", "Currently running effect (1 = active)"),
+            &["effect"],
+        )?;
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled"),
+            &["method", "path", "status"],
+        )?;
+        let http_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency"),
+            &["method", "path"],
+        )?;
+
+        registry.register(Box::new(frames_rendered.clone()))?;
+        registry.register(Box::new(frames_late.clone()))?;
+        registry.register(Box::new(driver_errors.clone()))?;
+        registry.register(Box::new(fps.clone()))?;
+        registry.register(Box::new(tick_duration.clone()))?;
+        registry.register(Box::new(display_duration.clone()))?;
+        registry.register(Box::new(current_effect.clone()))?;
+        registry.register(Box::new(http_requests.clone()))?;
+        registry.register(Box::new(http_duration.clone()))?;
+
+        Ok(Self {
+            registry,
+            frames_rendered,
+            frames_late,
+            driver_errors,
+            fps,
+            tick_duration,
+            display_duration,
+            current_effect,
+            http_requests,
+            http_duration,
+            fps_window: Mutex::new(FpsWindow {
+                start: Instant::now(),
+                frames: 0,
+            }),
+            effect_label: Mutex::new(None),
+        })
+    }
+
+    /// Record one rendered frame and update the FPS gauge.
+    pub fn record_frame(&self, tick: Duration, display: Duration) {
+        self.frames_rendered.inc();
+        self.tick_duration.observe(tick.as_secs_f64());
+        self.display_duration.observe(display.as_secs_f64());
+
+        let mut window = self.fps_window.lock().unwrap();
+        window.frames += 1;
+        let elapsed = window.start.elapsed();
+        if elapsed >= FPS_WINDOW {
+            self.fps
+                .set(f64::from(window.frames) / elapsed.as_secs_f64());
+            window.start = Instant::now();
+            window.frames = 0;
+        }
+    }
+
+    /// Record frames that missed their deadline.
+    pub fn record_late_frames(&self, count: u64) {
+        self.frames_late.inc_by(count);
+    }
+
+    /// Record a driver error.
+    pub fn record_driver_error(&self) {
+        self.driver_errors.inc();
+    }
+
+    /// Set the current effect label (`None` when stopped).
+    pub fn set_current_effect(&self, effect: Option<&str>) {
+        let mut label = self.effect_label.lock().unwrap();
+        if label.as_deref() == effect {
+            return;
+        }
+
+        self.current_effect.reset();
+        if let Some(effect) = effect {
+            self.current_effect.with_label_values(&[effect]).set(1);
+        }
+        *label = effect.map(String::from);
+    }
+
+    /// Record a completed HTTP request.
+    pub fn record_request(&self, method: &str, path: &str, status: u16, latency: Duration) {
+        self.http_requests
+            .with_label_values(&[method, path, &status.to_string()])
+            .inc();
+        self.http_duration
+            .with_label_values(&[method, path])
+            .observe(latency.as_secs_f64());
+    }
+
+    /// Encode all metrics in the Prometheus text format.
+    pub fn encode(&self) -> prometheus::Result<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+    }
+}
+
+/// Middleware recording request counts and latencies.
+///
+/// Requests are labelled by route template (`/api/scripts/:name`) rather
+/// than the raw path to keep label cardinality bounded.
+pub async fn track_requests(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", MatchedPath::as_str)
+        .to_string();
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+
+    state
+        .metrics
+        .record_request(&method, &path, response.status().as_u16(), start.elapsed());
+    response
+}
+
+/// `GET /metrics` in Prometheus text format.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "system",
+    responses((status = 200, description = "Prometheus text exposition", body = String, content_type = "text/plain"))
+)]
+pub async fn metrics(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, ApiError> {
+    let body = state
+        .metrics
+        .encode()
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            TextEncoder::new().format_type().to_string(),
+        )],
+        body,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let metrics = Metrics::new().unwrap();
+        metrics.record_frame(Duration::from_millis(2), Duration::from_millis(1));
+        metrics.record_driver_error();
+        metrics.set_current_effect(Some("fire"));
+        metrics.set_current_effect(Some("solid"));
+        metrics.record_request("GET", "/health", 200, Duration::from_millis(3));
+
+        let text = metrics.encode().unwrap();
+        assert!(text.contains("super_pixeled_frames_rendered_total 1"));
+        assert!(text.contains("super_pixeled_driver_errors_total 1"));
+        assert!(text.contains("super_pixeled_current_effect{effect=\"solid\"} 1"));
+        assert!(!text.contains("effect=\"fire\""));
+        assert!(text.contains("super_pixeled_tick_duration_seconds_count 1"));
+        assert!(text.contains(
+            "super_pixeled_http_requests_total{method=\"GET\",path=\"/health\",status=\"200\"} 1"
+        ));
+    }
+}
@@ -0,0 +1,413 @@
+//! Notification queue drawn by the render thread over the running effect.
+//!
+//! One notification is visible at a time; others wait ordered by priority,
+//! then arrival. A `critical` notification preempts a lower one, which goes
+//! back to the queue with its remaining time. Full-screen notifications
+//! pause the effect (see [`EffectManager::pause`](sp_effects::EffectManager::pause))
+//! so it resumes with its state intact.
+
+use serde::{Deserialize, Serialize};
+use sp_core::{Color, Error, Point, Result};
+use sp_renderer::{font, Framebuffer};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+/// Notifications allowed to wait at once.
+pub const MAX_PENDING: usize = 32;
+
+/// Scroll speed of text too wide for the panel, in pixels per second.
+const SCROLL_SPEED: f32 = 30.0;
+
+/// Icon size in pixels.
+const ICON_SIZE: i32 = 7;
+
+/// Gap between icon and text.
+const ICON_GAP: i32 = 2;
+
+/// Height of the overlay band at the bottom of the panel.
+const BAND_HEIGHT: u32 = font::GLYPH_HEIGHT + 2;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    /// Preempts any lower notification on screen.
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyStyle {
+    /// A band over the bottom of the running effect.
+    #[default]
+    Overlay,
+    /// The whole panel; the effect is paused meanwhile.
+    Fullscreen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Icon {
+    Info,
+    Check,
+    Warning,
+    Error,
+    Heart,
+    Bell,
+}
+
+impl Icon {
+    /// 7x7 bitmap, one byte per row, bit 6 = leftmost column.
+    fn rows(self) -> [u8; 7] {
+        match self {
+            Self::Info => [0x08, 0x00, 0x18, 0x08, 0x08, 0x08, 0x1C],
+            Self::Check => [0x00, 0x01, 0x02, 0x44, 0x28, 0x10, 0x00],
+            Self::Warning => [0x08, 0x1C, 0x14, 0x36, 0x22, 0x7F, 0x00],
+            Self::Error => [0x41, 0x22, 0x14, 0x08, 0x14, 0x22, 0x41],
+            Self::Heart => [0x36, 0x7F, 0x7F, 0x7F, 0x3E, 0x1C, 0x08],
+            Self::Bell => [0x08, 0x1C, 0x3E, 0x3E, 0x3E, 0x7F, 0x08],
+        }
+    }
+
+    fn draw(self, fb: &mut Framebuffer, x: i32, y: i32, color: Color) {
+        for (dy, row) in self.rows().iter().enumerate() {
+            for dx in 0..ICON_SIZE {
+                if row & (0x40 >> dx) != 0 {
+                    fb.set(Point::new(x + dx, y + dy as i32), color);
+                }
+            }
+        }
+    }
+}
+
+/// A message or icon shown for a while.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Notification {
+    /// Assigned when queued.
+    pub id: u64,
+    pub text: Option<String>,
+    pub icon: Option<Icon>,
+    #[schema(value_type = Vec<u8>)]
+    pub color: [u8; 3],
+    pub style: NotifyStyle,
+    pub priority: Priority,
+    pub duration_ms: u64,
+}
+
+/// Where a new notification went.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NotifyReceipt {
+    pub id: u64,
+    /// 0 when shown immediately, otherwise the place in the queue.
+    pub position: usize,
+}
+
+/// The notification on screen.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ActiveNotification {
+    pub notification: Notification,
+    pub remaining_ms: u64,
+}
+
+/// Visible and waiting notifications.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NotificationsStatus {
+    pub active: Option<ActiveNotification>,
+    /// In display order.
+    pub pending: Vec<Notification>,
+}
+
+#[derive(Debug)]
+struct Active {
+    notification: Notification,
+    started: Instant,
+    until: Instant,
+}
+
+/// Queue of notifications, owned by the render thread.
+#[derive(Debug)]
+pub struct NotificationQueue {
+    next_id: u64,
+    active: Option<Active>,
+    pending: VecDeque<Notification>,
+}
+
+impl NotificationQueue {
+    pub fn new() -> Self {
+        Self {
+            next_id: 1,
+            active: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Show `notification` now or queue it; its `id` is assigned here.
+    pub fn push(&mut self, mut notification: Notification, now: Instant) -> Result<NotifyReceipt> {
+        if self.pending.len() >= MAX_PENDING {
+            return Err(Error::invalid_param("notification", "Queue is full"));
+        }
+        notification.id = self.next_id;
+        self.next_id += 1;
+        let id = notification.id;
+
+        let preempts = self.active.as_ref().is_some_and(|active| {
+            notification.priority == Priority::Critical
+                && active.notification.priority < Priority::Critical
+        });
+        if preempts {
+            if let Some(active) = self.active.take() {
+                // Ahead of its priority peers: it was already on screen
+                let interrupted = active.interrupt(now);
+                let index = self.insertion_index(|n| n.priority <= interrupted.priority);
+                self.pending.insert(index, interrupted);
+            }
+        }
+
+        if self.active.is_none() {
+            self.start(notification, now);
+            return Ok(NotifyReceipt { id, position: 0 });
+        }
+        let index = self.insertion_index(|n| n.priority < notification.priority);
+        self.pending.insert(index, notification);
+        Ok(NotifyReceipt {
+            id,
+            position: index + 1,
+        })
+    }
+
+    /// First waiting slot matching `before`, or the end of the queue.
+    fn insertion_index(&self, before: impl Fn(&Notification) -> bool) -> usize {
+        self.pending
+            .iter()
+            .position(before)
+            .unwrap_or(self.pending.len())
+    }
+
+    fn start(&mut self, notification: Notification, now: Instant) {
+        let until = now + Duration::from_millis(notification.duration_ms);
+        self.active = Some(Active {
+            notification,
+            started: now,
+            until,
+        });
+    }
+
+    /// End an expired notification and start the next; true if the panel changed.
+    pub fn advance(&mut self, now: Instant) -> bool {
+        if self
+            .active
+            .as_ref()
+            .map_or(true, |active| now < active.until)
+        {
+            return false;
+        }
+        self.active = None;
+        if let Some(next) = self.pending.pop_front() {
+            self.start(next, now);
+        }
+        true
+    }
+
+    /// The notification on screen.
+    pub fn active(&self) -> Option<&Notification> {
+        self.active.as_ref().map(|active| &active.notification)
+    }
+
+    /// Whether a full-screen notification is on screen.
+    pub fn is_fullscreen(&self) -> bool {
+        self.active()
+            .is_some_and(|n| n.style == NotifyStyle::Fullscreen)
+    }
+
+    /// Drop waiting notifications, returning how many there were.
+    pub fn clear(&mut self) -> usize {
+        let cleared = self.pending.len();
+        self.pending.clear();
+        cleared
+    }
+
+    pub fn status(&self, now: Instant) -> NotificationsStatus {
+        NotificationsStatus {
+            active: self.active.as_ref().map(|active| ActiveNotification {
+                notification: active.notification.clone(),
+                remaining_ms: active.until.saturating_duration_since(now).as_millis() as u64,
+            }),
+            pending: self.pending.iter().cloned().collect(),
+        }
+    }
+
+    /// Draw the notification on screen, if any, over `fb`.
+    pub fn draw(&self, fb: &mut Framebuffer, now: Instant) {
+        let Some(active) = &self.active else {
+            return;
+        };
+        let notification = &active.notification;
+        let elapsed = now.saturating_duration_since(active.started);
+
+        let (top, height) = match notification.style {
+            NotifyStyle::Fullscreen => {
+                fb.clear();
+                (0, fb.height())
+            }
+            NotifyStyle::Overlay => {
+                let height = BAND_HEIGHT.min(fb.height());
+                let top = (fb.height() - height) as i32;
+                fb.fill_rect(0, top, fb.width(), height, Color::BLACK);
+                (top, height)
+            }
+        };
+        draw_content(fb, notification, top, height, elapsed);
+    }
+}
+
+impl Default for NotificationQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Active {
+    /// The notification with the time it had left.
+    fn interrupt(self, now: Instant) -> Notification {
+        let mut notification = self.notification;
+        let remaining = self.until.saturating_duration_since(now);
+        notification.duration_ms = (remaining.as_millis() as u64).max(1);
+        notification
+    }
+}
+
+/// Icon and text centered in a band of the panel, scrolling when too wide.
+fn draw_content(
+    fb: &mut Framebuffer,
+    notification: &Notification,
+    top: i32,
+    height: u32,
+    elapsed: Duration,
+) {
+    let color = Color::from(notification.color);
+    let text = notification.text.as_deref().unwrap_or_default();
+    let text_width = font::text_width(text) as i32;
+    let icon_width = match (notification.icon, text.is_empty()) {
+        (None, _) => 0,
+        (Some(_), true) => ICON_SIZE,
+        (Some(_), false) => ICON_SIZE + ICON_GAP,
+    };
+
+    let width = fb.width() as i32;
+    let total = icon_width + text_width;
+    let x = if total <= width {
+        (width - total) / 2
+    } else {
+        let span = (width + total) as f32;
+        let offset = (elapsed.as_secs_f32() * SCROLL_SPEED) % span;
+        width - offset as i32
+    };
+    let y = top + (height as i32 - ICON_SIZE) / 2;
+
+    if let Some(icon) = notification.icon {
+        icon.draw(fb, x, y, color);
+    }
+    fb.draw_text(x + icon_width, y, text, color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(priority: Priority, duration_ms: u64) -> Notification {
+        Notification {
+            id: 0,
+            text: Some("Hi".to_string()),
+            icon: None,
+            color: [255, 255, 255],
+            style: NotifyStyle::Overlay,
+            priority,
+            duration_ms,
+        }
+    }
+
+    #[test]
+    fn test_queue_order_and_expiry() {
+        let now = Instant::now();
+        let mut queue = NotificationQueue::new();
+
+        let first = queue.push(notification(Priority::Low, 1000), now).unwrap();
+        assert_eq!(first.position, 0);
+        let low = queue.push(notification(Priority::Low, 1000), now).unwrap();
+        let high = queue.push(notification(Priority::High, 1000), now).unwrap();
+        assert_eq!((low.position, high.position), (1, 1));
+
+        let status = queue.status(now);
+        assert_eq!(status.active.unwrap().notification.id, first.id);
+        let order: Vec<_> = status.pending.iter().map(|n| n.id).collect();
+        assert_eq!(order, [high.id, low.id]);
+
+        assert!(!queue.advance(now + Duration::from_millis(999)));
+        assert!(queue.advance(now + Duration::from_millis(1000)));
+        assert_eq!(queue.active().unwrap().id, high.id);
+
+        assert_eq!(queue.clear(), 1);
+        assert!(queue.advance(now + Duration::from_millis(2000)));
+        assert!(queue.active().is_none());
+        assert!(!queue.advance(now + Duration::from_millis(3000)));
+    }
+
+    #[test]
+    fn test_critical_preempts() {
+        let now = Instant::now();
+        let mut queue = NotificationQueue::new();
+        let normal = queue
+            .push(notification(Priority::Normal, 1000), now)
+            .unwrap();
+        queue.push(notification(Priority::High, 1000), now).unwrap();
+
+        let later = now + Duration::from_millis(400);
+        let critical = queue
+            .push(notification(Priority::Critical, 500), later)
+            .unwrap();
+        assert_eq!(critical.position, 0);
+
+        // The interrupted one waits with its remaining time, behind higher priorities
+        let status = queue.status(later);
+        assert_eq!(status.active.unwrap().notification.id, critical.id);
+        assert_eq!(status.pending[1].id, normal.id);
+        assert_eq!(status.pending[1].duration_ms, 600);
+
+        // Critical notifications don't preempt each other
+        let second = queue
+            .push(notification(Priority::Critical, 500), later)
+            .unwrap();
+        assert_eq!(second.position, 1);
+    }
+
+    #[test]
+    fn test_draw() {
+        let now = Instant::now();
+        let mut queue = NotificationQueue::new();
+        let mut fb = Framebuffer::new(64, 32);
+        fb.fill(Color::RED);
+
+        queue
+            .push(notification(Priority::Normal, 1000), now)
+            .unwrap();
+        queue.draw(&mut fb, now);
+        // The effect shows above the band, text within it
+        assert_eq!(fb.get(Point::new(0, 0)), Some(Color::RED));
+        assert_eq!(fb.get(Point::new(0, 31)), Some(Color::BLACK));
+        assert!(fb.data().contains(&Color::WHITE));
+
+        let mut fullscreen = notification(Priority::Critical, 1000);
+        fullscreen.style = NotifyStyle::Fullscreen;
+        fullscreen.icon = Some(Icon::Heart);
+        queue.push(fullscreen, now).unwrap();
+        assert!(queue.is_fullscreen());
+        queue.draw(&mut fb, now);
+        assert!(!fb.data().contains(&Color::RED));
+    }
+}
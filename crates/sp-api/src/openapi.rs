@@ -0,0 +1,227 @@
+//! OpenAPI document and docs UI.
+
+use axum::{Json, Router};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::{Config, SwaggerUi};
+
+use crate::{
+    brightness::BrightnessStatus,
+    canvas::{self, CanvasOp, CanvasRequest},
+    handlers::{
+        self, BrightnessRequest, EffectRequest, EffectResponse, HealthResponse, NotifyRequest,
+        PanelInfo, ParamsResponse, ScriptRequest, TextRequest, WebhookRequest, WebhookResponse,
+    },
+    inbound::{self, HookResponse},
+    metrics,
+    notify::{
+        ActiveNotification, Icon, Notification, NotificationsStatus, NotifyReceipt, NotifyStyle,
+        Priority,
+    },
+    sse::{self, PanelSnapshot},
+    stream::{self, FrameFormat, FrameRequest},
+    validation::{ErrorResponse, FieldError},
+    webhooks::Delivery,
+};
+
+/// OpenAPI 3 description of the REST API.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Super Pixeled API", description = "REST API for controlling the LED panel"),
+    paths(
+        handlers::health,
+        metrics::metrics,
+        sse::events_stream,
+        handlers::set_effect,
+        handlers::get_current_effect,
+        handlers::patch_effect,
+        handlers::stop_effect,
+        handlers::list_effects,
+        handlers::get_effect_schema,
+        handlers::list_scripts,
+        handlers::upload_script,
+        handlers::get_script,
+        handlers::delete_script,
+        handlers::display_text,
+        handlers::get_brightness,
+        handlers::set_brightness,
+        handlers::notify,
+        handlers::get_notify_queue,
+        handlers::clear_notify_queue,
+        canvas::draw,
+        canvas::get_canvas,
+        stream::put_frame,
+        handlers::list_webhooks,
+        handlers::create_webhook,
+        handlers::get_webhook,
+        handlers::update_webhook,
+        handlers::delete_webhook,
+        handlers::list_deliveries,
+        inbound::github_hook,
+        inbound::named_hook,
+    ),
+    components(schemas(
+        HealthResponse,
+        PanelInfo,
+        EffectRequest,
+        EffectResponse,
+        ParamsResponse,
+        ScriptRequest,
+        TextRequest,
+        BrightnessRequest,
+        BrightnessStatus,
+        NotifyRequest,
+        NotifyReceipt,
+        Notification,
+        ActiveNotification,
+        NotificationsStatus,
+        Priority,
+        NotifyStyle,
+        Icon,
+        CanvasRequest,
+        CanvasOp,
+        FrameRequest,
+        FrameFormat,
+        ErrorResponse,
+        FieldError,
+        WebhookRequest,
+        WebhookResponse,
+        Delivery,
+        PanelSnapshot,
+        HookResponse,
+    )),
+    tags(
+        (name = "system", description = "Health, metrics and event stream"),
+        (name = "effects", description = "Effect selection and parameters"),
+        (name = "scripts", description = "Rhai script effects"),
+        (name = "display", description = "Text and brightness"),
+        (name = "notifications", description = "Prioritized messages shown over the current effect"),
+        (name = "canvas", description = "Persistent canvas drawn by operations"),
+        (name = "frames", description = "Frames pushed by external renderers"),
+        (name = "webhooks", description = "Outgoing event webhooks"),
+        (name = "hooks", description = "Inbound webhooks from GitHub, CI and other services"),
+    )
+)]
+pub struct ApiDoc;
+
+/// `GET /api/openapi.json`
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// `GET /api/docs` - Swagger UI loading the spec above.
+///
+/// The UI assets are embedded in the binary, so the docs work offline.
+pub fn swagger_ui<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    SwaggerUi::new("/api/docs")
+        .config(Config::from("/api/openapi.json"))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::routes;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use std::collections::BTreeSet;
+    use tower::ServiceExt;
+
+    /// Routes that are not part of the REST spec.
+    ///
+    /// `/mcp` and `/json/info` are merged in by sp-mcp and sp-wled and follow
+    /// the MCP and WLED protocols instead; `/api/docs` is the Swagger UI.
+    const UNDOCUMENTED: [(&str, &str); 5] = [
+        ("GET", "/ws"),
+        ("GET", "/ws/effect"),
+        ("GET", "/ws/frames"),
+        ("GET", "/ws/audio"),
+        ("GET", "/api/openapi.json"),
+    ];
+
+    fn spec_routes() -> BTreeSet<(String, String)> {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let mut routes = BTreeSet::new();
+
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for method in item.as_object().unwrap().keys() {
+                routes.insert((method.to_uppercase(), path.clone()));
+            }
+        }
+        routes
+    }
+
+    /// Routes served by `create_router`, with paths in OpenAPI syntax.
+    fn served_routes() -> BTreeSet<(String, String)> {
+        routes()
+            .iter()
+            .map(|route| {
+                let path: Vec<String> = route
+                    .path
+                    .split('/')
+                    .map(|segment| match segment.strip_prefix(':') {
+                        Some(param) => format!("{{{param}}}"),
+                        None => segment.to_string(),
+                    })
+                    .collect();
+                (route.method.to_string(), path.join("/"))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_spec_matches_routes() {
+        let spec = spec_routes();
+        let mut served = served_routes();
+        assert!(!spec.is_empty());
+
+        for (method, path) in UNDOCUMENTED {
+            let route = (method.to_string(), path.to_string());
+            assert!(served.remove(&route), "{method} {path} is gone");
+        }
+        let undocumented: Vec<_> = served.difference(&spec).collect();
+        assert!(
+            undocumented.is_empty(),
+            "Routes missing from the spec: {undocumented:?}"
+        );
+        let stale: Vec<_> = spec.difference(&served).collect();
+        assert!(stale.is_empty(), "OpenAPI paths without a route: {stale:?}");
+    }
+
+    #[tokio::test]
+    async fn test_docs_are_embedded() {
+        let docs: axum::Router = swagger_ui();
+        let get = |path: &str| {
+            let request = Request::get(path).body(Body::empty()).unwrap();
+            docs.clone().oneshot(request)
+        };
+
+        let response = get("/api/docs/swagger-ui.css").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get("/api/docs/swagger-initializer.js").await.unwrap();
+        let script = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let script = String::from_utf8_lossy(&script);
+        assert!(script.contains("/api/openapi.json"));
+        assert!(!script.contains("unpkg"));
+    }
+
+    #[test]
+    fn test_schemas() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let schemas = &spec["components"]["schemas"];
+
+        for name in [
+            "EffectRequest",
+            "TextRequest",
+            "BrightnessRequest",
+            "HealthResponse",
+            "ErrorResponse",
+        ] {
+            assert!(schemas[name].is_object(), "missing schema {name}");
+        }
+        assert_eq!(spec["openapi"], "3.0.3");
+    }
+}
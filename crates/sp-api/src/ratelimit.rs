@@ -0,0 +1,193 @@
+//! Token-bucket rate limiting for state-changing requests.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use sp_core::RateLimitConfig;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{auth::TokenName, state::AppState, validation::ApiError};
+
+/// Buckets kept at most; idle, refilled ones are dropped first, then the
+/// least recently used.
+const MAX_CLIENTS: usize = 1024;
+
+/// Who a request is charged to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Client {
+    Token(String),
+    Ip(IpAddr),
+    Unknown,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Per-client token buckets.
+#[derive(Debug)]
+pub struct RateLimiter {
+    per_second: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<Client, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Create a limiter from `[server.rate_limit]`.
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            per_second: f64::from(config.per_second),
+            burst: f64::from(config.burst),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        self.per_second > 0.0
+    }
+
+    /// Take one token for `client`, or return how long until one is available.
+    fn acquire(&self, client: Client, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() >= MAX_CLIENTS && !buckets.contains_key(&client) {
+            let (rate, burst) = (self.per_second, self.burst);
+            buckets.retain(|_, b| {
+                let idle = now.saturating_duration_since(b.updated).as_secs_f64();
+                b.tokens + idle * rate < burst
+            });
+
+            // Every client is still draining: make room anyway
+            if buckets.len() >= MAX_CLIENTS {
+                let oldest = buckets
+                    .iter()
+                    .min_by_key(|(_, b)| b.updated)
+                    .map(|(client, _)| client.clone());
+                if let Some(oldest) = oldest {
+                    buckets.remove(&oldest);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(client).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.per_second).min(self.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.per_second,
+            ))
+        }
+    }
+}
+
+/// Middleware limiting non-read requests per token, or per client IP when
+/// unauthenticated.
+pub async fn limit_requests(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let read_only = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    if read_only || !state.rate_limiter.enabled() {
+        return Ok(next.run(request).await);
+    }
+
+    let extensions = request.extensions();
+    let client = if let Some(TokenName(name)) = extensions.get::<TokenName>() {
+        Client::Token(name.clone())
+    } else if let Some(ConnectInfo(addr)) = extensions.get::<ConnectInfo<SocketAddr>>() {
+        Client::Ip(addr.ip())
+    } else {
+        Client::Unknown
+    };
+
+    if let Err(retry_after) = state.rate_limiter.acquire(client.clone(), Instant::now()) {
+        tracing::debug!(?client, ?retry_after, "Rate limited");
+        return Err(ApiError::RateLimited(retry_after));
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(per_second: u32, burst: u32) -> RateLimiter {
+        RateLimiter::new(&RateLimitConfig { per_second, burst })
+    }
+
+    #[test]
+    fn test_burst_then_refill() {
+        let limiter = limiter(2, 3);
+        let client = Client::Ip([10, 0, 0, 1].into());
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.acquire(client.clone(), start).is_ok());
+        }
+        let retry = limiter.acquire(client.clone(), start).unwrap_err();
+        assert_eq!(retry, Duration::from_millis(500));
+
+        // Other clients have their own bucket
+        assert!(limiter.acquire(Client::Token("ci".into()), start).is_ok());
+
+        let later = start + Duration::from_millis(500);
+        assert!(limiter.acquire(client.clone(), later).is_ok());
+        assert!(limiter.acquire(client, later).is_err());
+    }
+
+    #[test]
+    fn test_idle_buckets_are_dropped() {
+        let limiter = limiter(1000, 1);
+        let start = Instant::now();
+
+        for i in 0..MAX_CLIENTS as u32 {
+            let ip = IpAddr::from(i.to_be_bytes());
+            limiter.acquire(Client::Ip(ip), start).unwrap();
+        }
+        let later = start + Duration::from_secs(1);
+        limiter.acquire(Client::Unknown, later).unwrap();
+
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_busy_buckets_stay_capped() {
+        let limiter = limiter(1, 1);
+        let start = Instant::now();
+
+        for i in 0..MAX_CLIENTS as u32 {
+            let ip = IpAddr::from(i.to_be_bytes());
+            let at = start + Duration::from_micros(i.into());
+            limiter.acquire(Client::Ip(ip), at).unwrap();
+        }
+        // Nobody has refilled yet
+        let later = start + Duration::from_millis(10);
+        limiter.acquire(Client::Unknown, later).unwrap();
+
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), MAX_CLIENTS);
+        assert!(!buckets.contains_key(&Client::Ip(IpAddr::from(0u32.to_be_bytes()))));
+        assert!(buckets.contains_key(&Client::Unknown));
+    }
+}
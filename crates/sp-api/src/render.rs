@@ -0,0 +1,775 @@
+//! Render thread and the typed commands handlers use to reach it.
+//!
+//! The render thread owns the `EffectManager` and the driver. Handlers talk
+//! to it through a [`RenderHandle`], which sends [`RenderCommand`]s and waits
+//! for the oneshot reply, so the hot path never waits on an API lock.
+
+use serde::Serialize;
+use sp_core::{Error, Result};
+use sp_effects::{EffectManager, EffectParams};
+use sp_hub75::Driver;
+use sp_renderer::Framebuffer;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tokio::sync::{oneshot, watch};
+
+use crate::{
+    brightness::{Brightness, BrightnessStatus},
+    events::EventKind,
+    notify::{Notification, NotificationQueue, NotificationsStatus, NotifyReceipt},
+    state::AppState,
+};
+
+type Reply<T> = oneshot::Sender<Result<T>>;
+
+/// Quiet time after a parameter change before it is announced, so a slider
+/// dragged over `/ws/effect` yields one `EffectChanged` event.
+const PARAMS_DEBOUNCE: Duration = Duration::from_millis(250);
+
+type Job = Box<dyn FnOnce(&mut EffectManager) + Send>;
+
+/// Commands processed by the render thread between frames.
+pub enum RenderCommand {
+    /// Switch to an effect.
+    SetEffect {
+        name: String,
+        params: EffectParams,
+        reply: Reply<()>,
+    },
+    /// Merge partial params into the current effect's.
+    ///
+    /// Replies `None` when no effect is running.
+    UpdateParams {
+        patch: serde_json::Map<String, serde_json::Value>,
+        reply: Reply<Option<ParamsUpdate>>,
+    },
+    /// Stop the current effect.
+    Stop { reply: Reply<()> },
+    /// Set panel brightness, ramping over `duration` if non-zero.
+    SetBrightness {
+        level: u8,
+        duration: Duration,
+        reply: Reply<BrightnessStatus>,
+    },
+    /// Show static or scrolling text.
+    ShowText { text: TextCommand, reply: Reply<()> },
+    /// Show a notification over the current effect, or queue it.
+    Notify {
+        notification: Notification,
+        reply: Reply<NotifyReceipt>,
+    },
+    /// Report the visible and waiting notifications.
+    Notifications { reply: Reply<NotificationsStatus> },
+    /// Drop waiting notifications, replying how many there were.
+    ClearNotifications { reply: Reply<usize> },
+    /// Run arbitrary code against the manager (registry and script queries).
+    WithManager(Job),
+}
+
+/// Text to display with the built-in text effect.
+#[derive(Debug, Clone)]
+pub struct TextCommand {
+    pub text: String,
+    pub color: [u8; 3],
+    pub scroll: bool,
+    /// Scroll speed in pixels per second.
+    pub speed: u32,
+}
+
+/// Result of a parameter update.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamsUpdate {
+    pub effect: String,
+    pub params: EffectParams,
+}
+
+/// Cloneable handle for sending commands to the render thread.
+#[derive(Clone)]
+pub struct RenderHandle {
+    commands: mpsc::Sender<RenderCommand>,
+    brightness: watch::Receiver<BrightnessStatus>,
+    driver_healthy: Arc<AtomicBool>,
+}
+
+impl RenderHandle {
+    async fn request<T>(&self, command: impl FnOnce(Reply<T>) -> RenderCommand) -> Result<T> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.commands
+            .send(command(reply_tx))
+            .map_err(|_| Error::Internal("Render thread is not running".to_string()))?;
+        reply_rx
+            .await
+            .map_err(|_| Error::Internal("Render thread dropped the request".to_string()))?
+    }
+
+    /// Switch to an effect.
+    pub async fn set_effect(&self, name: impl Into<String>, params: EffectParams) -> Result<()> {
+        let name = name.into();
+        self.request(|reply| RenderCommand::SetEffect {
+            name,
+            params,
+            reply,
+        })
+        .await
+    }
+
+    /// Merge partial params into the current effect's; `None` if no effect is running.
+    pub async fn update_params(
+        &self,
+        patch: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Option<ParamsUpdate>> {
+        self.request(|reply| RenderCommand::UpdateParams { patch, reply })
+            .await
+    }
+
+    /// Stop the current effect.
+    pub async fn stop(&self) -> Result<()> {
+        self.request(|reply| RenderCommand::Stop { reply }).await
+    }
+
+    /// Set panel brightness, ramping over `duration` if non-zero.
+    pub async fn set_brightness(&self, level: u8, duration: Duration) -> Result<BrightnessStatus> {
+        self.request(|reply| RenderCommand::SetBrightness {
+            level,
+            duration,
+            reply,
+        })
+        .await
+    }
+
+    /// Show static or scrolling text.
+    pub async fn show_text(&self, text: TextCommand) -> Result<()> {
+        self.request(|reply| RenderCommand::ShowText { text, reply })
+            .await
+    }
+
+    /// Show a notification over the current effect, or queue it.
+    pub async fn notify(&self, notification: Notification) -> Result<NotifyReceipt> {
+        self.request(|reply| RenderCommand::Notify {
+            notification,
+            reply,
+        })
+        .await
+    }
+
+    /// Visible and waiting notifications.
+    pub async fn notifications(&self) -> Result<NotificationsStatus> {
+        self.request(|reply| RenderCommand::Notifications { reply })
+            .await
+    }
+
+    /// Drop waiting notifications, returning how many there were.
+    pub async fn clear_notifications(&self) -> Result<usize> {
+        self.request(|reply| RenderCommand::ClearNotifications { reply })
+            .await
+    }
+
+    /// Run `f` on the render thread's manager and return its result.
+    pub async fn call<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut EffectManager) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.request(|reply| {
+            RenderCommand::WithManager(Box::new(move |manager| {
+                let _ = reply.send(Ok(f(manager)));
+            }))
+        })
+        .await
+    }
+
+    /// Brightness currently applied by the render thread.
+    pub fn brightness(&self) -> BrightnessStatus {
+        self.brightness.borrow().clone()
+    }
+
+    /// Whether the last frame reached the driver without error.
+    pub fn driver_healthy(&self) -> bool {
+        self.driver_healthy.load(Ordering::Relaxed)
+    }
+
+    /// Resolves once the render thread has exited.
+    pub async fn stopped(&self) {
+        let mut brightness = self.brightness.clone();
+        while brightness.changed().await.is_ok() {}
+    }
+}
+
+/// Owns the `EffectManager` and driver and runs the render loop.
+pub struct Renderer {
+    manager: EffectManager,
+    driver: Box<dyn Driver>,
+    brightness: Brightness,
+    brightness_tx: watch::Sender<BrightnessStatus>,
+    commands: mpsc::Receiver<RenderCommand>,
+    notifications: NotificationQueue,
+    /// Id of the notification last announced, to publish each one once.
+    shown_notification: Option<u64>,
+    /// Whether the last driver call succeeded, to report health changes once.
+    driver_healthy: Arc<AtomicBool>,
+    /// Parameter change not announced yet, and when it was made.
+    pending_params: Option<(ParamsUpdate, Instant)>,
+}
+
+impl Renderer {
+    /// Create a renderer around an initialized driver, and the handle used to reach it.
+    pub fn new(manager: EffectManager, driver: Box<dyn Driver>) -> (Self, RenderHandle) {
+        let (commands_tx, commands_rx) = mpsc::channel();
+        let brightness = Brightness::new(driver.brightness());
+        let (brightness_tx, brightness_rx) = watch::channel(brightness.status());
+        let driver_healthy = Arc::new(AtomicBool::new(true));
+
+        let renderer = Self {
+            manager,
+            driver,
+            brightness,
+            brightness_tx,
+            commands: commands_rx,
+            notifications: NotificationQueue::new(),
+            shown_notification: None,
+            driver_healthy: Arc::clone(&driver_healthy),
+            pending_params: None,
+        };
+        let handle = RenderHandle {
+            commands: commands_tx,
+            brightness: brightness_rx,
+            driver_healthy,
+        };
+        (renderer, handle)
+    }
+
+    /// Run the render loop on a dedicated OS thread until shutdown.
+    ///
+    /// The thread gets `SCHED_FIFO` priority when `hardware.render_priority`
+    /// is non-zero (Linux only, needs `CAP_SYS_NICE` or root).
+    pub fn spawn(
+        self,
+        state: Arc<AppState>,
+        shutdown_rx: watch::Receiver<bool>,
+    ) -> std::io::Result<JoinHandle<()>> {
+        thread::Builder::new()
+            .name("render".to_string())
+            .spawn(move || {
+                set_realtime_priority(state.config.hardware.render_priority);
+                self.run(&state, &shutdown_rx);
+            })
+    }
+
+    /// Render frames at `panel.target_fps` until shutdown.
+    ///
+    /// Frames are scheduled on fixed deadlines; missed deadlines are skipped
+    /// and counted as late. Effects preferring a lower rate are only ticked
+    /// at that rate (or as soon as their parameters change). Completed frames
+    /// are published to `state.frames`; frames identical to the last one are
+    /// neither published nor re-sent to the driver. Notifications are drawn
+    /// over a copy of the effect's frame, so the effect never sees them.
+    fn run(mut self, state: &AppState, shutdown_rx: &watch::Receiver<bool>) {
+        let target_fps = state.config.panel.target_fps.max(1);
+        let frame_duration = Duration::from_secs(1) / target_fps;
+
+        // The canvas drawn over HTTP is selectable like any effect. Both
+        // names are reserved, so no script or plugin is replaced.
+        self.manager
+            .registry_mut()
+            .register_or_replace(state.canvas.registration());
+        // Spectrum reads pushed PCM and files from the configured directory
+        self.manager
+            .registry_mut()
+            .register_or_replace(state.audio.registration());
+
+        let mut back = Framebuffer::new(state.config.panel.width, state.config.panel.height);
+        let mut layer = back.clone();
+        let mut next_deadline = Instant::now();
+        let mut last_render: Option<Instant> = None;
+        let mut last_revision = None;
+
+        while !*shutdown_rx.borrow() {
+            // Wait for the next deadline, applying commands as they arrive
+            self.process_commands(state, next_deadline);
+
+            let frame_start = Instant::now();
+            let behind = frame_start.saturating_duration_since(next_deadline);
+            let missed = (behind.as_nanos() / frame_duration.as_nanos()) as u32;
+            if missed > 0 {
+                state.metrics.record_late_frames(missed.into());
+            }
+            next_deadline += frame_duration * (missed + 1);
+
+            // Apply brightness ramps
+            self.step_brightness(state, frame_start);
+            self.announce_params(state, Some(frame_start));
+            let notified = self.step_notifications(state, frame_start);
+
+            // Generate frame
+            let fps = self
+                .manager
+                .preferred_fps()
+                .map_or(target_fps, |fps| fps.clamp(1, target_fps));
+            let due = last_render.map_or(true, |last| {
+                frame_start.duration_since(last) + frame_duration / 2
+                    >= Duration::from_secs(1) / fps
+            });
+            let revision = self.manager.revision();
+            if !due && !notified && last_revision == Some(revision) {
+                continue;
+            }
+            last_render = Some(frame_start);
+            last_revision = Some(revision);
+
+            // Effects may draw on top of their previous frame
+            self.manager.tick(&mut layer);
+            let front = state.frames.latest();
+            back.data_mut().copy_from_slice(layer.data());
+            self.notifications.draw(&mut back, frame_start);
+            state
+                .metrics
+                .set_current_effect(self.manager.current_effect());
+            let tick_time = frame_start.elapsed();
+
+            // Display and publish frame
+            let display_start = Instant::now();
+            if back.data() != front.data() || state.frames.sequence() == 0 {
+                drop(front);
+                let result = self.driver.display(&back);
+                if let Err(e) = &result {
+                    state.metrics.record_driver_error();
+                    tracing::error!(error = %e, "Failed to display frame");
+                }
+                self.report_driver_health(state, result.err());
+                let next = state.frames.publish(back);
+                back = next.unwrap_or_else(|| (*state.frames.latest()).clone());
+            }
+            state
+                .metrics
+                .record_frame(tick_time, display_start.elapsed());
+        }
+
+        // Cleanup
+        self.announce_params(state, None);
+        tracing::info!("Render loop stopped");
+        if let Err(e) = self.driver.shutdown() {
+            tracing::error!(error = %e, "Error during driver shutdown");
+        }
+    }
+
+    /// Handle commands until `deadline`.
+    fn process_commands(&mut self, state: &AppState, deadline: Instant) {
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if timeout.is_zero() {
+                return;
+            }
+            match self.commands.recv_timeout(timeout) {
+                Ok(command) => self.handle(state, command),
+                Err(mpsc::RecvTimeoutError::Timeout) => return,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    thread::sleep(timeout);
+                    return;
+                }
+            }
+        }
+    }
+
+    fn handle(&mut self, state: &AppState, command: RenderCommand) {
+        match command {
+            RenderCommand::SetEffect {
+                name,
+                params,
+                reply,
+            } => {
+                let _ = reply.send(self.switch_effect(state, &name, params));
+            }
+            RenderCommand::UpdateParams { patch, reply } => {
+                let result = match self.manager.current_effect().map(String::from) {
+                    Some(effect) => self
+                        .manager
+                        .patch_params(&patch)
+                        .map(|params| Some(ParamsUpdate { effect, params })),
+                    None => Ok(None),
+                };
+                if let Ok(Some(update)) = &result {
+                    self.pending_params = Some((update.clone(), Instant::now()));
+                }
+                let _ = reply.send(result);
+            }
+            RenderCommand::Stop { reply } => {
+                self.announce_params(state, None);
+                let finished = self.manager.current_effect().map(String::from);
+                self.manager.stop();
+                if let Some(effect) = finished {
+                    state.events.publish(EventKind::EffectFinished { effect });
+                }
+                let _ = reply.send(Ok(()));
+            }
+            RenderCommand::SetBrightness {
+                level,
+                duration,
+                reply,
+            } => {
+                let result = if duration.is_zero() {
+                    self.driver
+                        .set_brightness(level)
+                        .map(|()| self.brightness.set(level))
+                } else {
+                    self.brightness.ramp_to(level, duration);
+                    Ok(())
+                };
+                self.publish_brightness();
+                if result.is_ok() {
+                    state.events.publish(EventKind::BrightnessChanged {
+                        status: self.brightness.status(),
+                    });
+                }
+                let _ = reply.send(result.map(|()| self.brightness.status()));
+            }
+            RenderCommand::ShowText { text, reply } => {
+                let mut params = EffectParams {
+                    color: Some(text.color),
+                    ..Default::default()
+                };
+                params.extra.insert("text".into(), text.text.clone().into());
+                params.extra.insert("scroll".into(), text.scroll.into());
+                params
+                    .extra
+                    .insert("scroll_speed".into(), text.speed.into());
+
+                let result = self.switch_effect(state, "text", params);
+                if result.is_ok() {
+                    state
+                        .events
+                        .publish(EventKind::TextShown { text: text.text });
+                }
+                let _ = reply.send(result);
+            }
+            RenderCommand::Notify {
+                notification,
+                reply,
+            } => {
+                let _ = reply.send(self.notifications.push(notification, Instant::now()));
+            }
+            RenderCommand::Notifications { reply } => {
+                let _ = reply.send(Ok(self.notifications.status(Instant::now())));
+            }
+            RenderCommand::ClearNotifications { reply } => {
+                let _ = reply.send(Ok(self.notifications.clear()));
+            }
+            RenderCommand::WithManager(job) => job(&mut self.manager),
+        }
+    }
+
+    /// Expire notifications, pause the effect under full-screen ones and
+    /// announce new ones. True while a notification needs drawing.
+    fn step_notifications(&mut self, state: &AppState, now: Instant) -> bool {
+        let changed = self.notifications.advance(now);
+        if self.notifications.is_fullscreen() {
+            self.manager.pause();
+        } else {
+            self.manager.resume();
+        }
+
+        let Some(active) = self.notifications.active() else {
+            self.shown_notification = None;
+            return changed;
+        };
+        if self.shown_notification != Some(active.id) {
+            self.shown_notification = Some(active.id);
+            state.events.publish(EventKind::NotificationShown {
+                id: active.id,
+                priority: active.priority,
+                text: active.text.clone(),
+            });
+        }
+        true
+    }
+
+    /// Switch effects, announcing the one that finished and the new one.
+    fn switch_effect(&mut self, state: &AppState, name: &str, params: EffectParams) -> Result<()> {
+        let finished = self.manager.current_effect().map(String::from);
+        self.manager.set_effect(name, params)?;
+        self.announce_params(state, None);
+
+        if let Some(effect) = finished {
+            state.events.publish(EventKind::EffectFinished { effect });
+        }
+        state.events.publish(EventKind::EffectChanged {
+            effect: name.to_string(),
+            params: self.manager.params().clone(),
+        });
+        Ok(())
+    }
+
+    /// Publish the pending parameter change once it has settled at `now`,
+    /// or right away when `now` is `None` (before other effect events).
+    fn announce_params(&mut self, state: &AppState, now: Option<Instant>) {
+        let Some((_, changed)) = &self.pending_params else {
+            return;
+        };
+        if now.is_some_and(|now| now.duration_since(*changed) < PARAMS_DEBOUNCE) {
+            return;
+        }
+        if let Some((update, _)) = self.pending_params.take() {
+            state.events.publish(EventKind::EffectChanged {
+                effect: update.effect,
+                params: update.params,
+            });
+        }
+    }
+
+    /// Publish a `DriverHealth` event when the driver starts or stops failing.
+    fn report_driver_health(&mut self, state: &AppState, error: Option<sp_core::Error>) {
+        let healthy = error.is_none();
+        if self.driver_healthy.swap(healthy, Ordering::Relaxed) == healthy {
+            return;
+        }
+        state.events.publish(EventKind::DriverHealth {
+            healthy,
+            error: error.map(|e| e.to_string()),
+        });
+    }
+
+    /// Advance any brightness ramp and push the new level to the driver.
+    fn step_brightness(&mut self, state: &AppState, now: Instant) {
+        if !self.brightness.is_ramping() {
+            return;
+        }
+        if let Some(level) = self.brightness.step(now) {
+            if let Err(e) = self.driver.set_brightness(level) {
+                state.metrics.record_driver_error();
+                tracing::error!(error = %e, "Failed to set brightness");
+            }
+        }
+        self.publish_brightness();
+
+        if !self.brightness.is_ramping() {
+            state.events.publish(EventKind::BrightnessChanged {
+                status: self.brightness.status(),
+            });
+        }
+    }
+
+    fn publish_brightness(&self) {
+        let status = self.brightness.status();
+        self.brightness_tx.send_if_modified(|current| {
+            let modified = *current != status;
+            *current = status;
+            modified
+        });
+    }
+}
+
+/// Give the calling thread `SCHED_FIFO` priority (1-99); 0 leaves it unchanged.
+#[cfg(target_os = "linux")]
+fn set_realtime_priority(priority: u8) {
+    if priority == 0 {
+        return;
+    }
+
+    let param = libc::sched_param {
+        sched_priority: i32::from(priority.min(99)),
+    };
+    // SAFETY: `param` is a valid sched_param that outlives the call, and
+    // pthread_self() always refers to the calling thread.
+    let result =
+        unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) };
+
+    if result == 0 {
+        tracing::info!(priority, "Render thread running with real-time priority");
+    } else {
+        tracing::warn!(
+            priority,
+            error = %std::io::Error::from_raw_os_error(result),
+            "Failed to set real-time priority for render thread"
+        );
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_realtime_priority(priority: u8) {
+    if priority > 0 {
+        tracing::warn!(
+            priority,
+            "Real-time render priority is only supported on Linux"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+    use sp_core::{Color, Config};
+
+    async fn wait_for(state: &AppState, color: Color) {
+        for _ in 0..100 {
+            if state.frames.latest().data()[0] == color {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("frame never turned {color:?}");
+    }
+
+    #[tokio::test]
+    async fn test_commands() {
+        let (state, render) = test_util::start(Config::default());
+        let renderer = &state.renderer;
+
+        let params = EffectParams {
+            color: Some([255, 0, 0]),
+            ..Default::default()
+        };
+        renderer.set_effect("solid", params).await.unwrap();
+        wait_for(&state, Color::RED).await;
+
+        let patch = serde_json::json!({ "color": [0, 0, 255] });
+        let update = renderer
+            .update_params(patch.as_object().unwrap().clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(update.effect, "solid");
+        wait_for(&state, Color::BLUE).await;
+
+        let status = renderer.set_brightness(40, Duration::ZERO).await.unwrap();
+        assert_eq!(status.brightness, 40);
+        assert_eq!(renderer.brightness().brightness, 40);
+
+        assert!(renderer
+            .set_effect("nope", EffectParams::default())
+            .await
+            .is_err());
+
+        renderer.stop().await.unwrap();
+        assert!(renderer
+            .update_params(serde_json::Map::new())
+            .await
+            .unwrap()
+            .is_none());
+        wait_for(&state, Color::BLACK).await;
+
+        drop(render);
+        assert!(renderer.stop().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_events() {
+        let (state, _render) = test_util::start(Config::default());
+        let renderer = &state.renderer;
+        let mut events = state.events.subscribe();
+
+        renderer
+            .set_effect("solid", EffectParams::default())
+            .await
+            .unwrap();
+        let text = TextCommand {
+            text: "Hi".to_string(),
+            color: [255, 255, 255],
+            scroll: false,
+            speed: 50,
+        };
+        renderer.show_text(text).await.unwrap();
+        renderer.stop().await.unwrap();
+        renderer.set_brightness(10, Duration::ZERO).await.unwrap();
+
+        let mut names = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            names.push(event.kind.name());
+        }
+        assert_eq!(
+            names,
+            [
+                "effect_changed",
+                "effect_finished",
+                "effect_changed",
+                "text_shown",
+                "effect_finished",
+                "brightness_changed",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_param_events_are_debounced() {
+        let (state, _render) = test_util::start(Config::default());
+        let renderer = &state.renderer;
+        renderer
+            .set_effect("solid", EffectParams::default())
+            .await
+            .unwrap();
+        let mut events = state.events.subscribe();
+
+        for level in [0.2, 0.4, 0.6] {
+            let patch = serde_json::json!({ "intensity": level });
+            renderer
+                .update_params(patch.as_object().unwrap().clone())
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(PARAMS_DEBOUNCE * 2).await;
+        renderer.stop().await.unwrap();
+
+        let mut changes = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let EventKind::EffectChanged { params, .. } = event.kind {
+                changes.push(params.intensity);
+            }
+        }
+        assert_eq!(changes, [0.6]);
+    }
+
+    #[tokio::test]
+    async fn test_notifications() {
+        use crate::notify::{NotifyStyle, Priority};
+
+        let (state, _render) = test_util::start(Config::default());
+        let renderer = &state.renderer;
+        let mut events = state.events.subscribe();
+
+        let params = EffectParams {
+            color: Some([255, 0, 0]),
+            ..Default::default()
+        };
+        renderer.set_effect("solid", params).await.unwrap();
+        wait_for(&state, Color::RED).await;
+
+        let notification = |style, priority, duration_ms| Notification {
+            id: 0,
+            text: Some("Hi".to_string()),
+            icon: None,
+            color: [255, 255, 255],
+            style,
+            priority,
+            duration_ms,
+        };
+        let first = renderer
+            .notify(notification(NotifyStyle::Fullscreen, Priority::Normal, 200))
+            .await
+            .unwrap();
+        let queued = renderer
+            .notify(notification(NotifyStyle::Overlay, Priority::Low, 200))
+            .await
+            .unwrap();
+        assert_eq!((first.position, queued.position), (0, 1));
+
+        // Full-screen: the effect is hidden and paused, then resumes intact
+        wait_for(&state, Color::BLACK).await;
+        assert!(renderer.call(|manager| manager.is_paused()).await.unwrap());
+        let status = renderer.notifications().await.unwrap();
+        assert_eq!(status.active.unwrap().notification.id, first.id);
+        assert_eq!(status.pending.len(), 1);
+
+        wait_for(&state, Color::RED).await;
+        assert!(!renderer.call(|manager| manager.is_paused()).await.unwrap());
+        assert_eq!(renderer.clear_notifications().await.unwrap(), 0);
+
+        let mut shown = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let EventKind::NotificationShown { id, .. } = event.kind {
+                shown.push(id);
+            }
+        }
+        assert_eq!(shown, [first.id, queued.id]);
+    }
+}
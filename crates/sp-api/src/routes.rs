@@ -1,37 +1,196 @@
 //! API route definitions.
 
 use axum::{
-    routing::{get, post},
+    extract::{DefaultBodyLimit, Request},
+    handler::Handler,
+    http::{HeaderValue, Method},
+    middleware,
+    routing::{on, MethodFilter, MethodRouter},
     Router,
 };
 use std::sync::Arc;
 use tower_http::{
-    cors::{Any, CorsLayer},
+    cors::{AllowOrigin, Any, CorsLayer},
     trace::TraceLayer,
 };
 
-use crate::{handlers, state::AppState};
+use crate::{
+    audio, auth, canvas, handlers, inbound, metrics, openapi, ratelimit, sse, state::AppState,
+    stream,
+};
 
-/// Create the API router with all routes.
-pub fn create_router(state: Arc<AppState>) -> Router {
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
+/// CORS policy from `server.cors_origins`.
+fn cors_layer(origins: &[String]) -> CorsLayer {
+    let allow_origin = if origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        let origins: Vec<HeaderValue> = origins
+            .iter()
+            .filter_map(|origin| match origin.parse() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    tracing::warn!(origin, "Ignoring invalid CORS origin");
+                    None
+                }
+            })
+            .collect();
+        AllowOrigin::list(origins)
+    };
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+}
 
-    Router::new()
+/// A method and path served by a handler.
+pub(crate) struct Route {
+    // Only read by the OpenAPI test; the handler carries its own filter
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub method: Method,
+    /// Path in axum syntax, with `:param` segments.
+    pub path: &'static str,
+    handler: MethodRouter<Arc<AppState>>,
+}
+
+impl Route {
+    /// Raise the request body limit above axum's default 2 MB.
+    fn with_body_limit(mut self, limit: usize) -> Self {
+        self.handler = self.handler.layer(DefaultBodyLimit::max(limit));
+        self
+    }
+}
+
+fn route<H, T>(method: Method, path: &'static str, handler: H) -> Route
+where
+    H: Handler<T, Arc<AppState>>,
+    T: 'static,
+{
+    let filter = MethodFilter::try_from(method.clone()).expect("routes use standard methods");
+    Route {
+        method,
+        path,
+        handler: on(filter, handler),
+    }
+}
+
+/// Every route of the API, documented or not.
+///
+/// The router is built from this table, and a test checks it against the
+/// OpenAPI spec so the two cannot drift apart.
+pub(crate) fn routes() -> Vec<Route> {
+    vec![
         // Health check
-        .route("/health", get(handlers::health))
+        route(Method::GET, "/health", handlers::health),
+        route(Method::GET, "/metrics", metrics::metrics),
+        // API documentation
+        route(Method::GET, "/api/openapi.json", openapi::openapi_json),
+        // Panel state stream
+        route(Method::GET, "/api/events", sse::events_stream),
         // Effects
-        .route("/api/effect", post(handlers::set_effect))
-        .route("/api/effect", get(handlers::get_current_effect))
-        .route("/api/effect/stop", post(handlers::stop_effect))
+        route(Method::POST, "/api/effect", handlers::set_effect),
+        route(Method::GET, "/api/effect", handlers::get_current_effect),
+        route(Method::PATCH, "/api/effect", handlers::patch_effect),
+        route(Method::GET, "/ws/effect", handlers::effect_params_ws),
+        route(Method::GET, "/ws", handlers::preview_ws),
+        route(Method::POST, "/api/effect/stop", handlers::stop_effect),
+        route(Method::GET, "/api/effects", handlers::list_effects),
+        route(
+            Method::GET,
+            "/api/effects/:name",
+            handlers::get_effect_schema,
+        ),
+        // Scripts
+        route(Method::GET, "/api/scripts", handlers::list_scripts),
+        route(Method::POST, "/api/scripts", handlers::upload_script),
+        route(Method::GET, "/api/scripts/:name", handlers::get_script),
+        route(
+            Method::DELETE,
+            "/api/scripts/:name",
+            handlers::delete_script,
+        ),
         // Text
-        .route("/api/text", post(handlers::display_text))
+        route(Method::POST, "/api/text", handlers::display_text),
         // Brightness
-        .route("/api/brightness", post(handlers::set_brightness))
+        route(Method::GET, "/api/brightness", handlers::get_brightness),
+        route(Method::POST, "/api/brightness", handlers::set_brightness),
+        // Notifications
+        route(Method::POST, "/api/notify", handlers::notify),
+        route(Method::GET, "/api/notify/queue", handlers::get_notify_queue),
+        route(
+            Method::DELETE,
+            "/api/notify/queue",
+            handlers::clear_notify_queue,
+        ),
+        // Canvas
+        route(Method::GET, "/api/canvas", canvas::get_canvas),
+        route(Method::POST, "/api/canvas/ops", canvas::draw),
+        // Pushed frames
+        route(Method::PUT, "/api/frame", stream::put_frame).with_body_limit(stream::MAX_BODY),
+        route(Method::GET, "/ws/frames", stream::frames_ws),
+        // Audio for the spectrum effect
+        route(Method::GET, "/ws/audio", audio::audio_ws),
+        // Webhooks
+        route(Method::GET, "/api/webhooks", handlers::list_webhooks),
+        route(Method::POST, "/api/webhooks", handlers::create_webhook),
+        route(
+            Method::GET,
+            "/api/webhooks/deliveries",
+            handlers::list_deliveries,
+        ),
+        route(Method::GET, "/api/webhooks/:id", handlers::get_webhook),
+        route(Method::PUT, "/api/webhooks/:id", handlers::update_webhook),
+        route(
+            Method::DELETE,
+            "/api/webhooks/:id",
+            handlers::delete_webhook,
+        ),
+        // Inbound webhooks
+        route(Method::POST, "/hooks/github", inbound::github_hook),
+        route(Method::POST, "/hooks/:name", inbound::named_hook),
+    ]
+}
+
+/// Create the API router with all routes.
+pub fn create_router(state: Arc<AppState>) -> Router {
+    create_router_with(state, Router::new())
+}
+
+/// Create the API router with extra routes (such as `/mcp`) served behind
+/// the same auth, rate limits and metrics.
+pub fn create_router_with(state: Arc<AppState>, extra: Router<Arc<AppState>>) -> Router {
+    let cors = cors_layer(&state.config.server.cors_origins);
+
+    let router = routes().into_iter().fold(extra, |router, route| {
+        router.route(route.path, route.handler)
+    });
+
+    router
+        .merge(openapi::swagger_ui())
         // Middleware
-        .layer(TraceLayer::new_for_http())
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            ratelimit::limit_requests,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            auth::require_token,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            metrics::track_requests,
+        ))
+        // Spans log the path only, so `?token=` never reaches the logs
+        .layer(
+            TraceLayer::new_for_http().make_span_with(|request: &Request<_>| {
+                tracing::debug_span!(
+                    "request",
+                    method = %request.method(),
+                    path = request.uri().path(),
+                    version = ?request.version(),
+                )
+            }),
+        )
         .layer(cors)
         .with_state(state)
 }
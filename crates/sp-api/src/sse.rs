@@ -0,0 +1,204 @@
+//! Server-Sent Events stream of panel state.
+//!
+//! A client first receives a `snapshot` of the whole panel state, then every
+//! bus [`Event`] under its own type, and an `uptime` tick in between. When a
+//! slow client misses events a fresh snapshot is sent so it stays consistent.
+
+use axum::{
+    extract::State,
+    response::sse::{Event as SseEvent, KeepAlive, Sse},
+};
+use futures_util::{stream, Stream};
+use serde::Serialize;
+use sp_effects::EffectParams;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::{Instant, Interval};
+use utoipa::ToSchema;
+
+use crate::{brightness::BrightnessStatus, events::Event, state::AppState};
+
+/// Interval between `uptime` events.
+const UPTIME_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Full panel state, sent first and after missed events.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PanelSnapshot {
+    pub effect: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub params: Option<EffectParams>,
+    pub brightness: BrightnessStatus,
+    pub driver_healthy: bool,
+    pub uptime_secs: u64,
+}
+
+impl PanelSnapshot {
+    /// Read the current state from the render thread.
+    pub async fn capture(state: &AppState) -> sp_core::Result<Self> {
+        let (effect, params) = state
+            .renderer
+            .call(|manager| {
+                let effect = manager.current_effect().map(String::from);
+                let params = effect.as_ref().map(|_| manager.params().clone());
+                (effect, params)
+            })
+            .await?;
+
+        Ok(Self {
+            effect,
+            params,
+            brightness: state.renderer.brightness(),
+            driver_healthy: state.renderer.driver_healthy(),
+            uptime_secs: state.uptime_secs(),
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct Uptime {
+    uptime_secs: u64,
+}
+
+/// Per-client stream state.
+struct Client {
+    state: Arc<AppState>,
+    events: broadcast::Receiver<Event>,
+    ticks: Interval,
+    snapshot_due: bool,
+}
+
+impl Client {
+    async fn next(&mut self) -> Option<Result<SseEvent, axum::Error>> {
+        loop {
+            if std::mem::take(&mut self.snapshot_due) {
+                return match PanelSnapshot::capture(&self.state).await {
+                    Ok(snapshot) => Some(SseEvent::default().event("snapshot").json_data(snapshot)),
+                    // The render thread is gone: the server is shutting down
+                    Err(_) => None,
+                };
+            }
+
+            tokio::select! {
+                event = self.events.recv() => match event {
+                    Ok(event) => {
+                        return Some(
+                            SseEvent::default()
+                                .event(event.kind.name())
+                                .id(event.id.to_string())
+                                .json_data(event),
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!(skipped, "SSE client lagged, resending snapshot");
+                        self.snapshot_due = true;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+                _ = self.ticks.tick() => {
+                    let uptime = Uptime { uptime_secs: self.state.uptime_secs() };
+                    return Some(SseEvent::default().event("uptime").json_data(uptime));
+                }
+                // End the stream so graceful shutdown isn't held open
+                _ = self.state.renderer.stopped() => return None,
+            }
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "system",
+    responses((
+        status = 200,
+        description = "`snapshot` (PanelSnapshot), then bus events by type and `uptime` ticks",
+        body = String,
+        content_type = "text/event-stream"
+    ))
+)]
+pub async fn events_stream(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<SseEvent, axum::Error>>> {
+    // Subscribe before the snapshot so no change falls in between
+    let client = Client {
+        events: state.events.subscribe(),
+        state,
+        ticks: tokio::time::interval_at(Instant::now() + UPTIME_INTERVAL, UPTIME_INTERVAL),
+        snapshot_due: true,
+    };
+
+    let stream = stream::unfold(client, |mut client| async move {
+        let event = client.next().await?;
+        Some((event, client))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{create_router, test_util};
+    use axum::body::{Body, BodyDataStream};
+    use axum::http::{header, Request, StatusCode};
+    use futures_util::StreamExt;
+    use sp_core::Config;
+    use sp_effects::EffectParams;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    /// Read frames until one of type `name`, returning its data line.
+    async fn expect_event(body: &mut BodyDataStream, name: &str) -> serde_json::Value {
+        let wait = async {
+            loop {
+                let chunk = body.next().await.unwrap().unwrap();
+                let frame = std::str::from_utf8(&chunk).unwrap();
+                if frame.lines().any(|line| line == format!("event: {name}")) {
+                    let data = frame
+                        .lines()
+                        .find_map(|line| line.strip_prefix("data: "))
+                        .unwrap();
+                    return serde_json::from_str(data).unwrap();
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), wait)
+            .await
+            .unwrap_or_else(|_| panic!("no {name} event"))
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_then_changes() {
+        let config = Config::default();
+        let (state, render) = test_util::start(config);
+
+        let request = Request::get("/api/events").body(Body::empty()).unwrap();
+        let response = create_router(Arc::clone(&state))
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+        let mut body = response.into_body().into_data_stream();
+
+        let snapshot = expect_event(&mut body, "snapshot").await;
+        assert_eq!(snapshot["effect"], serde_json::Value::Null);
+        assert_eq!(snapshot["driver_healthy"], true);
+        assert!(snapshot["brightness"]["target"].is_u64());
+
+        state
+            .renderer
+            .set_effect("solid", EffectParams::default())
+            .await
+            .unwrap();
+        let changed = expect_event(&mut body, "effect_changed").await;
+        assert_eq!(changed["effect"], "solid");
+
+        drop(render);
+        let end = tokio::time::timeout(Duration::from_secs(5), body.next()).await;
+        assert!(matches!(end, Ok(None)), "stream should end on shutdown");
+    }
+}
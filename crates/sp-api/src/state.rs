@@ -1,36 +1,53 @@
 //! Application state shared across handlers.
 
 use sp_core::Config;
-use sp_effects::EffectManager;
-use sp_hub75::Driver;
-use sp_renderer::Framebuffer;
+use sp_renderer::FrameExchange;
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::RwLock;
+
+use crate::{
+    audio::AudioStream, canvas::Canvas, events::EventBus, inbound::HookRestore, metrics::Metrics,
+    ratelimit::RateLimiter, render::RenderHandle, stream::FrameStream, webhooks::Webhooks,
+};
 
 /// Shared application state.
 pub struct AppState {
     pub config: Config,
-    pub effect_manager: RwLock<EffectManager>,
-    pub framebuffer: RwLock<Framebuffer>,
-    pub driver: RwLock<Box<dyn Driver>>,
+    pub renderer: RenderHandle,
+    pub frames: FrameExchange,
+    pub metrics: Metrics,
+    pub rate_limiter: RateLimiter,
+    pub events: EventBus,
+    pub webhooks: Webhooks,
+    pub hook_restore: HookRestore,
+    pub stream: FrameStream,
+    pub canvas: Canvas,
+    pub audio: AudioStream,
     pub start_time: Instant,
 }
 
 impl AppState {
     /// Create new application state.
-    pub fn new(
-        config: Config,
-        effect_manager: EffectManager,
-        driver: Box<dyn Driver>,
-    ) -> Arc<Self> {
-        let fb = Framebuffer::new(config.panel.width, config.panel.height);
+    pub fn new(config: Config, renderer: RenderHandle) -> Arc<Self> {
+        let frames = FrameExchange::new(config.panel.width, config.panel.height);
+        let rate_limiter = RateLimiter::new(&config.server.rate_limit);
+        let webhooks = Webhooks::new(&config.webhooks);
+        let stream = FrameStream::new(config.panel.width, config.panel.height);
+        let canvas = Canvas::new(config.panel.width, config.panel.height);
+        let audio = AudioStream::new(&config.audio);
 
         Arc::new(Self {
             config,
-            effect_manager: RwLock::new(effect_manager),
-            framebuffer: RwLock::new(fb),
-            driver: RwLock::new(driver),
+            renderer,
+            frames,
+            metrics: Metrics::new().expect("metric registration is static"),
+            rate_limiter,
+            events: EventBus::new(),
+            webhooks,
+            hook_restore: HookRestore::default(),
+            stream,
+            canvas,
+            audio,
             start_time: Instant::now(),
         })
     }
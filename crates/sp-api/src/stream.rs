@@ -0,0 +1,556 @@
+//! Frames pushed by external renderers: `PUT /api/frame` and `/ws/frames`.
+//!
+//! Pushed frames are shown by the `stream` effect, which takes over the
+//! panel while frames arrive. Once they stop for `stream.timeout_ms`, the
+//! panel holds the last frame, blanks, or goes back to the previous effect,
+//! as `stream.on_stop` says.
+
+use axum::{
+    body::Bytes,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use sp_core::{Color, Error, Point, StreamStopPolicy};
+use sp_effects::LiveInput;
+use sp_renderer::Framebuffer;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, watch, Notify};
+use tokio::task::JoinHandle;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::{live::LiveOverride, state::AppState, validation::ApiError};
+
+/// Effect showing pushed frames.
+pub const EFFECT_NAME: &str = "stream";
+
+/// Largest accepted frame side, in pixels, before scaling.
+const MAX_SIZE: u32 = 1024;
+
+/// Largest `PUT /api/frame` body: a frame of the largest size as base64 JSON,
+/// which takes 4 characters per 3 RGB bytes, plus room for the other fields.
+/// Raw and PNG frames of that size are smaller.
+pub(crate) const MAX_BODY: usize = MAX_SIZE as usize * MAX_SIZE as usize * 4 + 1024;
+
+/// How often the stream is checked for silence.
+const EXPIRE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// First bytes of every PNG file.
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+/// Latest pushed frame, shared with the `stream` effect.
+pub struct FrameStream {
+    width: u32,
+    height: u32,
+    input: LiveInput,
+    pushed: Notify,
+}
+
+impl FrameStream {
+    /// Create a black stream the size of the panel.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            input: LiveInput::new(width, height),
+            pushed: Notify::new(),
+        }
+    }
+
+    /// Show `frame`, stretched to the panel when `scale` is set.
+    ///
+    /// Frames of another size are refused unless scaled.
+    pub fn push(&self, frame: Framebuffer, scale: bool) -> sp_core::Result<()> {
+        let frame = fit(frame, self.width, self.height, scale)?;
+        self.input
+            .write(|pixels| pixels.data_mut().copy_from_slice(frame.data()));
+        self.pushed.notify_one();
+        Ok(())
+    }
+
+    /// Spawn the task switching the panel to the `stream` effect while
+    /// frames arrive; it runs until `shutdown` flips.
+    pub fn spawn(state: Arc<AppState>, shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+        tokio::spawn(run(state, shutdown))
+    }
+}
+
+async fn run(state: Arc<AppState>, mut shutdown: watch::Receiver<bool>) {
+    let stream = &state.stream;
+    let config = &state.config.stream;
+    let mut live = LiveOverride::new(Arc::clone(&state), EFFECT_NAME);
+    let registered = live
+        .register("Frames pushed by external renderers", stream.input.clone())
+        .await;
+    if let Err(e) = registered {
+        warn!(error = %e, "Failed to register stream effect");
+        return;
+    }
+
+    let timeout = Duration::from_millis(config.timeout_ms);
+    let mut events = state.events.subscribe();
+    let mut expire = tokio::time::interval(EXPIRE_INTERVAL);
+    let mut last_frame: Option<Instant> = None;
+
+    loop {
+        tokio::select! {
+            // Effect changes made before a frame must not count against it
+            biased;
+            event = events.recv() => match event {
+                Ok(event) => live.observe(&event.kind),
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = stream.pushed.notified() => {
+                last_frame = Some(Instant::now());
+                live.activate().await;
+            }
+            _ = expire.tick() => {
+                if last_frame.is_some_and(|at| at.elapsed() >= timeout) {
+                    last_frame = None;
+                    match config.on_stop {
+                        StreamStopPolicy::Hold => {
+                            live.settle();
+                        }
+                        StreamStopPolicy::Blank => {
+                            live.settle();
+                            stream.input.clear();
+                        }
+                        StreamStopPolicy::Fallback => {
+                            if live.release().await {
+                                stream.input.clear();
+                            }
+                        }
+                    }
+                }
+            }
+            _ = shutdown.changed() => break,
+        }
+    }
+}
+
+/// Encoding of a frame in a JSON body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameFormat {
+    /// Packed RGB24, row-major.
+    #[default]
+    Rgb,
+    Png,
+}
+
+/// Query of `PUT /api/frame` and `/ws/frames`.
+#[derive(Debug, Default, Deserialize)]
+pub struct FrameQuery {
+    /// Width of raw RGB frames; the panel's by default.
+    pub width: Option<u32>,
+    /// Height of raw RGB frames; the panel's by default.
+    pub height: Option<u32>,
+    /// Stretch frames of another size to the panel.
+    #[serde(default)]
+    pub scale: bool,
+}
+
+/// A frame sent as JSON, for clients that cannot send binary bodies.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FrameRequest {
+    #[serde(default)]
+    pub format: FrameFormat,
+    /// Base64 of the RGB bytes or of the PNG file.
+    pub data: String,
+    /// Width of RGB frames; the panel's by default.
+    pub width: Option<u32>,
+    /// Height of RGB frames; the panel's by default.
+    pub height: Option<u32>,
+    /// Stretch frames of another size to the panel.
+    pub scale: Option<bool>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/frame",
+    tag = "frames",
+    params(
+        ("width" = Option<u32>, Query, description = "Width of raw RGB frames, the panel's by default"),
+        ("height" = Option<u32>, Query, description = "Height of raw RGB frames, the panel's by default"),
+        ("scale" = Option<bool>, Query, description = "Stretch frames of another size to the panel")
+    ),
+    request_body(
+        content = FrameRequest,
+        description = "Packed RGB24 (application/octet-stream), a PNG file (image/png) or base64 JSON",
+        content_type = "application/json"
+    ),
+    responses(
+        (status = 200, description = "Frame shown by the stream effect", body = Object),
+        (status = 400, description = "Undecodable frame or wrong size", body = ErrorResponse)
+    )
+)]
+pub async fn put_frame(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<FrameQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, ApiError> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    let (frame, scale) = if content_type.starts_with("application/json") {
+        let request = serde_json::from_slice(&body)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid frame JSON: {e}")))?;
+        decode_request(&state, request, &query)?
+    } else if content_type.starts_with("image/png") {
+        (decode_png(&body)?, query.scale)
+    } else {
+        (decode_raw(&state, &body, &query)?, query.scale)
+    };
+    state.stream.push(frame, scale)?;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "effect": EFFECT_NAME
+    })))
+}
+
+/// Continuous frame push: binary messages carry raw RGB or PNG frames, text
+/// messages JSON frames. Only failures get a reply.
+pub async fn frames_ws(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<FrameQuery>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| frames_socket(socket, state, query))
+}
+
+async fn frames_socket(mut socket: WebSocket, state: Arc<AppState>, query: FrameQuery) {
+    while let Some(Ok(msg)) = socket.recv().await {
+        let decoded = match msg {
+            Message::Binary(data) if data.starts_with(&PNG_SIGNATURE) => {
+                decode_png(&data).map(|frame| (frame, query.scale))
+            }
+            Message::Binary(data) => {
+                decode_raw(&state, &data, &query).map(|frame| (frame, query.scale))
+            }
+            Message::Text(text) => match serde_json::from_str(&text) {
+                Ok(request) => decode_request(&state, request, &query),
+                Err(e) => Err(Error::invalid_param("frame", format!("Invalid JSON: {e}"))),
+            },
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let pushed = decoded.and_then(|(frame, scale)| state.stream.push(frame, scale));
+        let Err(e) = pushed else { continue };
+        let Ok(reply) = serde_json::to_string(&ApiError::from(e).into_response_body()) else {
+            continue;
+        };
+        if socket.send(Message::Text(reply)).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Decode a JSON frame; its fields win over the query.
+fn decode_request(
+    state: &AppState,
+    request: FrameRequest,
+    query: &FrameQuery,
+) -> sp_core::Result<(Framebuffer, bool)> {
+    let data = STANDARD
+        .decode(&request.data)
+        .map_err(|e| Error::invalid_param("data", format!("Invalid base64: {e}")))?;
+    let scale = request.scale.unwrap_or(query.scale);
+    let frame = match request.format {
+        FrameFormat::Png => decode_png(&data)?,
+        FrameFormat::Rgb => {
+            let query = FrameQuery {
+                width: request.width.or(query.width),
+                height: request.height.or(query.height),
+                scale,
+            };
+            decode_raw(state, &data, &query)?
+        }
+    };
+    Ok((frame, scale))
+}
+
+/// Decode packed RGB24 at the query's size, the panel's by default.
+fn decode_raw(state: &AppState, data: &[u8], query: &FrameQuery) -> sp_core::Result<Framebuffer> {
+    let panel = &state.config.panel;
+    let width = query.width.unwrap_or(panel.width);
+    let height = query.height.unwrap_or(panel.height);
+    decode_rgb(data, width, height)
+}
+
+fn check_size(width: u32, height: u32) -> sp_core::Result<()> {
+    if !(1..=MAX_SIZE).contains(&width) || !(1..=MAX_SIZE).contains(&height) {
+        return Err(Error::invalid_param(
+            "frame",
+            format!("Frame is {width}x{height}, sides must be 1-{MAX_SIZE} pixels"),
+        ));
+    }
+    Ok(())
+}
+
+/// Decode packed RGB24 bytes of a `width` x `height` frame.
+fn decode_rgb(data: &[u8], width: u32, height: u32) -> sp_core::Result<Framebuffer> {
+    check_size(width, height)?;
+    let expected = width as usize * height as usize * 3;
+    if data.len() != expected {
+        return Err(Error::invalid_param(
+            "frame",
+            format!(
+                "Expected {expected} bytes of RGB for {width}x{height}, got {}",
+                data.len()
+            ),
+        ));
+    }
+
+    let mut frame = Framebuffer::new(width, height);
+    for (pixel, rgb) in frame.data_mut().iter_mut().zip(data.chunks_exact(3)) {
+        *pixel = Color::new(rgb[0], rgb[1], rgb[2]);
+    }
+    Ok(frame)
+}
+
+/// Decode a PNG file of any color type; transparent pixels are unlit.
+fn decode_png(data: &[u8]) -> sp_core::Result<Framebuffer> {
+    let invalid =
+        |e: png::DecodingError| Error::invalid_param("frame", format!("Invalid PNG: {e}"));
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(invalid)?;
+    let (width, height) = (reader.info().width, reader.info().height);
+    check_size(width, height)?;
+
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(invalid)?;
+    let samples = info.color_type.samples();
+    let over_black = |value: u8, alpha: u8| (u16::from(value) * u16::from(alpha) / 255) as u8;
+
+    let mut frame = Framebuffer::new(width, height);
+    let pixels = buf[..info.buffer_size()].chunks_exact(samples);
+    for (pixel, samples) in frame.data_mut().iter_mut().zip(pixels) {
+        *pixel = match *samples {
+            [v] => Color::new(v, v, v),
+            [v, a] => {
+                let v = over_black(v, a);
+                Color::new(v, v, v)
+            }
+            [r, g, b] => Color::new(r, g, b),
+            [r, g, b, a] => Color::new(over_black(r, a), over_black(g, a), over_black(b, a)),
+            _ => Color::BLACK,
+        };
+    }
+    Ok(frame)
+}
+
+/// Fit `frame` to `width` x `height`, stretching it (nearest neighbour)
+/// when `scale` is set.
+fn fit(frame: Framebuffer, width: u32, height: u32, scale: bool) -> sp_core::Result<Framebuffer> {
+    if frame.width() == width && frame.height() == height {
+        return Ok(frame);
+    }
+    if !scale {
+        return Err(Error::invalid_param(
+            "frame",
+            format!(
+                "Frame is {}x{}, the panel is {width}x{height}; set scale to resize it",
+                frame.width(),
+                frame.height()
+            ),
+        ));
+    }
+
+    let mut scaled = Framebuffer::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let source = Point::new(
+                (x * frame.width() / width) as i32,
+                (y * frame.height() / height) as i32,
+            );
+            let color = frame.get(source).unwrap_or(Color::BLACK);
+            scaled.set(Point::new(x as i32, y as i32), color);
+        }
+    }
+    Ok(scaled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_router, test_util, Renderer};
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use sp_core::Config;
+    use sp_effects::{EffectManager, EffectParams};
+    use sp_hub75::MockDriver;
+    use tower::ServiceExt;
+
+    fn encode_png(width: u32, height: u32, color: png::ColorType, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(color);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(data).unwrap();
+        writer.finish().unwrap();
+        out
+    }
+
+    #[test]
+    fn test_decode_and_fit() {
+        assert!(sp_effects::RESERVED_NAMES.contains(&EFFECT_NAME));
+        let frame = decode_rgb(&[255, 0, 0, 0, 0, 255], 2, 1).unwrap();
+        assert_eq!(frame.get(Point::new(1, 0)), Some(Color::BLUE));
+        assert!(decode_rgb(&[0; 5], 2, 1).is_err());
+        assert!(decode_rgb(&[], 0, 0).is_err());
+
+        let png = encode_png(
+            2,
+            1,
+            png::ColorType::Rgba,
+            &[0, 255, 0, 255, 200, 100, 50, 0],
+        );
+        let frame = decode_png(&png).unwrap();
+        assert_eq!(frame.get(Point::new(0, 0)), Some(Color::GREEN));
+        assert_eq!(frame.get(Point::new(1, 0)), Some(Color::BLACK));
+        let png = encode_png(1, 1, png::ColorType::Grayscale, &[255]);
+        assert_eq!(decode_png(&png).unwrap().data(), &[Color::WHITE]);
+        assert!(decode_png(b"not a png").is_err());
+
+        // Each source pixel becomes a 2x2 block
+        let scaled = fit(frame_2x1(), 4, 2, true).unwrap();
+        assert_eq!(scaled.get(Point::new(1, 1)), Some(Color::RED));
+        assert_eq!(scaled.get(Point::new(2, 0)), Some(Color::BLUE));
+        assert!(fit(frame_2x1(), 4, 2, false).is_err());
+        assert!(fit(frame_2x1(), 2, 1, false).is_ok());
+    }
+
+    fn frame_2x1() -> Framebuffer {
+        decode_rgb(&[255, 0, 0, 0, 0, 255], 2, 1).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_largest_frames_fit_the_body_limit() {
+        let config = Config::default();
+        let manager = EffectManager::new(config.panel.width, config.panel.height);
+        let (_renderer, handle) = Renderer::new(manager, Box::new(MockDriver::new()));
+        let router = create_router(AppState::new(config, handle));
+
+        let side = MAX_SIZE.to_string();
+        let rgb = vec![255; (MAX_SIZE * MAX_SIZE * 3) as usize];
+        let json = serde_json::json!({
+            "data": STANDARD.encode(&rgb),
+            "width": MAX_SIZE,
+            "height": MAX_SIZE,
+            "scale": true,
+        });
+        let requests = [
+            (
+                "application/octet-stream",
+                format!("?width={side}&height={side}&scale=true"),
+                rgb,
+            ),
+            (
+                "application/json",
+                String::new(),
+                json.to_string().into_bytes(),
+            ),
+        ];
+        for (content_type, query, body) in requests {
+            let request = Request::put(format!("/api/frame{query}"))
+                .header(header::CONTENT_TYPE, content_type)
+                .body(Body::from(body))
+                .unwrap();
+            let response = router.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{content_type}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_and_fallback() {
+        let mut config = Config::default();
+        config.stream.timeout_ms = 300;
+
+        let (state, render) = test_util::start(config);
+        let shutdown_rx = render.shutdown();
+        let task = FrameStream::spawn(Arc::clone(&state), shutdown_rx);
+        let router = create_router(Arc::clone(&state));
+        let current = || {
+            state
+                .renderer
+                .call(|m| m.current_effect().map(String::from))
+        };
+
+        // Wait for the stream effect to be registered
+        while state
+            .renderer
+            .call(|m| m.registry().get(EFFECT_NAME).is_none())
+            .await
+            .unwrap()
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        state
+            .renderer
+            .set_effect("fire", EffectParams::default())
+            .await
+            .unwrap();
+
+        let put = |content_type: &str, query: &str, body: Vec<u8>| {
+            let request = Request::put(format!("/api/frame{query}"))
+                .header(header::CONTENT_TYPE, content_type)
+                .body(Body::from(body))
+                .unwrap();
+            router.clone().oneshot(request)
+        };
+
+        let response = put("application/octet-stream", "", vec![0; 5])
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let green = encode_png(1, 1, png::ColorType::Rgb, &[0, 255, 0]);
+        let response = put("image/png", "", green.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = put("image/png", "?scale=true", green).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(current().await.unwrap().as_deref(), Some(EFFECT_NAME));
+
+        let body = serde_json::json!({
+            "data": STANDARD.encode([0, 0, 255]),
+            "width": 1,
+            "height": 1,
+            "scale": true,
+        });
+        let response = put("application/json", "", body.to_string().into_bytes())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state
+            .stream
+            .input
+            .snapshot()
+            .data()
+            .iter()
+            .all(|&c| c == Color::BLUE));
+
+        // Silence brings the previous effect back
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert_eq!(current().await.unwrap().as_deref(), Some("fire"));
+
+        drop(render);
+        task.await.unwrap();
+    }
+}
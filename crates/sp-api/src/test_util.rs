@@ -0,0 +1,57 @@
+//! Test helpers: the API running against a mock panel.
+//!
+//! Enabled for this crate's tests and, through the `test-util` feature, for
+//! crates built on top of it.
+
+use sp_core::Config;
+use sp_effects::EffectManager;
+use sp_hub75::{Driver, MockDriver};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tokio::sync::watch;
+
+use crate::{render::Renderer, state::AppState};
+
+/// Stops the render thread and joins it when dropped.
+pub struct RenderGuard {
+    shutdown: watch::Sender<bool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RenderGuard {
+    /// Shutdown signal flipped on drop, for tasks a test spawns alongside.
+    pub fn shutdown(&self) -> watch::Receiver<bool> {
+        self.shutdown.subscribe()
+    }
+}
+
+impl Drop for RenderGuard {
+    fn drop(&mut self) {
+        let _ = self.shutdown.send(true);
+        if let Some(thread) = self.thread.take() {
+            // Don't turn a failing test into an abort
+            if thread.join().is_err() && !std::thread::panicking() {
+                panic!("render thread panicked");
+            }
+        }
+    }
+}
+
+/// Start a render thread on a mock driver with `config`.
+pub fn start(config: Config) -> (Arc<AppState>, RenderGuard) {
+    let mut driver = MockDriver::new();
+    driver.init().expect("mock driver never fails");
+    let manager = EffectManager::new(config.panel.width, config.panel.height);
+    let (renderer, handle) = Renderer::new(manager, Box::new(driver));
+    let state = AppState::new(config, handle);
+
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    let thread = renderer
+        .spawn(Arc::clone(&state), shutdown_rx)
+        .expect("render thread starts");
+    let guard = RenderGuard {
+        shutdown,
+        thread: Some(thread),
+    };
+    (state, guard)
+}
@@ -1,54 +1,287 @@
 //! Request validation utilities.
 
 use axum::{
-    http::StatusCode,
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
+use std::time::Duration;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
 
 /// API error response.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
+    /// Per-field failures, for validation errors.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+}
+
+/// A single invalid field.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
 }
 
 /// API error types.
 #[derive(Debug)]
 pub enum ApiError {
     BadRequest(String),
-    Validation(String),
+    Validation {
+        message: String,
+        details: Vec<FieldError>,
+    },
+    Unauthorized(String),
+    Forbidden(String),
     NotFound(String),
+    /// Too many requests; retry after the given delay.
+    RateLimited(Duration),
     Internal(String),
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
-        let (status, error, message) = match self {
-            Self::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
-            Self::Validation(msg) => (StatusCode::BAD_REQUEST, "validation_error", msg),
-            Self::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg),
-            Self::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", msg),
+impl ApiError {
+    /// Validation error for a single field.
+    pub fn invalid_field(field: impl Into<String>, message: impl Into<String>) -> Self {
+        let (field, message) = (field.into(), message.into());
+        Self::Validation {
+            message: format!("{field}: {message}"),
+            details: vec![FieldError { field, message }],
+        }
+    }
+
+    fn parts(self) -> (StatusCode, ErrorResponse) {
+        let (status, error, message, details) = match self {
+            Self::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg, Vec::new()),
+            Self::Validation { message, details } => (
+                StatusCode::BAD_REQUEST,
+                "validation_error",
+                message,
+                details,
+            ),
+            Self::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, "unauthorized", msg, Vec::new()),
+            Self::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg, Vec::new()),
+            Self::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg, Vec::new()),
+            Self::RateLimited(retry_after) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate_limited",
+                format!(
+                    "Too many requests, retry in {}s",
+                    retry_after_secs(retry_after)
+                ),
+                Vec::new(),
+            ),
+            Self::Internal(msg) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal_error",
+                msg,
+                Vec::new(),
+            ),
         };
 
-        let body = Json(ErrorResponse {
+        let body = ErrorResponse {
             error: error.to_string(),
             message,
-        });
+            details,
+        };
+        (status, body)
+    }
 
-        (status, body).into_response()
+    /// Build the JSON error body (for transports without an HTTP status, e.g. WebSocket).
+    pub fn into_response_body(self) -> ErrorResponse {
+        self.parts().1
     }
 }
 
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let extra_header = match &self {
+            Self::Unauthorized(_) => {
+                Some((header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer")))
+            }
+            Self::RateLimited(retry_after) => Some((
+                header::RETRY_AFTER,
+                HeaderValue::from(retry_after_secs(*retry_after)),
+            )),
+            _ => None,
+        };
+        let (status, body) = self.parts();
+
+        let mut response = (status, Json(body)).into_response();
+        if let Some((name, value)) = extra_header {
+            response.headers_mut().insert(name, value);
+        }
+        response
+    }
+}
+
+/// `Retry-After` takes whole seconds; round up so clients don't retry early.
+fn retry_after_secs(delay: Duration) -> u64 {
+    delay.as_secs() + u64::from(delay.subsec_nanos() > 0)
+}
+
 impl From<sp_core::Error> for ApiError {
     fn from(err: sp_core::Error) -> Self {
         match err {
             sp_core::Error::EffectNotFound(name) => Self::NotFound(format!("Effect not found: {name}")),
             sp_core::Error::InvalidParameter { field, message } => {
-                Self::Validation(format!("{field}: {message}"))
+                Self::invalid_field(field, message)
             }
             _ => Self::Internal(err.to_string()),
         }
     }
 }
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        match rejection {
+            // Well-formed JSON that doesn't match the expected type
+            JsonRejection::JsonDataError(e) => Self::Validation {
+                message: e.body_text(),
+                details: Vec::new(),
+            },
+            other => Self::BadRequest(other.body_text()),
+        }
+    }
+}
+
+impl From<ValidationErrors> for ApiError {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut details = Vec::new();
+        collect_field_errors(&errors, "", &mut details);
+        details.sort_by(|a, b| a.field.cmp(&b.field));
+
+        let message = details
+            .iter()
+            .map(|d| format!("{}: {}", d.field, d.message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        Self::Validation { message, details }
+    }
+}
+
+fn collect_field_errors(errors: &ValidationErrors, prefix: &str, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let field = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{prefix}.{field}")
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                out.extend(errors.iter().map(|e| FieldError {
+                    field: field.clone(),
+                    message: describe(e),
+                }));
+            }
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(nested, &field, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(nested, &format!("{field}[{index}]"), out);
+                }
+            }
+        }
+    }
+}
+
+/// Human-readable message for a validator error.
+fn describe(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+
+    let param = |name: &str| error.params.get(name).map(ToString::to_string);
+    match (error.code.as_ref(), param("min"), param("max")) {
+        ("range", Some(min), Some(max)) => format!("must be between {min} and {max}"),
+        ("range", Some(min), None) => format!("must be at least {min}"),
+        ("range", None, Some(max)) => format!("must be at most {max}"),
+        ("length", Some(min), Some(max)) => format!("length must be between {min} and {max}"),
+        ("length", Some(min), None) => format!("length must be at least {min}"),
+        ("length", None, Some(max)) => format!("length must be at most {max}"),
+        (code, _, _) => format!("failed '{code}' validation"),
+    }
+}
+
+/// JSON body extractor that also runs `validator` rules.
+///
+/// Parse and validation failures are returned as `ErrorResponse` JSON
+/// instead of axum's plain-text rejections.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state).await?;
+        value.validate()?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Sample {
+        #[validate(length(min = 1, max = 4))]
+        name: String,
+        #[validate(range(min = 1, max = 10))]
+        speed: u32,
+    }
+
+    async fn extract(body: &str) -> Result<Sample, ApiError> {
+        let req = Request::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        ValidatedJson::<Sample>::from_request(req, &())
+            .await
+            .map(|v| v.0)
+    }
+
+    #[tokio::test]
+    async fn test_valid() {
+        let sample = extract(r#"{"name": "ok", "speed": 5}"#).await.unwrap();
+        assert_eq!(sample.speed, 5);
+    }
+
+    #[tokio::test]
+    async fn test_field_details() {
+        let err = extract(r#"{"name": "toolong", "speed": 0}"#)
+            .await
+            .unwrap_err();
+        let body = err.into_response_body();
+
+        assert_eq!(body.error, "validation_error");
+        let fields: Vec<_> = body.details.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(fields, ["name", "speed"]);
+        assert_eq!(body.details[1].message, "must be between 1 and 10");
+    }
+
+    #[tokio::test]
+    async fn test_parse_failures() {
+        let body = extract("{not json").await.unwrap_err().into_response_body();
+        assert_eq!(body.error, "bad_request");
+
+        let body = extract(r#"{"name": "ok", "speed": "fast"}"#)
+            .await
+            .unwrap_err()
+            .into_response_body();
+        assert_eq!(body.error, "validation_error");
+    }
+}
@@ -0,0 +1,436 @@
+//! Outgoing webhooks: signed JSON POSTs of bus events.
+//!
+//! Each delivery is retried with exponential backoff on network errors,
+//! `408`, `429` and `5xx`; other responses are final. Each webhook has its
+//! own bounded queue drained in order by one worker; events arriving while it
+//! is full are dropped and logged as failed. Outcomes are kept in a bounded
+//! delivery log.
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use sp_core::WebhooksConfig;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+
+use crate::{events::Event, state::AppState};
+
+/// Deliveries kept in the log.
+const LOG_CAPACITY: usize = 100;
+
+/// Events waiting for delivery per webhook.
+const QUEUE_CAPACITY: usize = 32;
+
+/// Longest wait between attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A registered webhook.
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub id: u64,
+    pub url: String,
+    pub secret: Option<String>,
+    /// Event types to deliver; all when empty.
+    pub events: Vec<String>,
+}
+
+impl Webhook {
+    fn wants(&self, event: &Event) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == event.kind.name())
+    }
+}
+
+/// Outcome of delivering one event to one webhook.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Delivery {
+    pub webhook_id: u64,
+    pub event_id: u64,
+    pub event: String,
+    pub url: String,
+    pub attempts: u32,
+    pub delivered: bool,
+    /// Last HTTP status received, if any.
+    pub status: Option<u16>,
+    /// Last error, if the delivery failed.
+    pub error: Option<String>,
+}
+
+/// Registered webhooks and their delivery log.
+pub struct Webhooks {
+    hooks: RwLock<Vec<Webhook>>,
+    next_id: AtomicU64,
+    log: Mutex<VecDeque<Delivery>>,
+    client: reqwest::Client,
+    max_attempts: u32,
+    backoff: Duration,
+}
+
+impl Webhooks {
+    /// Create the registry with the webhooks from `[webhooks]`.
+    pub fn new(config: &WebhooksConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .user_agent(concat!("super-pixeled/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+
+        let webhooks = Self {
+            hooks: RwLock::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+            log: Mutex::new(VecDeque::new()),
+            client,
+            max_attempts: config.max_attempts.max(1),
+            backoff: Duration::from_millis(config.backoff_ms),
+        };
+        for hook in &config.hooks {
+            webhooks.add(hook.url.clone(), hook.secret.clone(), hook.events.clone());
+        }
+        webhooks
+    }
+
+    /// All registered webhooks.
+    pub fn list(&self) -> Vec<Webhook> {
+        self.hooks.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn get(&self, id: u64) -> Option<Webhook> {
+        self.list().into_iter().find(|hook| hook.id == id)
+    }
+
+    /// Register a webhook and return it with its id.
+    pub fn add(&self, url: String, secret: Option<String>, events: Vec<String>) -> Webhook {
+        let hook = Webhook {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            url,
+            secret,
+            events,
+        };
+        let mut hooks = self.hooks.write().unwrap_or_else(|e| e.into_inner());
+        hooks.push(hook.clone());
+        hook
+    }
+
+    /// Replace a webhook's settings; `None` if the id is unknown.
+    pub fn update(
+        &self,
+        id: u64,
+        url: String,
+        secret: Option<String>,
+        events: Vec<String>,
+    ) -> Option<Webhook> {
+        let mut hooks = self.hooks.write().unwrap_or_else(|e| e.into_inner());
+        let hook = hooks.iter_mut().find(|hook| hook.id == id)?;
+        *hook = Webhook {
+            id,
+            url,
+            secret,
+            events,
+        };
+        Some(hook.clone())
+    }
+
+    /// Unregister a webhook; false if the id is unknown.
+    pub fn remove(&self, id: u64) -> bool {
+        let mut hooks = self.hooks.write().unwrap_or_else(|e| e.into_inner());
+        let before = hooks.len();
+        hooks.retain(|hook| hook.id != id);
+        hooks.len() != before
+    }
+
+    /// Recent deliveries, newest first.
+    pub fn deliveries(&self) -> Vec<Delivery> {
+        let log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+        log.iter().rev().cloned().collect()
+    }
+
+    fn record(&self, delivery: Delivery) {
+        let mut log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+        if log.len() == LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(delivery);
+    }
+
+    /// Forward bus events to matching webhooks until `shutdown` flips.
+    pub fn spawn(state: Arc<AppState>, mut shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+        let mut events = state.events.subscribe();
+        let mut queues: HashMap<u64, mpsc::Sender<Arc<Event>>> = HashMap::new();
+
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    event = events.recv() => event,
+                    _ = shutdown.changed() => break,
+                };
+                let event = match event {
+                    Ok(event) => Arc::new(event),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Webhook dispatcher lagged behind events");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                let hooks = state.webhooks.list();
+                queues.retain(|id, _| hooks.iter().any(|hook| hook.id == *id));
+                for hook in hooks.iter().filter(|hook| hook.wants(&event)) {
+                    let queue = queues
+                        .entry(hook.id)
+                        .or_insert_with(|| Self::spawn_worker(Arc::clone(&state), hook.id));
+                    if queue.try_send(Arc::clone(&event)).is_err() {
+                        state.webhooks.drop_event(hook, &event);
+                    }
+                }
+            }
+        })
+    }
+
+    /// Deliver queued events to webhook `id` one at a time, until its queue
+    /// is dropped or the webhook is removed.
+    fn spawn_worker(state: Arc<AppState>, id: u64) -> mpsc::Sender<Arc<Event>> {
+        let (tx, mut rx) = mpsc::channel::<Arc<Event>>(QUEUE_CAPACITY);
+        tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                // Settings may have changed since the event was queued
+                let Some(hook) = state.webhooks.get(id) else {
+                    break;
+                };
+                if hook.wants(&event) {
+                    state.webhooks.deliver(&hook, &event).await;
+                }
+            }
+        });
+        tx
+    }
+
+    /// Log `event` as not delivered because the queue of `hook` is full.
+    fn drop_event(&self, hook: &Webhook, event: &Event) {
+        tracing::warn!(
+            url = %hook.url,
+            event = event.kind.name(),
+            "Webhook queue full, dropping event"
+        );
+        self.record(Delivery {
+            webhook_id: hook.id,
+            event_id: event.id,
+            event: event.kind.name().to_string(),
+            url: hook.url.clone(),
+            attempts: 0,
+            delivered: false,
+            status: None,
+            error: Some("queue full".to_string()),
+        });
+    }
+
+    /// POST `event` to `hook`, retrying transient failures.
+    async fn deliver(&self, hook: &Webhook, event: &Event) {
+        let body = match serde_json::to_vec(event) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to encode webhook event");
+                return;
+            }
+        };
+        let mut delivery = Delivery {
+            webhook_id: hook.id,
+            event_id: event.id,
+            event: event.kind.name().to_string(),
+            url: hook.url.clone(),
+            attempts: 0,
+            delivered: false,
+            status: None,
+            error: None,
+        };
+
+        let mut backoff = self.backoff;
+        loop {
+            delivery.attempts += 1;
+            let mut request = self
+                .client
+                .post(&hook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header("X-Super-Pixeled-Event", event.kind.name())
+                .header("X-Super-Pixeled-Delivery", event.id);
+            if let Some(secret) = &hook.secret {
+                request = request.header("X-Super-Pixeled-Signature", sign(secret, &body));
+            }
+
+            let retry = match request.body(body.clone()).send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    delivery.status = Some(status.as_u16());
+                    delivery.delivered = status.is_success();
+                    delivery.error = (!status.is_success()).then(|| format!("HTTP {status}"));
+                    status.is_server_error()
+                        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+                        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                }
+                Err(e) => {
+                    delivery.error = Some(e.to_string());
+                    true
+                }
+            };
+
+            if !retry || delivery.attempts >= self.max_attempts {
+                break;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+
+        if !delivery.delivered {
+            tracing::warn!(
+                url = %hook.url,
+                attempts = delivery.attempts,
+                error = delivery.error.as_deref().unwrap_or_default(),
+                "Webhook delivery failed"
+            );
+        }
+        self.record(delivery);
+    }
+}
+
+/// `sha256=<hex>` HMAC of `body`, as sent in `X-Super-Pixeled-Signature`.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("sha256={hex}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventKind;
+    use crate::{AppState, Renderer};
+    use axum::{http::HeaderMap, http::StatusCode, routing::post, Router};
+    use sp_core::Config;
+    use sp_effects::EffectManager;
+    use sp_hub75::MockDriver;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_sign() {
+        assert_eq!(
+            sign("key", b"The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[test]
+    fn test_registry() {
+        let webhooks = Webhooks::new(&WebhooksConfig::default());
+        let hook = webhooks.add("http://a.test/".into(), None, vec![]);
+        assert_eq!(webhooks.list().len(), 1);
+
+        let updated = webhooks
+            .update(
+                hook.id,
+                "http://b.test/".into(),
+                None,
+                vec!["text_shown".into()],
+            )
+            .unwrap();
+        assert_eq!(webhooks.get(hook.id).unwrap().url, updated.url);
+
+        assert!(webhooks.remove(hook.id));
+        assert!(!webhooks.remove(hook.id));
+        assert!(webhooks
+            .update(hook.id, String::new(), None, vec![])
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deliver_retries_and_signs() {
+        // Receiver failing the first request, then checking the signature
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let app = Router::new().route(
+            "/hook",
+            post(
+                move |headers: HeaderMap, body: axum::body::Bytes| async move {
+                    if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                        return StatusCode::SERVICE_UNAVAILABLE;
+                    }
+                    let signature = headers["x-super-pixeled-signature"].to_str().unwrap();
+                    assert_eq!(signature, sign("s3cret", &body));
+                    assert_eq!(headers["x-super-pixeled-event"], "text_shown");
+                    StatusCode::NO_CONTENT
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let config = WebhooksConfig {
+            backoff_ms: 10,
+            ..Default::default()
+        };
+        let webhooks = Webhooks::new(&config);
+        let hook = webhooks.add(url, Some("s3cret".into()), vec!["text_shown".into()]);
+        let event = Event {
+            id: 7,
+            timestamp: 0,
+            kind: EventKind::TextShown { text: "Hi".into() },
+        };
+        assert!(hook.wants(&event));
+
+        webhooks.deliver(&hook, &event).await;
+
+        let log = webhooks.deliveries();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(log.len(), 1);
+        assert!(log[0].delivered);
+        assert_eq!(log[0].attempts, 2);
+        assert_eq!(log[0].status, Some(204));
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops_events() {
+        // Accepts connections but never answers, so the worker stays busy
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let config = Config::default();
+        let manager = EffectManager::new(config.panel.width, config.panel.height);
+        let (_renderer, handle) = Renderer::new(manager, Box::new(MockDriver::new()));
+        let state = AppState::new(config, handle);
+        let hook = state.webhooks.add(url, None, vec![]);
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let dispatcher = Webhooks::spawn(Arc::clone(&state), shutdown_rx);
+
+        let sent = QUEUE_CAPACITY + 10;
+        for i in 0..sent {
+            state.events.publish(EventKind::TextShown {
+                text: i.to_string(),
+            });
+        }
+        // A full queue, maybe one event in flight; the rest are dropped
+        let dropped = sent - QUEUE_CAPACITY - 1..=sent - QUEUE_CAPACITY;
+        for _ in 0..100 {
+            if state.webhooks.deliveries().len() >= *dropped.start() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let log = state.webhooks.deliveries();
+        assert!(dropped.contains(&log.len()));
+        assert!(log
+            .iter()
+            .all(|d| d.webhook_id == hook.id && d.attempts == 0));
+        assert_eq!(log[0].error.as_deref(), Some("queue full"));
+
+        shutdown_tx.send(true).unwrap();
+        dispatcher.await.unwrap();
+    }
+}
@@ -2,6 +2,8 @@
 
 use config::{Config as ConfigBuilder, Environment, File};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
 use validator::Validate;
 
 use crate::Result;
@@ -17,6 +19,28 @@ pub struct Config {
     #[validate(nested)]
     pub effects: EffectsConfig,
     pub logging: LoggingConfig,
+    #[serde(default)]
+    #[validate(nested)]
+    pub mqtt: MqttConfig,
+    #[serde(default)]
+    #[validate(nested)]
+    pub webhooks: WebhooksConfig,
+    /// Inbound webhook adapters, served at `/hooks/<name>`.
+    #[serde(default)]
+    #[validate(nested)]
+    pub hooks: HashMap<String, InboundHookConfig>,
+    #[serde(default)]
+    #[validate(nested)]
+    pub dmx: DmxConfig,
+    #[serde(default)]
+    #[validate(nested)]
+    pub wled: WledConfig,
+    #[serde(default)]
+    #[validate(nested)]
+    pub stream: StreamConfig,
+    #[serde(default)]
+    #[validate(nested)]
+    pub audio: AudioConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
@@ -24,6 +48,119 @@ pub struct ServerConfig {
     pub host: String,
     #[validate(range(min = 1, max = 65535))]
     pub port: u16,
+    /// Origins allowed to call the API from a browser; `"*"` allows any.
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+    #[serde(default)]
+    #[validate(nested)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    #[validate(nested)]
+    pub rate_limit: RateLimitConfig,
+}
+
+/// Token-bucket limits on state-changing requests, per token or client IP.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RateLimitConfig {
+    /// Sustained requests per second; 0 disables rate limiting.
+    #[serde(default = "default_rate_per_second")]
+    #[validate(range(max = 10000))]
+    pub per_second: u32,
+    /// Requests allowed in a burst above the sustained rate.
+    #[serde(default = "default_rate_burst")]
+    #[validate(range(min = 1, max = 10000))]
+    pub burst: u32,
+}
+
+fn default_rate_per_second() -> u32 {
+    10
+}
+
+fn default_rate_burst() -> u32 {
+    20
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            per_second: default_rate_per_second(),
+            burst: default_rate_burst(),
+        }
+    }
+}
+
+/// Bearer-token authentication; disabled while no token is configured.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct AuthConfig {
+    #[serde(default)]
+    #[validate(nested)]
+    pub tokens: Vec<TokenConfig>,
+}
+
+impl AuthConfig {
+    /// Whether requests must carry a token.
+    pub fn enabled(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
+    /// Look up the entry matching a presented token.
+    pub fn find(&self, token: &str) -> Option<&TokenConfig> {
+        // Compare against every entry so timing doesn't reveal which one matched
+        let mut found = None;
+        for entry in &self.tokens {
+            if constant_time_eq(&entry.token, token) && found.is_none() {
+                found = Some(entry);
+            }
+        }
+        found
+    }
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct TokenConfig {
+    /// Label used in logs; never the token itself.
+    pub name: String,
+    #[validate(length(min = 16))]
+    pub token: String,
+    pub scopes: Vec<Scope>,
+}
+
+impl TokenConfig {
+    /// Whether this token may perform actions requiring `required`.
+    pub fn allows(&self, required: Scope) -> bool {
+        self.scopes.iter().any(|&scope| scope >= required)
+    }
+}
+
+/// API permission levels; each one includes those below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Read state, effect list, preview and metrics.
+    Read,
+    /// Change the effect, its parameters, text and brightness.
+    Control,
+    /// Manage scripts and webhooks.
+    Admin,
+}
+
+impl Scope {
+    /// Name as written in the configuration.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Control => "control",
+            Self::Admin => "admin",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
@@ -34,12 +171,23 @@ pub struct PanelConfig {
     pub height: u32,
     #[validate(range(min = 0, max = 100))]
     pub brightness: u8,
+    /// Render loop frame rate; effects may ask for less.
+    #[serde(default = "default_target_fps")]
+    #[validate(range(min = 1, max = 240))]
+    pub target_fps: u32,
+}
+
+fn default_target_fps() -> u32 {
+    60
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HardwareConfig {
     pub mock: bool,
     pub gpio_slowdown: u8,
+    /// `SCHED_FIFO` priority for the render thread (1-99, 0 = normal).
+    #[serde(default)]
+    pub render_priority: u8,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
@@ -47,6 +195,435 @@ pub struct EffectsConfig {
     pub default: String,
     #[validate(range(min = 0, max = 5000))]
     pub transition_ms: u32,
+    /// CPU-time budget per script `tick` call.
+    #[serde(default = "default_script_tick_ms")]
+    #[validate(range(min = 1, max = 100))]
+    pub script_tick_ms: u32,
+    /// Directory scanned for `.wasm` plugin effects at startup.
+    #[serde(default = "default_plugins_dir")]
+    pub plugins_dir: String,
+    /// Fuel (roughly, instructions) a plugin may use per call.
+    #[serde(default = "default_plugin_fuel")]
+    #[validate(range(min = 1000))]
+    pub plugin_fuel: u64,
+}
+
+fn default_script_tick_ms() -> u32 {
+    10
+}
+
+fn default_plugins_dir() -> String {
+    "plugins".to_string()
+}
+
+fn default_plugin_fuel() -> u64 {
+    5_000_000
+}
+
+/// MQTT broker connection and topic layout.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct MqttConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_mqtt_host")]
+    pub host: String,
+    #[serde(default = "default_mqtt_port")]
+    #[validate(range(min = 1))]
+    pub port: u16,
+    #[serde(default = "default_mqtt_client_id")]
+    #[validate(length(min = 1, max = 23))]
+    pub client_id: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Prefix of the command, state and availability topics.
+    #[serde(default = "default_base_topic")]
+    #[validate(length(min = 1))]
+    pub base_topic: String,
+    /// Home Assistant discovery prefix; empty disables discovery.
+    #[serde(default = "default_discovery_prefix")]
+    pub discovery_prefix: String,
+}
+
+fn default_mqtt_host() -> String {
+    "localhost".to_string()
+}
+
+fn default_mqtt_port() -> u16 {
+    1883
+}
+
+fn default_mqtt_client_id() -> String {
+    "super-pixeled".to_string()
+}
+
+fn default_base_topic() -> String {
+    "super-pixeled".to_string()
+}
+
+fn default_discovery_prefix() -> String {
+    "homeassistant".to_string()
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: default_mqtt_host(),
+            port: default_mqtt_port(),
+            client_id: default_mqtt_client_id(),
+            username: None,
+            password: None,
+            base_topic: default_base_topic(),
+            discovery_prefix: default_discovery_prefix(),
+        }
+    }
+}
+
+/// Outgoing webhooks and their delivery policy.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct WebhooksConfig {
+    /// Webhooks registered at startup; more can be added over the API.
+    #[serde(default)]
+    #[validate(nested)]
+    pub hooks: Vec<WebhookConfig>,
+    /// Delivery attempts per event, including the first.
+    #[serde(default = "default_webhook_attempts")]
+    #[validate(range(min = 1, max = 10))]
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled after each failure.
+    #[serde(default = "default_webhook_backoff_ms")]
+    #[validate(range(min = 10, max = 60000))]
+    pub backoff_ms: u64,
+    /// Per-request timeout.
+    #[serde(default = "default_webhook_timeout_ms")]
+    #[validate(range(min = 100, max = 60000))]
+    pub timeout_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct WebhookConfig {
+    #[validate(url)]
+    pub url: String,
+    /// Key for the `X-Super-Pixeled-Signature` HMAC-SHA256 header.
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Event types to deliver; all when empty.
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+fn default_webhook_attempts() -> u32 {
+    5
+}
+
+fn default_webhook_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_webhook_timeout_ms() -> u64 {
+    5000
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            hooks: Vec::new(),
+            max_attempts: default_webhook_attempts(),
+            backoff_ms: default_webhook_backoff_ms(),
+            timeout_ms: default_webhook_timeout_ms(),
+        }
+    }
+}
+
+/// Inbound webhook adapter mapping payloads to panel actions.
+///
+/// `github` is served at `/hooks/github` and matches rules against the
+/// `X-GitHub-Event` header; other names take any JSON payload.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct InboundHookConfig {
+    /// Key for the `X-Hub-Signature-256` HMAC-SHA256 header. Signed hooks
+    /// need no bearer token; unsigned ones need the `control` scope.
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Rules tried in order; the first match is applied.
+    #[serde(default)]
+    #[validate(nested)]
+    pub rules: Vec<HookRule>,
+}
+
+/// Payload pattern and the action it triggers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct HookRule {
+    /// Event name to match: `X-GitHub-Event`, or `X-Event` on generic hooks.
+    #[serde(default)]
+    pub event: Option<String>,
+    /// Values the payload must contain. Tables match nested objects and
+    /// arrays match any of their values.
+    #[serde(default)]
+    pub when: serde_json::Map<String, serde_json::Value>,
+    /// Effect to start.
+    #[serde(default)]
+    pub effect: Option<String>,
+    /// Params for `effect`.
+    #[serde(default)]
+    pub params: serde_json::Map<String, serde_json::Value>,
+    /// Text to show; `{{dotted.path}}` placeholders are read from the payload.
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Text or effect color; flashes this solid color when there is neither.
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
+    #[serde(default)]
+    pub scroll: bool,
+    /// Restore the previous effect after this long; the action stays otherwise.
+    #[serde(default)]
+    #[validate(range(min = 1, max = 86400))]
+    pub duration_secs: Option<u64>,
+}
+
+/// E1.31 (sACN) and Art-Net receiver for lighting consoles.
+///
+/// Universes are filled one after another from `start_universe`, each
+/// carrying as many whole pixels as fit after `channel_offset`; pixels run
+/// left to right, top to bottom.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct DmxConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_udp_bind")]
+    pub bind: String,
+    #[serde(default = "default_true")]
+    pub e131: bool,
+    #[serde(default = "default_e131_port")]
+    pub e131_port: u16,
+    #[serde(default = "default_true")]
+    pub artnet: bool,
+    #[serde(default = "default_artnet_port")]
+    pub artnet_port: u16,
+    /// Universe holding the top-left pixel, for both protocols.
+    #[serde(default = "default_start_universe")]
+    pub start_universe: u16,
+    /// DMX channels skipped at the start of each universe.
+    #[serde(default)]
+    #[validate(range(max = 509))]
+    pub channel_offset: u16,
+    #[serde(default)]
+    pub pixel_order: PixelOrder,
+    /// Silence after which the previous effect comes back.
+    #[serde(default = "default_live_timeout_ms")]
+    #[validate(range(min = 100, max = 60000))]
+    pub timeout_ms: u64,
+    /// Priority given to Art-Net sources, which carry none (E1.31: 0-200).
+    #[serde(default = "default_artnet_priority")]
+    #[validate(range(max = 200))]
+    pub artnet_priority: u8,
+}
+
+/// Order of the color channels of each pixel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PixelOrder {
+    #[default]
+    Rgb,
+    Rbg,
+    Grb,
+    Gbr,
+    Brg,
+    Bgr,
+}
+
+impl PixelOrder {
+    /// Reorder a channel triplet into `[r, g, b]`.
+    pub fn to_rgb(self, [a, b, c]: [u8; 3]) -> [u8; 3] {
+        match self {
+            Self::Rgb => [a, b, c],
+            Self::Rbg => [a, c, b],
+            Self::Grb => [b, a, c],
+            Self::Gbr => [c, a, b],
+            Self::Brg => [b, c, a],
+            Self::Bgr => [c, b, a],
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_udp_bind() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_e131_port() -> u16 {
+    5568
+}
+
+fn default_artnet_port() -> u16 {
+    6454
+}
+
+fn default_start_universe() -> u16 {
+    1
+}
+
+fn default_live_timeout_ms() -> u64 {
+    2500
+}
+
+fn default_artnet_priority() -> u8 {
+    100
+}
+
+impl Default for DmxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_udp_bind(),
+            e131: true,
+            e131_port: default_e131_port(),
+            artnet: true,
+            artnet_port: default_artnet_port(),
+            start_universe: default_start_universe(),
+            channel_offset: 0,
+            pixel_order: PixelOrder::default(),
+            timeout_ms: default_live_timeout_ms(),
+            artnet_priority: default_artnet_priority(),
+        }
+    }
+}
+
+/// DDP and WLED UDP realtime input, with a WLED-compatible `/json/info`.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct WledConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_udp_bind")]
+    pub bind: String,
+    #[serde(default = "default_true")]
+    pub ddp: bool,
+    #[serde(default = "default_ddp_port")]
+    pub ddp_port: u16,
+    /// WLED realtime protocols: WARLS, DRGB, DRGBW and DNRGB.
+    #[serde(default = "default_true")]
+    pub realtime: bool,
+    #[serde(default = "default_realtime_port")]
+    pub realtime_port: u16,
+    /// Silence after which the previous effect comes back, for DDP; WLED
+    /// realtime packets carry their own.
+    #[serde(default = "default_live_timeout_ms")]
+    #[validate(range(min = 100, max = 60000))]
+    pub timeout_ms: u64,
+    /// Device name shown by WLED tools.
+    #[serde(default = "default_wled_name")]
+    #[validate(length(min = 1, max = 32))]
+    pub name: String,
+}
+
+fn default_ddp_port() -> u16 {
+    4048
+}
+
+fn default_realtime_port() -> u16 {
+    21324
+}
+
+fn default_wled_name() -> String {
+    "Super Pixeled".to_string()
+}
+
+impl Default for WledConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_udp_bind(),
+            ddp: true,
+            ddp_port: default_ddp_port(),
+            realtime: true,
+            realtime_port: default_realtime_port(),
+            timeout_ms: default_live_timeout_ms(),
+            name: default_wled_name(),
+        }
+    }
+}
+
+/// Frames pushed by external renderers over `PUT /api/frame` and `/ws/frames`.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct StreamConfig {
+    /// Time without frames after which `on_stop` applies.
+    #[serde(default = "default_live_timeout_ms")]
+    #[validate(range(min = 100, max = 60000))]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub on_stop: StreamStopPolicy,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: default_live_timeout_ms(),
+            on_stop: StreamStopPolicy::default(),
+        }
+    }
+}
+
+/// What the panel shows once pushed frames stop.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamStopPolicy {
+    /// Keep showing the last frame.
+    Hold,
+    /// Turn the panel off.
+    Blank,
+    /// Go back to the effect shown before the stream started.
+    #[default]
+    Fallback,
+}
+
+/// Audio input of the `spectrum` effect.
+///
+/// Raw PCM (signed 16-bit little-endian mono) arrives over `/ws/audio` and,
+/// when `udp` is set, as UDP datagrams.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct AudioConfig {
+    /// Directory WAV files are played from; `path` params are relative to it.
+    #[serde(default = "default_audio_dir")]
+    pub dir: String,
+    /// Sample rate of the pushed PCM.
+    #[serde(default = "default_sample_rate")]
+    #[validate(range(min = 1000, max = 192000))]
+    pub sample_rate: u32,
+    #[serde(default)]
+    pub udp: bool,
+    #[serde(default = "default_udp_bind")]
+    pub bind: String,
+    #[serde(default = "default_audio_port")]
+    pub udp_port: u16,
+}
+
+fn default_audio_dir() -> String {
+    "audio".to_string()
+}
+
+fn default_sample_rate() -> u32 {
+    44_100
+}
+
+fn default_audio_port() -> u16 {
+    7777
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            dir: default_audio_dir(),
+            sample_rate: default_sample_rate(),
+            udp: false,
+            bind: default_udp_bind(),
+            udp_port: default_audio_port(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,9 +640,16 @@ impl Config {
     /// 2. config/local.toml (optional, gitignored)
     /// 3. config/default.toml
     pub fn load() -> Result<Self> {
+        Self::load_from("config")
+    }
+
+    /// Load `default.toml` and `local.toml` from `dir`, then the environment,
+    /// with the same priority as [`load`](Self::load).
+    pub fn load_from(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
         let config = ConfigBuilder::builder()
-            .add_source(File::with_name("config/default").required(false))
-            .add_source(File::with_name("config/local").required(false))
+            .add_source(File::from(dir.join("default")).required(false))
+            .add_source(File::from(dir.join("local")).required(false))
             .add_source(Environment::with_prefix("SP").separator("__"))
             .build()?;
 
@@ -86,24 +670,39 @@ impl Default for Config {
             server: ServerConfig {
                 host: "0.0.0.0".to_string(),
                 port: 3000,
+                cors_origins: Vec::new(),
+                auth: AuthConfig::default(),
+                rate_limit: RateLimitConfig::default(),
             },
             panel: PanelConfig {
                 width: 64,
                 height: 32,
                 brightness: 80,
+                target_fps: default_target_fps(),
             },
             hardware: HardwareConfig {
                 mock: false,
                 gpio_slowdown: 2,
+                render_priority: 0,
             },
             effects: EffectsConfig {
                 default: "fire".to_string(),
                 transition_ms: 500,
+                script_tick_ms: default_script_tick_ms(),
+                plugins_dir: default_plugins_dir(),
+                plugin_fuel: default_plugin_fuel(),
             },
             logging: LoggingConfig {
                 level: "info".to_string(),
                 format: "pretty".to_string(),
             },
+            mqtt: MqttConfig::default(),
+            webhooks: WebhooksConfig::default(),
+            hooks: HashMap::new(),
+            dmx: DmxConfig::default(),
+            wled: WledConfig::default(),
+            stream: StreamConfig::default(),
+            audio: AudioConfig::default(),
         }
     }
 }
@@ -127,5 +726,159 @@ mod tests {
 
         let result = config.validate();
         assert!(result.is_err());
+
+        let mut config = Config::default();
+        config.panel.target_fps = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_auth_tokens() {
+        let auth: AuthConfig = toml::from_str(
+            r#"
+            [[tokens]]
+            name = "dashboard"
+            token = "0123456789abcdef"
+            scopes = ["control"]
+            "#,
+        )
+        .unwrap();
+        assert!(auth.enabled());
+        assert!(!AuthConfig::default().enabled());
+
+        let entry = auth.find("0123456789abcdef").unwrap();
+        assert_eq!(entry.name, "dashboard");
+        assert!(entry.allows(Scope::Read));
+        assert!(entry.allows(Scope::Control));
+        assert!(!entry.allows(Scope::Admin));
+        assert!(auth.find("0123456789abcdeX").is_none());
+        assert!(auth.find("short").is_none());
+
+        let mut config = Config::default();
+        config.server.auth = auth;
+        config.server.auth.tokens[0].token = "short".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_inbound_hooks() {
+        let hooks: HashMap<String, InboundHookConfig> = toml::from_str(
+            r#"
+            [github]
+            secret = "s3cret"
+
+            [[github.rules]]
+            event = "workflow_run"
+            when = { action = "completed", workflow_run = { conclusion = ["failure", "timed_out"] } }
+            effect = "fire"
+            color = [255, 0, 0]
+            duration_secs = 30
+            "#,
+        )
+        .unwrap();
+
+        let rule = &hooks["github"].rules[0];
+        assert_eq!(rule.event.as_deref(), Some("workflow_run"));
+        assert_eq!(rule.when["workflow_run"]["conclusion"][1], "timed_out");
+
+        let mut config = Config {
+            hooks,
+            ..Config::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.hooks.get_mut("github").unwrap().rules[0].duration_secs = Some(0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_load_from_files() {
+        // The shipped defaults must load on their own
+        let shipped = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config");
+        let config = Config::load_from(shipped).unwrap();
+        assert_eq!(config.server.port, 3000);
+        assert!(!config.mqtt.enabled);
+
+        let dir = std::env::temp_dir().join(format!("sp-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config/default.toml"),
+            dir.join("default.toml"),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("local.toml"),
+            r#"
+            [server]
+            cors_origins = ["https://dash.example"]
+
+            [[server.auth.tokens]]
+            name = "dashboard"
+            token = "0123456789abcdef"
+            scopes = ["control"]
+
+            [mqtt]
+            enabled = true
+            host = "broker.lan"
+
+            [[webhooks.hooks]]
+            url = "https://example.com/hook"
+            events = ["effect_changed"]
+
+            [hooks.github]
+            secret = "s3cret"
+            [[hooks.github.rules]]
+            event = "push"
+            effect = "fire"
+            params = { intensity = 0.5 }
+
+            [dmx]
+            enabled = true
+            start_universe = 2
+
+            [wled]
+            enabled = true
+            name = "Desk"
+            "#,
+        )
+        .unwrap();
+
+        let config = Config::load_from(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(config.server.port, 3000);
+        assert_eq!(config.server.cors_origins, ["https://dash.example"]);
+        assert_eq!(config.server.auth.tokens[0].name, "dashboard");
+        assert!(config.mqtt.enabled);
+        assert_eq!(config.mqtt.host, "broker.lan");
+        assert_eq!(config.mqtt.port, 1883);
+        assert_eq!(config.webhooks.hooks[0].events, ["effect_changed"]);
+        assert_eq!(config.hooks["github"].rules[0].params["intensity"], 0.5);
+        assert_eq!(config.dmx.start_universe, 2);
+        assert_eq!(config.wled.name, "Desk");
+    }
+
+    #[test]
+    fn test_dmx() {
+        let dmx: DmxConfig = toml::from_str(
+            r#"
+            enabled = true
+            artnet = false
+            start_universe = 3
+            pixel_order = "grb"
+            "#,
+        )
+        .unwrap();
+        assert!(dmx.e131 && !dmx.artnet);
+        assert_eq!(dmx.e131_port, 5568);
+        assert_eq!(dmx.start_universe, 3);
+        assert_eq!(dmx.pixel_order.to_rgb([1, 2, 3]), [2, 1, 3]);
+        assert!(dmx.validate().is_ok());
+
+        let dmx = DmxConfig {
+            channel_offset: 510,
+            ..DmxConfig::default()
+        };
+        assert!(dmx.validate().is_err());
     }
 }
@@ -12,6 +12,11 @@ mod error;
 mod point;
 
 pub use color::Color;
-pub use config::{Config, EffectsConfig, HardwareConfig, LoggingConfig, PanelConfig, ServerConfig};
+pub use config::{
+    AudioConfig, AuthConfig, Config, DmxConfig, EffectsConfig, HardwareConfig, HookRule,
+    InboundHookConfig, LoggingConfig, MqttConfig, PanelConfig, PixelOrder, RateLimitConfig, Scope,
+    ServerConfig, StreamConfig, StreamStopPolicy, TokenConfig, WebhookConfig, WebhooksConfig,
+    WledConfig,
+};
 pub use error::{Error, Result};
 pub use point::Point;
@@ -0,0 +1,78 @@
+//! Art-Net `ArtDmx` packet parsing.
+//!
+//! Other opcodes, including `ArtPoll` discovery, are ignored: consoles must
+//! send to the panel's address or broadcast.
+
+use std::net::IpAddr;
+
+use crate::packet::{DmxPacket, Source, UNIVERSE_SIZE};
+
+/// Standard Art-Net port.
+pub const PORT: u16 = 6454;
+
+const ID: &[u8; 8] = b"Art-Net\0";
+const OP_DMX: u16 = 0x5000;
+const HEADER_LEN: usize = 18;
+
+/// Parse an `ArtDmx` packet from `sender`, giving it `priority`.
+///
+/// The 15-bit port address is used as the universe.
+pub fn parse(packet: &[u8], sender: IpAddr, priority: u8) -> Option<DmxPacket<'_>> {
+    if packet.len() < HEADER_LEN
+        || packet[..8] != ID[..]
+        || u16::from_le_bytes([packet[8], packet[9]]) != OP_DMX
+    {
+        return None;
+    }
+
+    let universe = u16::from_le_bytes([packet[14], packet[15] & 0x7f]);
+    let len = u16::from_be_bytes([packet[16], packet[17]]) as usize;
+    let end = (HEADER_LEN + len.min(UNIVERSE_SIZE)).min(packet.len());
+
+    Some(DmxPacket {
+        source: Source::ArtNet(sender),
+        universe,
+        priority,
+        data: &packet[HEADER_LEN..end],
+        terminated: false,
+    })
+}
+
+/// Build an `ArtDmx` packet, as a console would send it.
+#[cfg(test)]
+pub(crate) fn encode(universe: u16, data: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(HEADER_LEN + data.len());
+    packet.extend_from_slice(ID);
+    packet.extend_from_slice(&OP_DMX.to_le_bytes());
+    packet.extend_from_slice(&14u16.to_be_bytes());
+    packet.extend_from_slice(&[0, 0]);
+    packet.extend_from_slice(&universe.to_le_bytes());
+    packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    packet.extend_from_slice(data);
+    packet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_parse() {
+        let sender = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let packet = encode(0x0123, &[9, 8, 7, 6]);
+        let parsed = parse(&packet, sender, 100).unwrap();
+        assert_eq!(parsed.source, Source::ArtNet(sender));
+        assert_eq!((parsed.universe, parsed.priority), (0x0123, 100));
+        assert_eq!(parsed.data, [9, 8, 7, 6]);
+
+        // A length past the end of the datagram is clamped
+        let truncated = &packet[..packet.len() - 2];
+        assert_eq!(parse(truncated, sender, 100).unwrap().data, [9, 8]);
+
+        let mut poll = packet.clone();
+        poll[8..10].copy_from_slice(&0x2000u16.to_le_bytes());
+        assert!(parse(&poll, sender, 100).is_none());
+        assert!(parse(b"Art-Net", sender, 100).is_none());
+    }
+}
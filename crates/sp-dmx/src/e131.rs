@@ -0,0 +1,129 @@
+//! E1.31 (Streaming ACN) data packet parsing.
+//!
+//! Only data packets with the null start code are read; universe sync and
+//! discovery packets, and preview data meant for visualizers, are ignored.
+
+use crate::packet::{DmxPacket, Source, UNIVERSE_SIZE};
+
+/// Standard E1.31 port.
+pub const PORT: u16 = 5568;
+
+const ACN_IDENTIFIER: &[u8; 12] = b"ASC-E1.17\0\0\0";
+const VECTOR_ROOT_DATA: u32 = 0x0000_0004;
+const VECTOR_FRAMING_DATA: u32 = 0x0000_0002;
+const VECTOR_DMP_SET_PROPERTY: u8 = 0x02;
+
+/// Offset of the first property value (the DMX start code).
+const START_CODE: usize = 125;
+
+const OPTION_PREVIEW: u8 = 0x80;
+const OPTION_TERMINATED: u8 = 0x40;
+
+/// Multicast group carrying `universe`: `239.255.<hi>.<lo>`.
+pub fn multicast_group(universe: u16) -> std::net::Ipv4Addr {
+    let [hi, lo] = universe.to_be_bytes();
+    std::net::Ipv4Addr::new(239, 255, hi, lo)
+}
+
+/// Parse an E1.31 data packet.
+pub fn parse(packet: &[u8]) -> Option<DmxPacket<'_>> {
+    if packet.len() < START_CODE + 1
+        || packet[4..16] != ACN_IDENTIFIER[..]
+        || read_u32(packet, 18) != VECTOR_ROOT_DATA
+        || read_u32(packet, 40) != VECTOR_FRAMING_DATA
+        || packet[117] != VECTOR_DMP_SET_PROPERTY
+    {
+        return None;
+    }
+
+    let options = packet[112];
+    if options & OPTION_PREVIEW != 0 {
+        return None;
+    }
+    let terminated = options & OPTION_TERMINATED != 0;
+    // Alternate start codes carry non-level data (e.g. per-channel priority)
+    if packet[START_CODE] != 0 && !terminated {
+        return None;
+    }
+
+    let mut cid = [0; 16];
+    cid.copy_from_slice(&packet[22..38]);
+    let count = u16::from_be_bytes([packet[123], packet[124]]) as usize;
+    let end = (START_CODE + count).min(packet.len());
+    let data = &packet[START_CODE + 1..end.max(START_CODE + 1)];
+
+    Some(DmxPacket {
+        source: Source::E131(cid),
+        universe: u16::from_be_bytes([packet[113], packet[114]]),
+        priority: packet[108].min(200),
+        data: &data[..data.len().min(UNIVERSE_SIZE)],
+        terminated,
+    })
+}
+
+fn read_u32(packet: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([
+        packet[offset],
+        packet[offset + 1],
+        packet[offset + 2],
+        packet[offset + 3],
+    ])
+}
+
+/// Build an E1.31 data packet, as a console would send it.
+#[cfg(test)]
+pub(crate) fn encode(cid: [u8; 16], universe: u16, priority: u8, data: &[u8]) -> Vec<u8> {
+    let mut packet = vec![0u8; START_CODE + 1 + data.len()];
+    let len = packet.len();
+    let pdu = |offset: usize| 0x7000 | (len - offset) as u16;
+
+    packet[0..2].copy_from_slice(&0x0010u16.to_be_bytes());
+    packet[4..16].copy_from_slice(ACN_IDENTIFIER);
+    packet[16..18].copy_from_slice(&pdu(16).to_be_bytes());
+    packet[18..22].copy_from_slice(&VECTOR_ROOT_DATA.to_be_bytes());
+    packet[22..38].copy_from_slice(&cid);
+    packet[38..40].copy_from_slice(&pdu(38).to_be_bytes());
+    packet[40..44].copy_from_slice(&VECTOR_FRAMING_DATA.to_be_bytes());
+    packet[44..49].copy_from_slice(b"tests");
+    packet[108] = priority;
+    packet[113..115].copy_from_slice(&universe.to_be_bytes());
+    packet[115..117].copy_from_slice(&pdu(115).to_be_bytes());
+    packet[117] = VECTOR_DMP_SET_PROPERTY;
+    packet[118] = 0xa1;
+    packet[121..123].copy_from_slice(&1u16.to_be_bytes());
+    packet[123..125].copy_from_slice(&(data.len() as u16 + 1).to_be_bytes());
+    packet[START_CODE + 1..].copy_from_slice(data);
+    packet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let cid = [7; 16];
+        let packet = encode(cid, 3, 150, &[1, 2, 3]);
+        let parsed = parse(&packet).unwrap();
+        assert_eq!(parsed.source, Source::E131(cid));
+        assert_eq!((parsed.universe, parsed.priority), (3, 150));
+        assert_eq!(parsed.data, [1, 2, 3]);
+        assert!(!parsed.terminated);
+
+        let mut terminated = packet.clone();
+        terminated[112] = OPTION_TERMINATED;
+        assert!(parse(&terminated).unwrap().terminated);
+
+        let mut preview = packet.clone();
+        preview[112] = OPTION_PREVIEW;
+        assert!(parse(&preview).is_none());
+
+        let mut priorities = packet.clone();
+        priorities[START_CODE] = 0xdd;
+        assert!(parse(&priorities).is_none());
+
+        assert!(parse(&packet[..100]).is_none());
+        assert!(parse(b"Art-Net\0").is_none());
+        assert_eq!(multicast_group(258).octets(), [239, 255, 1, 2]);
+    }
+}
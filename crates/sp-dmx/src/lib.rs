@@ -0,0 +1,17 @@
+//! E1.31 (sACN) and Art-Net input for Super Pixeled.
+//!
+//! Lighting consoles send DMX universes over UDP; their channels are mapped
+//! to panel pixels and shown as the `dmx` live input effect while data
+//! flows, falling back to the previous effect once every source is silent.
+
+pub mod artnet;
+pub mod e131;
+mod mapping;
+mod packet;
+mod receiver;
+mod sources;
+
+pub use mapping::PixelMap;
+pub use packet::{DmxPacket, Source, UNIVERSE_SIZE};
+pub use receiver::{DmxReceiver, EFFECT_NAME};
+pub use sources::Sources;
@@ -0,0 +1,98 @@
+//! Mapping of DMX channels to panel pixels.
+
+use sp_core::{Color, DmxConfig, PixelOrder, Point};
+use sp_renderer::Framebuffer;
+
+use crate::packet::UNIVERSE_SIZE;
+
+/// Where each universe's channels land on the panel.
+#[derive(Debug, Clone, Copy)]
+pub struct PixelMap {
+    width: u32,
+    height: u32,
+    start_universe: u16,
+    channel_offset: usize,
+    order: PixelOrder,
+}
+
+impl PixelMap {
+    pub fn new(config: &DmxConfig, width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            start_universe: config.start_universe,
+            channel_offset: usize::from(config.channel_offset).min(UNIVERSE_SIZE - 3),
+            order: config.pixel_order,
+        }
+    }
+
+    /// Whole pixels carried by each universe.
+    pub fn pixels_per_universe(&self) -> u32 {
+        ((UNIVERSE_SIZE - self.channel_offset) / 3) as u32
+    }
+
+    /// Universes needed to cover the panel.
+    pub fn universes(&self) -> impl Iterator<Item = u16> {
+        let pixels = self.width * self.height;
+        let count = pixels.div_ceil(self.pixels_per_universe());
+        let start = u32::from(self.start_universe);
+        (start..start + count).filter_map(|universe| u16::try_from(universe).ok())
+    }
+
+    /// Write a universe's channels; false when it maps to no pixel.
+    pub fn write(&self, fb: &mut Framebuffer, universe: u16, data: &[u8]) -> bool {
+        let Some(index) = universe.checked_sub(self.start_universe) else {
+            return false;
+        };
+        let first = u32::from(index) * self.pixels_per_universe();
+        let pixels = self.width * self.height;
+        if first >= pixels {
+            return false;
+        }
+
+        let channels = data.get(self.channel_offset..).unwrap_or_default();
+        for (i, rgb) in channels.chunks_exact(3).enumerate() {
+            let pixel = first + i as u32;
+            if pixel >= pixels || i as u32 >= self.pixels_per_universe() {
+                break;
+            }
+            let point = Point::new((pixel % self.width) as i32, (pixel / self.width) as i32);
+            let color = self.order.to_rgb([rgb[0], rgb[1], rgb[2]]);
+            fb.set(point, Color::from(color));
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write() {
+        let config = DmxConfig {
+            start_universe: 1,
+            channel_offset: 2,
+            pixel_order: PixelOrder::Grb,
+            ..DmxConfig::default()
+        };
+        let map = PixelMap::new(&config, 64, 32);
+        assert_eq!(map.pixels_per_universe(), 170);
+        assert_eq!(
+            map.universes().collect::<Vec<_>>(),
+            (1..=13).collect::<Vec<_>>()
+        );
+
+        let mut fb = Framebuffer::new(64, 32);
+        assert!(map.write(&mut fb, 1, &[99, 99, 0, 255, 0, 10, 20, 30]));
+        assert_eq!(fb.get(Point::new(0, 0)), Some(Color::RED));
+        assert_eq!(fb.get(Point::new(1, 0)), Some(Color::new(20, 10, 30)));
+
+        // Universe 2 starts at pixel 170: x = 42, y = 2
+        assert!(map.write(&mut fb, 2, &[0, 0, 0, 0, 255]));
+        assert_eq!(fb.get(Point::new(42, 2)), Some(Color::BLUE));
+
+        assert!(!map.write(&mut fb, 0, &[255; 9]));
+        assert!(!map.write(&mut fb, 14, &[255; 9]));
+    }
+}
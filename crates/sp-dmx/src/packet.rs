@@ -0,0 +1,28 @@
+//! Protocol-independent view of a received DMX universe.
+
+use std::net::IpAddr;
+
+/// Largest DMX universe, in channels.
+pub const UNIVERSE_SIZE: usize = 512;
+
+/// Who sent a packet; priorities are tracked per source and universe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// E1.31 component identifier.
+    E131([u8; 16]),
+    /// Art-Net has no identifier; the sender's address stands in.
+    ArtNet(IpAddr),
+}
+
+/// Channel data for one universe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DmxPacket<'a> {
+    pub source: Source,
+    pub universe: u16,
+    /// 0-200; higher priority sources hide lower ones.
+    pub priority: u8,
+    /// Channel values from channel 1, at most 512.
+    pub data: &'a [u8],
+    /// The source announced it is stopping; `data` must be ignored.
+    pub terminated: bool,
+}
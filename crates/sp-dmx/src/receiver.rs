@@ -0,0 +1,242 @@
+//! UDP receiver task switching the panel to live DMX input and back.
+
+use sp_api::{AppState, LiveOverride};
+use sp_core::{DmxConfig, Result};
+use sp_effects::LiveInput;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::{artnet, e131, mapping::PixelMap, packet::DmxPacket, sources::Sources};
+
+/// Effect showing received pixels.
+pub const EFFECT_NAME: &str = "dmx";
+
+/// How often silent sources are looked for.
+const EXPIRE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Largest datagram read; both protocols fit in an Ethernet frame.
+const MAX_PACKET: usize = 1500;
+
+/// Receives E1.31 and Art-Net universes and shows them as the `dmx` effect.
+pub struct DmxReceiver {
+    state: Arc<AppState>,
+    config: DmxConfig,
+    map: PixelMap,
+    input: LiveInput,
+    sources: Sources,
+    live: LiveOverride,
+    e131: Option<UdpSocket>,
+    artnet: Option<UdpSocket>,
+}
+
+impl DmxReceiver {
+    /// Bind the enabled protocols and register the `dmx` effect.
+    pub async fn bind(state: Arc<AppState>) -> Result<Self> {
+        let config = state.config.dmx.clone();
+        let panel = &state.config.panel;
+        let map = PixelMap::new(&config, panel.width, panel.height);
+        let input = LiveInput::new(panel.width, panel.height);
+
+        let e131 = match config.e131 {
+            true => Some(UdpSocket::bind((config.bind.as_str(), config.e131_port)).await?),
+            false => None,
+        };
+        if let Some(socket) = &e131 {
+            join_universes(socket, &map);
+        }
+        let artnet = match config.artnet {
+            true => Some(UdpSocket::bind((config.bind.as_str(), config.artnet_port)).await?),
+            false => None,
+        };
+
+        let live = LiveOverride::new(Arc::clone(&state), EFFECT_NAME);
+        live.register(
+            "Pixels from E1.31 / Art-Net lighting consoles",
+            input.clone(),
+        )
+        .await?;
+
+        Ok(Self {
+            sources: Sources::new(Duration::from_millis(config.timeout_ms)),
+            state,
+            config,
+            map,
+            input,
+            live,
+            e131,
+            artnet,
+        })
+    }
+
+    /// Address receiving E1.31, when enabled.
+    pub fn e131_addr(&self) -> Option<SocketAddr> {
+        self.e131.as_ref().and_then(|s| s.local_addr().ok())
+    }
+
+    /// Address receiving Art-Net, when enabled.
+    pub fn artnet_addr(&self) -> Option<SocketAddr> {
+        self.artnet.as_ref().and_then(|s| s.local_addr().ok())
+    }
+
+    /// Spawn the receiver task; it runs until `shutdown` flips.
+    pub fn spawn(self, shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+        tokio::spawn(self.run(shutdown))
+    }
+
+    async fn run(mut self, mut shutdown: watch::Receiver<bool>) {
+        info!(
+            e131 = ?self.e131_addr(),
+            artnet = ?self.artnet_addr(),
+            start_universe = self.config.start_universe,
+            "Starting DMX receiver"
+        );
+        let (e131, artnet) = (self.e131.take(), self.artnet.take());
+        let mut e131_buf = vec![0u8; MAX_PACKET];
+        let mut artnet_buf = vec![0u8; MAX_PACKET];
+        let mut events = self.state.events.subscribe();
+        let mut expire = tokio::time::interval(EXPIRE_INTERVAL);
+
+        loop {
+            tokio::select! {
+                Ok((len, _)) = recv(e131.as_ref(), &mut e131_buf) => {
+                    if let Some(packet) = e131::parse(&e131_buf[..len]) {
+                        self.on_packet(packet).await;
+                    }
+                }
+                Ok((len, from)) = recv(artnet.as_ref(), &mut artnet_buf) => {
+                    let priority = self.config.artnet_priority;
+                    if let Some(packet) = artnet::parse(&artnet_buf[..len], from.ip(), priority) {
+                        self.on_packet(packet).await;
+                    }
+                }
+                event = events.recv() => match event {
+                    Ok(event) => self.live.observe(&event.kind),
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = expire.tick() => {
+                    if self.sources.expire(Instant::now()) && self.live.release().await {
+                        self.input.clear();
+                    }
+                }
+                _ = shutdown.changed() => break,
+            }
+        }
+        info!("DMX receiver stopped");
+    }
+
+    async fn on_packet(&mut self, packet: DmxPacket<'_>) {
+        if !self.sources.accept(&packet, Instant::now()) {
+            return;
+        }
+        let map = self.map;
+        if !self
+            .input
+            .write(|pixels| map.write(pixels, packet.universe, packet.data))
+        {
+            return;
+        }
+        self.live.activate().await;
+    }
+}
+
+/// Receive on `socket`, or never when the protocol is disabled.
+async fn recv(socket: Option<&UdpSocket>, buf: &mut [u8]) -> std::io::Result<(usize, SocketAddr)> {
+    match socket {
+        Some(socket) => socket.recv_from(buf).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Join the E1.31 multicast groups of the panel's universes.
+fn join_universes(socket: &UdpSocket, map: &PixelMap) {
+    let bound = socket.local_addr().map(|addr| addr.ip());
+    if !matches!(bound, Ok(IpAddr::V4(ip)) if ip.is_unspecified()) {
+        debug!("E1.31 bound to a specific address, not joining multicast groups");
+        return;
+    }
+    for universe in map.universes() {
+        let group = e131::multicast_group(universe);
+        if let Err(e) = socket.join_multicast_v4(group, Ipv4Addr::UNSPECIFIED) {
+            warn!(error = %e, %group, "Failed to join E1.31 multicast group");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_api::test_util;
+    use sp_core::{Color, Config, Point};
+    use sp_effects::EffectParams;
+
+    async fn wait_for(state: &AppState, color: Color) {
+        for _ in 0..200 {
+            if state.frames.latest().get(Point::new(0, 0)) == Some(color) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("frame never turned {color:?}");
+    }
+
+    #[tokio::test]
+    async fn test_live_input_and_fallback() {
+        assert!(sp_effects::RESERVED_NAMES.contains(&EFFECT_NAME));
+        let mut config = Config::default();
+        config.dmx.bind = "127.0.0.1".to_string();
+        config.dmx.e131_port = 0;
+        config.dmx.artnet_port = 0;
+        config.dmx.timeout_ms = 300;
+
+        let (state, render) = test_util::start(config);
+        let shutdown_rx = render.shutdown();
+
+        let params = EffectParams {
+            color: Some([255, 0, 0]),
+            ..Default::default()
+        };
+        state.renderer.set_effect("solid", params).await.unwrap();
+        wait_for(&state, Color::RED).await;
+
+        let receiver = DmxReceiver::bind(Arc::clone(&state)).await.unwrap();
+        let (e131_addr, artnet_addr) = (
+            receiver.e131_addr().unwrap(),
+            receiver.artnet_addr().unwrap(),
+        );
+        let task = receiver.spawn(shutdown_rx);
+        let console = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        // E1.31 at priority 150 takes over the panel
+        let green = e131::encode([1; 16], 1, 150, &[0, 255, 0]);
+        console.send_to(&green, e131_addr).unwrap();
+        wait_for(&state, Color::GREEN).await;
+        let current = state
+            .renderer
+            .call(|manager| manager.current_effect().map(String::from))
+            .await
+            .unwrap();
+        assert_eq!(current.as_deref(), Some(EFFECT_NAME));
+
+        // Art-Net at the default priority 100 is hidden meanwhile
+        console
+            .send_to(&artnet::encode(1, &[0, 0, 255]), artnet_addr)
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(
+            state.frames.latest().get(Point::new(0, 0)),
+            Some(Color::GREEN)
+        );
+
+        // Silence brings the previous effect back
+        wait_for(&state, Color::RED).await;
+
+        drop(render);
+        task.await.unwrap();
+    }
+}
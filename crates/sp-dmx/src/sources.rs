@@ -0,0 +1,106 @@
+//! Per-universe source tracking and priority arbitration.
+//!
+//! As in E1.31, the highest priority source of a universe wins; lower ones
+//! are ignored until it stops sending for the timeout or terminates.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::packet::{DmxPacket, Source};
+
+#[derive(Debug, Clone, Copy)]
+struct Seen {
+    priority: u8,
+    at: Instant,
+}
+
+/// Sources currently sending to each universe.
+#[derive(Debug)]
+pub struct Sources {
+    timeout: Duration,
+    universes: HashMap<u16, HashMap<Source, Seen>>,
+}
+
+impl Sources {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            universes: HashMap::new(),
+        }
+    }
+
+    /// Record `packet`; true when its data should be shown.
+    pub fn accept(&mut self, packet: &DmxPacket<'_>, now: Instant) -> bool {
+        let timeout = self.timeout;
+        let sources = self.universes.entry(packet.universe).or_default();
+        sources.retain(|_, seen| now.duration_since(seen.at) < timeout);
+
+        if packet.terminated {
+            sources.remove(&packet.source);
+            return false;
+        }
+        sources.insert(
+            packet.source,
+            Seen {
+                priority: packet.priority,
+                at: now,
+            },
+        );
+        let highest = sources.values().map(|seen| seen.priority).max();
+        highest == Some(packet.priority)
+    }
+
+    /// Forget sources silent for the timeout; true if none remain.
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let timeout = self.timeout;
+        self.universes.retain(|_, sources| {
+            sources.retain(|_, seen| now.duration_since(seen.at) < timeout);
+            !sources.is_empty()
+        });
+        self.universes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    fn packet(source: Source, priority: u8) -> DmxPacket<'static> {
+        DmxPacket {
+            source,
+            universe: 1,
+            priority,
+            data: &[],
+            terminated: false,
+        }
+    }
+
+    #[test]
+    fn test_priority_and_timeout() {
+        let now = Instant::now();
+        let mut sources = Sources::new(Duration::from_secs(1));
+        let console = Source::E131([1; 16]);
+        let backup = Source::ArtNet(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        assert!(sources.accept(&packet(backup, 100), now));
+        assert!(sources.accept(&packet(console, 150), now));
+        assert!(!sources.accept(&packet(backup, 100), now));
+
+        // The console terminates: the backup shows again
+        let stop = DmxPacket {
+            terminated: true,
+            ..packet(console, 150)
+        };
+        assert!(!sources.accept(&stop, now));
+        assert!(sources.accept(&packet(backup, 100), now));
+
+        // The console goes silent: it no longer hides the backup
+        assert!(sources.accept(&packet(console, 150), now));
+        let later = now + Duration::from_millis(1500);
+        assert!(sources.accept(&packet(backup, 100), later));
+
+        assert!(!sources.expire(later));
+        assert!(sources.expire(later + Duration::from_secs(1)));
+    }
+}
@@ -0,0 +1,7 @@
+//! Audio input and analysis for audio-reactive effects.
+
+mod fft;
+mod source;
+
+pub use fft::{fft, magnitude_spectrum};
+pub use source::{relative_path, AudioInput, AudioSource, WavClip};
@@ -0,0 +1,318 @@
+//! Audio sample sources: WAV files and raw PCM pushed by network receivers.
+
+use sp_core::{Error, Result};
+use std::collections::VecDeque;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Largest WAV file loaded, in bytes.
+const MAX_WAV_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Most recent PCM samples kept by an [`AudioInput`].
+const PCM_CAPACITY: usize = 8192;
+
+/// Decoded mono audio clip.
+#[derive(Debug, Clone)]
+pub struct WavClip {
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+impl WavClip {
+    /// Load a WAV file from disk.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::parse(&bytes)
+    }
+
+    /// Load a WAV file given relative to `dir`, refusing anything outside it.
+    ///
+    /// Symlinks are resolved before the check.
+    pub fn load_within(dir: impl AsRef<Path>, path: &str) -> Result<Self> {
+        let relative = relative_path(path)?;
+        let dir = dir.as_ref().canonicalize()?;
+        let resolved = dir.join(relative).canonicalize()?;
+        if !resolved.starts_with(&dir) {
+            return Err(Error::invalid_param(
+                "path",
+                "must stay inside the audio directory",
+            ));
+        }
+        if std::fs::metadata(&resolved)?.len() > MAX_WAV_BYTES {
+            return Err(Error::invalid_param(
+                "path",
+                "WAV file is larger than 64 MiB",
+            ));
+        }
+        Self::load(resolved)
+    }
+
+    /// Parse a RIFF/WAVE buffer (PCM 8/16/24/32-bit or 32-bit float), downmixed to mono.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err(Error::invalid_param("path", "not a RIFF/WAVE file"));
+        }
+
+        let mut format = None;
+        let mut data = None;
+        let mut pos = 12;
+        while pos + 8 <= bytes.len() {
+            let id = &bytes[pos..pos + 4];
+            let len = u32::from_le_bytes([
+                bytes[pos + 4],
+                bytes[pos + 5],
+                bytes[pos + 6],
+                bytes[pos + 7],
+            ]) as usize;
+            let body = &bytes[pos + 8..(pos + 8 + len).min(bytes.len())];
+            match id {
+                b"fmt " if body.len() >= 16 => {
+                    let tag = u16::from_le_bytes([body[0], body[1]]);
+                    let channels = u16::from_le_bytes([body[2], body[3]]);
+                    let rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+                    let bits = u16::from_le_bytes([body[14], body[15]]);
+                    format = Some((tag, channels, rate, bits));
+                }
+                b"data" => data = Some(body),
+                _ => {}
+            }
+            // Chunks are padded to even sizes
+            pos += 8 + len + (len & 1);
+        }
+
+        let (tag, channels, sample_rate, bits) =
+            format.ok_or_else(|| Error::invalid_param("path", "missing fmt chunk"))?;
+        let data = data.ok_or_else(|| Error::invalid_param("path", "missing data chunk"))?;
+
+        if channels == 0 || sample_rate == 0 {
+            return Err(Error::invalid_param("path", "invalid WAV format"));
+        }
+
+        let decode: fn(&[u8]) -> f32 = match (tag, bits) {
+            (1, 8) => |b| (b[0] as f32 - 128.0) / 128.0,
+            (1, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+            (1, 24) => |b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0,
+            (1, 32) => |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+            (3, 32) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            _ => {
+                return Err(Error::invalid_param(
+                    "path",
+                    format!("unsupported WAV encoding (format {tag}, {bits} bits)"),
+                ))
+            }
+        };
+
+        let width = bits as usize / 8;
+        let frame = width * channels as usize;
+        let samples = data
+            .chunks_exact(frame)
+            .map(|f| f.chunks_exact(width).map(decode).sum::<f32>() / channels as f32)
+            .collect();
+
+        Ok(Self {
+            sample_rate,
+            samples,
+        })
+    }
+}
+
+/// Check that `path` is relative and never climbs out of its base directory.
+pub fn relative_path(path: &str) -> Result<PathBuf> {
+    let path = Path::new(path);
+    let plain = path.components().all(|c| matches!(c, Component::Normal(_)));
+    if path.as_os_str().is_empty() || !plain {
+        return Err(Error::invalid_param(
+            "path",
+            "must be a relative path inside the audio directory",
+        ));
+    }
+    Ok(path.to_path_buf())
+}
+
+/// PCM samples shared between network receivers and the effects analyzing them.
+///
+/// Receivers push raw signed 16-bit little-endian mono PCM from their own
+/// task; effects read the most recent window on each tick.
+#[derive(Clone)]
+pub struct AudioInput {
+    sample_rate: u32,
+    samples: Arc<Mutex<VecDeque<f32>>>,
+}
+
+impl AudioInput {
+    /// Create an empty input receiving PCM at `sample_rate` Hz.
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            samples: Arc::new(Mutex::new(VecDeque::with_capacity(PCM_CAPACITY))),
+        }
+    }
+
+    /// Sample rate of the pushed PCM in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Append signed 16-bit little-endian samples; a trailing odd byte is dropped.
+    pub fn push_pcm(&self, bytes: &[u8]) {
+        let mut samples = self.lock();
+        samples.extend(
+            bytes
+                .chunks_exact(2)
+                .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0),
+        );
+        let excess = samples.len().saturating_sub(PCM_CAPACITY);
+        samples.drain(..excess);
+    }
+
+    /// Copy the most recent `out.len()` samples, zero-padded at the start.
+    fn read(&self, out: &mut [f32]) {
+        let samples = self.lock();
+        let count = samples.len().min(out.len());
+        let pad = out.len() - count;
+        out[..pad].fill(0.0);
+        for (slot, s) in out[pad..]
+            .iter_mut()
+            .zip(samples.range(samples.len() - count..))
+        {
+            *slot = *s;
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<f32>> {
+        // Samples stay usable even if a receiver panicked mid-push
+        self.samples.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Where an audio-reactive effect reads its samples from.
+pub enum AudioSource {
+    /// Loop a decoded WAV clip in real time.
+    Wav { clip: WavClip, position: f64 },
+    /// Raw PCM pushed over UDP or WebSocket.
+    Pcm(AudioInput),
+    /// No input (all analysis reads zero).
+    Silence { sample_rate: u32 },
+}
+
+impl AudioSource {
+    /// Loop a decoded clip from its start.
+    pub fn wav(clip: WavClip) -> Self {
+        Self::Wav {
+            clip,
+            position: 0.0,
+        }
+    }
+
+    /// Sample rate of the source in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        match self {
+            Self::Wav { clip, .. } => clip.sample_rate,
+            Self::Pcm(input) => input.sample_rate(),
+            Self::Silence { sample_rate } => *sample_rate,
+        }
+    }
+
+    /// Advance by `dt` and copy the most recent `out.len()` samples into `out`.
+    pub fn read_window(&mut self, dt: Duration, out: &mut [f32]) {
+        match self {
+            Self::Wav { clip, position } => {
+                let len = clip.samples.len();
+                if len == 0 {
+                    out.fill(0.0);
+                    return;
+                }
+                *position = (*position + dt.as_secs_f64() * clip.sample_rate as f64) % len as f64;
+                let start = *position as isize - out.len() as isize;
+                for (i, slot) in out.iter_mut().enumerate() {
+                    let idx = (start + i as isize).rem_euclid(len as isize) as usize;
+                    *slot = clip.samples[idx];
+                }
+            }
+            Self::Pcm(input) => input.read(out),
+            Self::Silence { .. } => out.fill(0.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav_bytes(rate: u32, channels: u16, samples: &[i16]) -> Vec<u8> {
+        let data_len = (samples.len() * 2) as u32;
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data_len).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&channels.to_le_bytes());
+        out.extend_from_slice(&rate.to_le_bytes());
+        out.extend_from_slice(&(rate * channels as u32 * 2).to_le_bytes());
+        out.extend_from_slice(&(channels * 2).to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&data_len.to_le_bytes());
+        for s in samples {
+            out.extend_from_slice(&s.to_le_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn test_parse_stereo_downmix() {
+        let bytes = wav_bytes(8000, 2, &[16384, 16384, -32768, 0]);
+        let clip = WavClip::parse(&bytes).unwrap();
+
+        assert_eq!(clip.sample_rate, 8000);
+        assert_eq!(clip.samples, vec![0.5, -0.5]);
+    }
+
+    #[test]
+    fn test_parse_rejects_garbage() {
+        assert!(WavClip::parse(b"not a wav file").is_err());
+    }
+
+    #[test]
+    fn test_pcm_source() {
+        let input = AudioInput::new(8000);
+        let mut source = AudioSource::Pcm(input.clone());
+
+        let pcm: Vec<u8> = [16384i16, -16384]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        input.push_pcm(&pcm);
+
+        let mut window = [1.0f32; 4];
+        source.read_window(Duration::ZERO, &mut window);
+        assert_eq!(window, [0.0, 0.0, 0.5, -0.5]);
+        assert_eq!(source.sample_rate(), 8000);
+    }
+
+    #[test]
+    fn test_load_within() {
+        let root = std::env::temp_dir().join(format!("sp-audio-{}", std::process::id()));
+        let dir = root.join("audio");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("tone.wav"), wav_bytes(8000, 1, &[0, 16384])).unwrap();
+        std::fs::write(root.join("secret.wav"), wav_bytes(8000, 1, &[0])).unwrap();
+
+        assert_eq!(
+            WavClip::load_within(&dir, "tone.wav")
+                .unwrap()
+                .samples
+                .len(),
+            2
+        );
+        assert!(WavClip::load_within(&dir, "../secret.wav").is_err());
+        assert!(relative_path("/etc/passwd").is_err());
+        assert!(relative_path("a/../../b.wav").is_err());
+        assert!(relative_path("").is_err());
+        assert!(relative_path("songs/a.wav").is_ok());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -4,7 +4,7 @@ use sp_core::{Color, Point};
 use sp_renderer::Framebuffer;
 use std::time::Duration;
 
-use crate::{Effect, EffectConfig, EffectParams};
+use crate::{Effect, EffectConfig, EffectParams, ParamKind, ParamSchema, ParamSpec};
 
 /// Fire effect with realistic flame animation.
 ///
@@ -29,6 +29,18 @@ impl FireEffect {
         }
     }
 
+    /// Parameters accepted by the fire effect.
+    pub fn schema() -> ParamSchema {
+        ParamSchema::new().param(
+            ParamSpec::new(
+                "intensity",
+                ParamKind::float(0.0, 1.0),
+                "Flame height and heat",
+            )
+            .with_default(0.8),
+        )
+    }
+
     /// Get heat value at position.
     fn get_heat(&self, x: i32, y: i32) -> u8 {
         if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
@@ -83,14 +95,14 @@ impl Default for FireEffect {
 }
 
 impl Effect for FireEffect {
-    fn name(&self) -> &'static str {
+    fn name(&self) -> &str {
         "fire"
     }
 
     fn init(&mut self, config: &EffectConfig) {
         self.width = config.width;
         self.height = config.height;
-        self.intensity = config.params.intensity;
+        self.intensity = config.params.intensity.clamp(0.0, 1.0);
         self.heat = vec![0u8; (self.width * self.height) as usize];
         self.time = 0.0;
 
@@ -108,7 +120,7 @@ impl Effect for FireEffect {
 
         // Step 1: Seed the bottom row with random heat
         for x in 0..self.width as i32 {
-            let seed = (frame.wrapping_mul(1000) + x as u32) as u32;
+            let seed = frame.wrapping_mul(1000).wrapping_add(x as u32);
             let base_heat = (self.intensity * 255.0) as u8;
             let variation = (self.random(seed) as i32 - 128) / 4;
             let heat = (base_heat as i32 + variation).clamp(0, 255) as u8;
@@ -156,7 +168,7 @@ impl Effect for FireEffect {
     }
 
     fn update_params(&mut self, params: &EffectParams) {
-        self.intensity = params.intensity;
+        self.intensity = params.intensity.clamp(0.0, 1.0);
         tracing::debug!(intensity = self.intensity, "Fire effect params updated");
     }
 }
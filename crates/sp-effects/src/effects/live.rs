@@ -0,0 +1,133 @@
+//! Live input effect - shows pixels written by an external source.
+
+use sp_renderer::Framebuffer;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::{Effect, EffectConfig, EffectOrigin, EffectRegistration};
+
+/// Pixels shared between a network receiver and the effect showing them.
+///
+/// Receivers write whole or partial frames from their own thread; the
+/// effect copies the latest state on each tick.
+#[derive(Clone)]
+pub struct LiveInput {
+    pixels: Arc<Mutex<Framebuffer>>,
+}
+
+impl LiveInput {
+    /// Create a black input the size of the panel.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            pixels: Arc::new(Mutex::new(Framebuffer::new(width, height))),
+        }
+    }
+
+    /// Update the pixels in place.
+    pub fn write<R>(&self, f: impl FnOnce(&mut Framebuffer) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    /// Turn every pixel off.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// A copy of the current pixels.
+    pub fn snapshot(&self) -> Framebuffer {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Framebuffer> {
+        // A writer panicking mid-frame leaves pixels, not broken invariants
+        self.pixels.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Live input effect - displays a [`LiveInput`] as it is written.
+pub struct LiveInputEffect {
+    name: String,
+    input: LiveInput,
+}
+
+impl LiveInputEffect {
+    /// Create an effect named `name` showing `input`.
+    pub fn new(name: impl Into<String>, input: LiveInput) -> Self {
+        Self {
+            name: name.into(),
+            input,
+        }
+    }
+
+    /// Registration creating effects that share `input`.
+    pub fn registration(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        input: LiveInput,
+    ) -> EffectRegistration {
+        let name = name.into();
+        let effect_name = name.clone();
+        EffectRegistration::new(name, description, move || {
+            Box::new(Self::new(effect_name.clone(), input.clone()))
+        })
+        .with_origin(EffectOrigin::External)
+    }
+}
+
+impl Effect for LiveInputEffect {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn init(&mut self, config: &EffectConfig) {
+        tracing::debug!(
+            effect = %self.name,
+            width = config.width,
+            height = config.height,
+            "Live input effect initialized"
+        );
+    }
+
+    fn tick(&mut self, fb: &mut Framebuffer, _dt: Duration) -> bool {
+        let pixels = self.input.lock();
+        if pixels.data().len() == fb.data().len() {
+            fb.data_mut().copy_from_slice(pixels.data());
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EffectManager;
+    use sp_core::{Color, Point};
+
+    #[test]
+    fn test_shows_written_pixels() {
+        let input = LiveInput::new(8, 4);
+        let mut manager = EffectManager::new(8, 4);
+        manager
+            .registry_mut()
+            .register(LiveInputEffect::registration(
+                "live",
+                "Test input",
+                input.clone(),
+            ))
+            .unwrap();
+        manager.set_effect("live", Default::default()).unwrap();
+
+        let mut fb = Framebuffer::new(8, 4);
+        fb.fill(Color::RED);
+        manager.tick(&mut fb);
+        assert!(fb.data().iter().all(|&c| c == Color::BLACK));
+
+        input.write(|pixels| pixels.set(Point::new(2, 1), Color::GREEN));
+        manager.tick(&mut fb);
+        assert_eq!(fb.get(Point::new(2, 1)), Some(Color::GREEN));
+
+        input.clear();
+        manager.tick(&mut fb);
+        assert_eq!(fb.get(Point::new(2, 1)), Some(Color::BLACK));
+    }
+}
@@ -1,24 +1,69 @@
 //! Built-in effects.
 
 mod fire;
+mod live;
 mod solid;
+mod spectrum;
+mod text;
 
 pub use fire::FireEffect;
+pub use live::{LiveInput, LiveInputEffect};
 pub use solid::SolidEffect;
+pub use spectrum::{SpectrumEffect, SpectrumMode};
+pub use text::TextEffect;
 
-use crate::Effect;
+use std::sync::OnceLock;
 
-/// Create an effect by name.
-pub fn create_effect(name: &str) -> Option<Box<dyn Effect>> {
-    match name {
-        "fire" => Some(Box::new(FireEffect::new())),
-        "solid" => Some(Box::new(SolidEffect::new())),
-        "off" => Some(Box::new(SolidEffect::off())),
-        _ => None,
+use crate::{Effect, EffectOrigin, EffectRegistration, EffectRegistry};
+
+/// Register all built-in effects.
+pub(crate) fn register_builtins(registry: &mut EffectRegistry) {
+    let builtins = [
+        EffectRegistration::new("fire", "Realistic flame animation", || {
+            Box::new(FireEffect::new())
+        })
+        .with_schema(FireEffect::schema()),
+        EffectRegistration::new("solid", "Fill the panel with a single color", || {
+            Box::new(SolidEffect::new())
+        })
+        .with_schema(SolidEffect::schema()),
+        EffectRegistration::new(
+            "off",
+            "Turn all pixels off",
+            || Box::new(SolidEffect::off()),
+        ),
+        SpectrumEffect::registration(None, None),
+        EffectRegistration::new("text", "Static or scrolling text", || {
+            Box::new(TextEffect::new())
+        })
+        .with_schema(TextEffect::schema()),
+    ];
+
+    for registration in builtins {
+        registry.register_or_replace(registration.with_origin(EffectOrigin::Builtin));
     }
 }
 
-/// List all available effect names.
+/// Built-in effects, registered on first use.
+fn builtins() -> &'static EffectRegistry {
+    static BUILTINS: OnceLock<EffectRegistry> = OnceLock::new();
+    BUILTINS.get_or_init(EffectRegistry::with_builtins)
+}
+
+/// Create a built-in effect by name.
+///
+/// Scripts and plugins are only known to an [`EffectManager`](crate::EffectManager);
+/// create those through its registry.
+#[deprecated(note = "built-in effects only; use `EffectManager::registry().create`")]
+pub fn create_effect(name: &str) -> Option<Box<dyn Effect>> {
+    builtins().create(name).ok()
+}
+
+/// List all built-in effect names.
+#[deprecated(
+    note = "built-in effects only; use `EffectManager::available_effects`, which includes scripts and plugins"
+)]
 pub fn available_effects() -> &'static [&'static str] {
-    &["fire", "solid", "off"]
+    static NAMES: OnceLock<Vec<&'static str>> = OnceLock::new();
+    NAMES.get_or_init(|| builtins().iter().map(|r| r.name.as_str()).collect())
 }
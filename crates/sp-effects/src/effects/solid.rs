@@ -4,7 +4,7 @@ use sp_core::Color;
 use sp_renderer::Framebuffer;
 use std::time::Duration;
 
-use crate::{Effect, EffectConfig, EffectParams};
+use crate::{Effect, EffectConfig, EffectParams, ParamKind, ParamSchema, ParamSpec};
 
 /// Solid color effect - fills panel with a single color.
 pub struct SolidEffect {
@@ -30,6 +30,13 @@ impl SolidEffect {
     pub fn with_color(color: Color) -> Self {
         Self { color }
     }
+
+    /// Parameters accepted by the solid effect.
+    pub fn schema() -> ParamSchema {
+        ParamSchema::new().param(
+            ParamSpec::new("color", ParamKind::Color, "Fill color").with_default([255, 255, 255]),
+        )
+    }
 }
 
 impl Default for SolidEffect {
@@ -39,7 +46,7 @@ impl Default for SolidEffect {
 }
 
 impl Effect for SolidEffect {
-    fn name(&self) -> &'static str {
+    fn name(&self) -> &str {
         "solid"
     }
 
@@ -70,4 +77,9 @@ impl Effect for SolidEffect {
             self.color = Color::from(rgb);
         }
     }
+
+    fn preferred_fps(&self) -> Option<u32> {
+        // The frame never changes; parameter updates bump the manager revision
+        Some(1)
+    }
 }
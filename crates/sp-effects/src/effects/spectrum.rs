@@ -0,0 +1,618 @@
+//! Spectrum analyzer effect - audio-reactive bars and VU meter.
+
+use sp_core::{Color, Error, Result};
+use sp_renderer::Framebuffer;
+use std::path::PathBuf;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::audio::{magnitude_spectrum, relative_path, AudioInput, AudioSource, WavClip};
+use crate::{
+    Effect, EffectConfig, EffectOrigin, EffectParams, EffectRegistration, ParamKind, ParamSchema,
+    ParamSpec,
+};
+
+/// FFT window size (samples).
+const WINDOW: usize = 1024;
+/// Dynamic range mapped onto the bar height.
+const RANGE_DB: f32 = 60.0;
+/// Lowest analyzed frequency.
+const MIN_FREQ: f32 = 40.0;
+/// Highest analyzed frequency (clamped to Nyquist).
+const MAX_FREQ: f32 = 16_000.0;
+
+/// Display style of the analyzer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpectrumMode {
+    /// One vertical bar per frequency band.
+    Bars,
+    /// Single horizontal level meter.
+    Vu,
+}
+
+/// Audio-reactive spectrum analyzer.
+///
+/// Plays a WAV file (`path`, relative to the configured audio directory)
+/// or analyzes the PCM pushed to the shared [`AudioInput`], and renders FFT
+/// bands or a VU meter. Files are read on a separate thread; the effect is
+/// silent until the clip is loaded.
+///
+/// Extra parameters:
+/// - `path`: WAV file to play instead of the PCM input
+/// - `mode`: `"bars"` (default) or `"vu"`
+/// - `bands`: number of frequency bands (default 16)
+/// - `falloff`: level decay per second, 0.0 - 10.0 (default 1.5)
+/// - `peak_hold`: show peak-hold dots (default true)
+/// - `peak_hold_ms`: how long peaks stay before falling (default 500)
+/// - `color_high`: gradient end color, `color` is the start
+/// - `peak_color`: color of peak dots (default white)
+pub struct SpectrumEffect {
+    width: u32,
+    height: u32,
+    source: AudioSource,
+    audio_dir: Option<PathBuf>,
+    input: Option<AudioInput>,
+    path: Option<String>,
+    loading: Option<JoinHandle<Result<WavClip>>>,
+    window: Vec<f32>,
+    mode: SpectrumMode,
+    bands: usize,
+    falloff: f32,
+    peak_hold: bool,
+    peak_hold_secs: f32,
+    brightness: f32,
+    low: Color,
+    high: Color,
+    peak_color: Color,
+    levels: Vec<f32>,
+    peaks: Vec<Peak>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Peak {
+    level: f32,
+    age: f32,
+}
+
+impl SpectrumEffect {
+    /// Create a new spectrum effect with no audio input.
+    pub fn new() -> Self {
+        Self {
+            width: 64,
+            height: 32,
+            source: AudioSource::Silence {
+                sample_rate: 44_100,
+            },
+            audio_dir: None,
+            input: None,
+            path: None,
+            loading: None,
+            window: vec![0.0; WINDOW],
+            mode: SpectrumMode::Bars,
+            bands: 16,
+            falloff: 1.5,
+            peak_hold: true,
+            peak_hold_secs: 0.5,
+            brightness: 0.8,
+            low: Color::GREEN,
+            high: Color::RED,
+            peak_color: Color::WHITE,
+            levels: Vec::new(),
+            peaks: Vec::new(),
+        }
+    }
+
+    /// Create a spectrum effect reading from a given source.
+    pub fn with_source(source: AudioSource) -> Self {
+        Self {
+            source,
+            ..Self::new()
+        }
+    }
+
+    /// Create a spectrum effect playing files from `audio_dir` and
+    /// otherwise analyzing `input`.
+    pub fn with_input(audio_dir: Option<PathBuf>, input: Option<AudioInput>) -> Self {
+        Self {
+            audio_dir,
+            input,
+            ..Self::new()
+        }
+    }
+
+    /// Registration of the `spectrum` effect reading from `audio_dir` and
+    /// `input`. Without a directory, `path` is rejected.
+    pub fn registration(
+        audio_dir: Option<PathBuf>,
+        input: Option<AudioInput>,
+    ) -> EffectRegistration {
+        let has_dir = audio_dir.is_some();
+        EffectRegistration::new(
+            "spectrum",
+            "Audio spectrum analyzer and VU meter from WAV or PCM input",
+            move || Box::new(Self::with_input(audio_dir.clone(), input.clone())),
+        )
+        .with_schema(Self::schema())
+        .with_validator(move |params| match params.extra.get("path") {
+            Some(_) if !has_dir => Err(Error::invalid_param(
+                "path",
+                "no audio directory is configured",
+            )),
+            Some(path) => relative_path(path.as_str().unwrap_or_default()).map(drop),
+            None => Ok(()),
+        })
+        .with_origin(EffectOrigin::Builtin)
+    }
+
+    /// Parameters accepted by the spectrum effect.
+    pub fn schema() -> ParamSchema {
+        ParamSchema::new()
+            .param(
+                ParamSpec::new("mode", ParamKind::one_of(["bars", "vu"]), "Display style")
+                    .with_default("bars"),
+            )
+            .param(
+                ParamSpec::new(
+                    "bands",
+                    ParamKind::integer(1, 64),
+                    "Number of frequency bands",
+                )
+                .with_default(16),
+            )
+            .param(
+                ParamSpec::new(
+                    "falloff",
+                    ParamKind::float(0.0, 10.0),
+                    "Level decay per second",
+                )
+                .with_default(1.5),
+            )
+            .param(
+                ParamSpec::new("peak_hold", ParamKind::Bool, "Show peak-hold dots")
+                    .with_default(true),
+            )
+            .param(
+                ParamSpec::new(
+                    "peak_hold_ms",
+                    ParamKind::integer(0, 10_000),
+                    "Time peaks stay before falling",
+                )
+                .with_default(500),
+            )
+            .param(
+                ParamSpec::new("intensity", ParamKind::float(0.0, 1.0), "Output brightness")
+                    .with_default(0.8),
+            )
+            .param(
+                ParamSpec::new(
+                    "speed",
+                    ParamKind::float(0.0, 10.0),
+                    "Falloff speed multiplier",
+                )
+                .with_default(1.0),
+            )
+            .param(
+                ParamSpec::new("color", ParamKind::Color, "Gradient start color")
+                    .with_default([0, 255, 0]),
+            )
+            .param(
+                ParamSpec::new("color_high", ParamKind::Color, "Gradient end color")
+                    .with_default([255, 0, 0]),
+            )
+            .param(
+                ParamSpec::new("peak_color", ParamKind::Color, "Peak dot color")
+                    .with_default([255, 255, 255]),
+            )
+            .param(ParamSpec::new(
+                "path",
+                ParamKind::string(4096),
+                "WAV file to play, relative to the audio directory",
+            ))
+    }
+
+    fn apply_params(&mut self, params: &EffectParams) {
+        let extra = &params.extra;
+
+        self.mode = match extra.get("mode").and_then(|v| v.as_str()) {
+            Some("vu") => SpectrumMode::Vu,
+            _ => SpectrumMode::Bars,
+        };
+        self.bands = extra
+            .get("bands")
+            .and_then(|v| v.as_u64())
+            .map_or(16, |b| b.clamp(1, 64) as usize);
+        self.falloff = extra
+            .get("falloff")
+            .and_then(|v| v.as_f64())
+            .map_or(1.5, |f| f.clamp(0.0, 10.0) as f32)
+            * params.speed.max(0.0);
+        self.peak_hold = extra
+            .get("peak_hold")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        self.peak_hold_secs = extra
+            .get("peak_hold_ms")
+            .and_then(|v| v.as_u64())
+            .map_or(0.5, |ms| ms.min(10_000) as f32 / 1000.0);
+        self.brightness = params.intensity.clamp(0.0, 1.0);
+        self.low = params.color.map_or(Color::GREEN, Color::from);
+        self.high = rgb_param(params, "color_high").unwrap_or(Color::RED);
+        self.peak_color = rgb_param(params, "peak_color").unwrap_or(Color::WHITE);
+
+        let count = match self.mode {
+            SpectrumMode::Bars => self.bands,
+            SpectrumMode::Vu => 1,
+        };
+        self.levels.resize(count, 0.0);
+        self.peaks.resize(count, Peak::default());
+    }
+
+    /// Switch to the WAV file named by `path`, or to the PCM input.
+    fn open_source(&mut self, params: &EffectParams) {
+        self.path = params
+            .extra
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        self.loading = None;
+        self.source = match &self.input {
+            Some(input) if self.path.is_none() => AudioSource::Pcm(input.clone()),
+            _ => AudioSource::Silence {
+                sample_rate: 44_100,
+            },
+        };
+
+        let (Some(path), Some(dir)) = (self.path.clone(), self.audio_dir.clone()) else {
+            return;
+        };
+        // Reading and decoding a file would stall the render thread
+        let spawned = std::thread::Builder::new()
+            .name("spectrum-wav".to_string())
+            .spawn(move || WavClip::load_within(dir, &path));
+        match spawned {
+            Ok(loader) => self.loading = Some(loader),
+            Err(e) => tracing::warn!(error = %e, "Failed to start WAV loader"),
+        }
+    }
+
+    /// Start playing the WAV clip once its loader is done.
+    fn poll_loading(&mut self) {
+        if !self.loading.as_ref().is_some_and(|l| l.is_finished()) {
+            return;
+        }
+        match self.loading.take().map(JoinHandle::join) {
+            Some(Ok(Ok(clip))) => self.source = AudioSource::wav(clip),
+            Some(Ok(Err(e))) => {
+                tracing::warn!(error = %e, path = ?self.path, "Spectrum WAV unavailable, using silence")
+            }
+            _ => tracing::warn!(path = ?self.path, "WAV loader panicked"),
+        }
+    }
+
+    /// Analyze the current window into normalized (0.0 - 1.0) levels.
+    fn analyze(&self) -> Vec<f32> {
+        match self.mode {
+            SpectrumMode::Vu => {
+                let rms = (self.window.iter().map(|s| s * s).sum::<f32>() / WINDOW as f32).sqrt();
+                // A full-scale sine has an RMS of 1/sqrt(2)
+                vec![normalize_db(rms * std::f32::consts::SQRT_2)]
+            }
+            SpectrumMode::Bars => {
+                let spectrum = magnitude_spectrum(&self.window);
+                let rate = self.source.sample_rate() as f32;
+                band_levels(&spectrum, rate, self.bands)
+            }
+        }
+    }
+
+    fn update_levels(&mut self, targets: &[f32], dt: f32) {
+        for ((level, peak), target) in self.levels.iter_mut().zip(&mut self.peaks).zip(targets) {
+            *level = if *target >= *level {
+                *target
+            } else {
+                (*level - self.falloff * dt).max(*target)
+            };
+
+            if *level >= peak.level {
+                *peak = Peak {
+                    level: *level,
+                    age: 0.0,
+                };
+            } else {
+                peak.age += dt;
+                if peak.age > self.peak_hold_secs {
+                    peak.level = (peak.level - self.falloff * 0.5 * dt).max(*level);
+                }
+            }
+        }
+    }
+
+    fn gradient(&self, t: f32) -> Color {
+        self.low.lerp(self.high, t).scale(self.brightness)
+    }
+
+    fn render_bars(&self, fb: &mut Framebuffer) {
+        let bands = self.levels.len() as u32;
+        let bar_width = (self.width / bands).max(1);
+        let gap = u32::from(bar_width >= 3);
+        let offset = (self.width.saturating_sub(bar_width * bands)) / 2;
+        let h = self.height as f32;
+
+        for (band, (level, peak)) in self.levels.iter().zip(&self.peaks).enumerate() {
+            let x0 = (offset + band as u32 * bar_width) as i32;
+            let lit = (level * h).round() as i32;
+
+            for row in 0..lit {
+                let color = self.gradient(row as f32 / h);
+                let y = self.height as i32 - 1 - row;
+                fb.draw_hline(y, x0, x0 + (bar_width - gap) as i32 - 1, color);
+            }
+
+            if self.peak_hold && peak.level > 0.0 {
+                let row = ((peak.level * h).round() as i32).clamp(1, self.height as i32);
+                let y = self.height as i32 - row;
+                let color = self.peak_color.scale(self.brightness);
+                fb.draw_hline(y, x0, x0 + (bar_width - gap) as i32 - 1, color);
+            }
+        }
+    }
+
+    fn render_vu(&self, fb: &mut Framebuffer) {
+        let w = self.width as f32;
+        let lit = (self.levels[0] * w).round() as i32;
+
+        for col in 0..lit {
+            let color = self.gradient(col as f32 / w);
+            fb.draw_vline(col, 0, self.height as i32 - 1, color);
+        }
+
+        if self.peak_hold && self.peaks[0].level > 0.0 {
+            let col =
+                ((self.peaks[0].level * w).round() as i32 - 1).clamp(0, self.width as i32 - 1);
+            let color = self.peak_color.scale(self.brightness);
+            fb.draw_vline(col, 0, self.height as i32 - 1, color);
+        }
+    }
+}
+
+impl Default for SpectrumEffect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Effect for SpectrumEffect {
+    fn name(&self) -> &str {
+        "spectrum"
+    }
+
+    fn init(&mut self, config: &EffectConfig) {
+        self.width = config.width;
+        self.height = config.height;
+        // Sources given to `with_source` are kept
+        if matches!(self.source, AudioSource::Silence { .. }) {
+            self.open_source(&config.params);
+        }
+        self.window = vec![0.0; WINDOW];
+        self.levels.clear();
+        self.peaks.clear();
+        self.apply_params(&config.params);
+
+        tracing::debug!(
+            mode = ?self.mode,
+            bands = self.bands,
+            sample_rate = self.source.sample_rate(),
+            "Spectrum effect initialized"
+        );
+    }
+
+    fn tick(&mut self, fb: &mut Framebuffer, dt: Duration) -> bool {
+        self.poll_loading();
+        self.source.read_window(dt, &mut self.window);
+
+        let targets = self.analyze();
+        self.update_levels(&targets, dt.as_secs_f32());
+
+        fb.clear();
+        match self.mode {
+            SpectrumMode::Bars => self.render_bars(fb),
+            SpectrumMode::Vu => self.render_vu(fb),
+        }
+
+        true
+    }
+
+    fn cleanup(&mut self) {
+        // Drop the decoded clip and any pending load
+        self.loading = None;
+        self.source = AudioSource::Silence {
+            sample_rate: self.source.sample_rate(),
+        };
+        tracing::debug!("Spectrum effect cleaned up");
+    }
+
+    fn supports_hot_update(&self) -> bool {
+        true
+    }
+
+    fn update_params(&mut self, params: &EffectParams) {
+        let path = params.extra.get("path").and_then(|v| v.as_str());
+        if path != self.path.as_deref() {
+            self.open_source(params);
+        }
+        self.apply_params(params);
+        tracing::debug!(mode = ?self.mode, bands = self.bands, "Spectrum effect params updated");
+    }
+}
+
+/// Map a linear amplitude (1.0 = full scale) to 0.0 - 1.0 over `RANGE_DB`.
+fn normalize_db(amplitude: f32) -> f32 {
+    let db = 20.0 * amplitude.max(1e-6).log10();
+    ((db + RANGE_DB) / RANGE_DB).clamp(0.0, 1.0)
+}
+
+/// Group FFT bins into log-spaced bands and normalize each band's peak.
+fn band_levels(spectrum: &[f32], sample_rate: f32, bands: usize) -> Vec<f32> {
+    let bin_hz = sample_rate / (spectrum.len() * 2) as f32;
+    let max_freq = MAX_FREQ.min(sample_rate / 2.0);
+    let ratio = (max_freq / MIN_FREQ).powf(1.0 / bands as f32);
+
+    (0..bands)
+        .map(|band| {
+            let lo = MIN_FREQ * ratio.powi(band as i32);
+            let hi = lo * ratio;
+            let first = ((lo / bin_hz) as usize).min(spectrum.len() - 1);
+            let last = ((hi / bin_hz) as usize).clamp(first + 1, spectrum.len());
+            let peak = spectrum[first..last].iter().copied().fold(0.0, f32::max);
+            normalize_db(peak)
+        })
+        .collect()
+}
+
+fn rgb_param(params: &EffectParams, key: &str) -> Option<Color> {
+    let rgb: [u8; 3] = serde_json::from_value(params.extra.get(key)?.clone()).ok()?;
+    Some(Color::from(rgb))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::WavClip;
+    use sp_core::Point;
+    use std::f32::consts::PI;
+
+    fn sine_source(freq: f32, rate: u32) -> AudioSource {
+        let samples = (0..rate)
+            .map(|i| (2.0 * PI * freq * i as f32 / rate as f32).sin())
+            .collect();
+        AudioSource::Wav {
+            clip: WavClip {
+                sample_rate: rate,
+                samples,
+            },
+            position: 0.0,
+        }
+    }
+
+    fn config(extra: serde_json::Value) -> EffectConfig {
+        EffectConfig {
+            width: 64,
+            height: 32,
+            params: EffectParams {
+                intensity: 1.0,
+                speed: 1.0,
+                extra: extra.as_object().cloned().unwrap_or_default(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_band_levels_peak() {
+        let rate = 44_100.0;
+        let samples: Vec<f32> = (0..WINDOW)
+            .map(|i| (2.0 * PI * 1000.0 * i as f32 / rate).sin())
+            .collect();
+        let levels = band_levels(&magnitude_spectrum(&samples), rate, 16);
+
+        let loudest = levels
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i)
+            .unwrap();
+        let ratio = (MAX_FREQ / MIN_FREQ).powf(1.0 / 16.0);
+        let expected = ((1000.0 / MIN_FREQ).ln() / ratio.ln()) as usize;
+
+        assert!(
+            loudest.abs_diff(expected) <= 1,
+            "loudest band {loudest}, expected {expected}"
+        );
+        assert!(levels[loudest] > 0.9);
+    }
+
+    #[test]
+    fn test_bars_from_sine() {
+        let mut effect = SpectrumEffect::with_source(sine_source(1000.0, 44_100));
+        effect.init(&config(serde_json::json!({ "bands": 8 })));
+
+        let mut fb = Framebuffer::new(64, 32);
+        assert!(effect.tick(&mut fb, Duration::from_millis(100)));
+
+        let lit = fb.data().iter().filter(|c| **c != Color::BLACK).count();
+        assert!(lit > 0);
+        // Lowest band (40-80 Hz) stays dark for a 1 kHz tone
+        assert_eq!(fb.get(Point::new(0, 31)), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn test_silence_is_black() {
+        let mut effect = SpectrumEffect::with_source(AudioSource::Silence {
+            sample_rate: 44_100,
+        });
+        effect.init(&config(serde_json::json!({ "mode": "vu" })));
+
+        let mut fb = Framebuffer::new(64, 32);
+        effect.tick(&mut fb, Duration::from_millis(16));
+
+        assert!(fb.data().iter().all(|c| *c == Color::BLACK));
+    }
+
+    #[test]
+    fn test_path_inside_audio_dir() {
+        let dir = std::env::temp_dir().join(format!("sp-spectrum-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF\x2a\0\0\0WAVEfmt \x10\0\0\0\x01\0\x01\0");
+        wav.extend_from_slice(&8000u32.to_le_bytes());
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(b"\x02\0\x10\0data\x06\0\0\0");
+        wav.extend_from_slice(&[0, 0, 0, 0x40, 0, 0xc0]);
+        std::fs::write(dir.join("tone.wav"), wav).unwrap();
+
+        let registration = SpectrumEffect::registration(Some(dir.clone()), None);
+        let params = |path: &str| config(serde_json::json!({ "path": path })).params;
+        assert!(registration.validate(&params("tone.wav")).is_ok());
+        assert!(registration.validate(&params("../tone.wav")).is_err());
+        assert!(registration.validate(&params("/etc/passwd")).is_err());
+        assert!(SpectrumEffect::registration(None, None)
+            .validate(&params("tone.wav"))
+            .is_err());
+
+        let mut effect = SpectrumEffect::with_input(Some(dir.clone()), None);
+        effect.init(&config(serde_json::json!({ "path": "tone.wav" })));
+        let mut fb = Framebuffer::new(64, 32);
+        for _ in 0..100 {
+            effect.tick(&mut fb, Duration::ZERO);
+            if effect.loading.is_none() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(effect.source.sample_rate(), 8000);
+
+        // Dropping `path` goes back to the PCM input
+        let input = AudioInput::new(22_050);
+        effect.input = Some(input);
+        effect.update_params(&config(serde_json::json!({})).params);
+        assert!(matches!(effect.source, AudioSource::Pcm(_)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_falloff_and_peak_hold() {
+        let mut effect = SpectrumEffect::new();
+        effect.init(&config(serde_json::json!({
+            "mode": "vu",
+            "falloff": 1.0,
+            "peak_hold_ms": 1000
+        })));
+
+        effect.update_levels(&[1.0], 0.0);
+        effect.update_levels(&[0.0], 0.5);
+
+        assert!((effect.levels[0] - 0.5).abs() < 1e-6);
+        assert_eq!(effect.peaks[0].level, 1.0);
+    }
+}
@@ -0,0 +1,184 @@
+//! Text effect - static or scrolling text with the embedded 5x7 font.
+
+use sp_core::Color;
+use sp_renderer::{font, Framebuffer};
+use std::time::Duration;
+
+use crate::{Effect, EffectConfig, EffectParams, ParamKind, ParamSchema, ParamSpec};
+
+/// Maximum text length, in characters.
+pub const MAX_TEXT_LEN: usize = 256;
+
+/// Text effect - draws a line of text, optionally scrolling right to left.
+pub struct TextEffect {
+    width: u32,
+    height: u32,
+    text: String,
+    color: Color,
+    scroll: bool,
+    /// Scroll speed in pixels per second.
+    scroll_speed: f32,
+    /// Current scroll offset from the right edge, in pixels.
+    offset: f32,
+}
+
+impl TextEffect {
+    /// Create an empty text effect.
+    pub fn new() -> Self {
+        Self {
+            width: 64,
+            height: 32,
+            text: String::new(),
+            color: Color::WHITE,
+            scroll: false,
+            scroll_speed: 50.0,
+            offset: 0.0,
+        }
+    }
+
+    /// Parameters accepted by the text effect.
+    pub fn schema() -> ParamSchema {
+        ParamSchema::new()
+            .param(ParamSpec::new(
+                "text",
+                ParamKind::string(MAX_TEXT_LEN),
+                "Text to display",
+            ))
+            .param(
+                ParamSpec::new("color", ParamKind::Color, "Text color")
+                    .with_default([255, 255, 255]),
+            )
+            .param(
+                ParamSpec::new("scroll", ParamKind::Bool, "Scroll right to left")
+                    .with_default(false),
+            )
+            .param(
+                ParamSpec::new(
+                    "scroll_speed",
+                    ParamKind::integer(1, 200),
+                    "Scroll speed in pixels per second",
+                )
+                .with_default(50),
+            )
+    }
+
+    fn apply_params(&mut self, params: &EffectParams) {
+        let extra = &params.extra;
+
+        self.text = extra
+            .get("text")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .chars()
+            .take(MAX_TEXT_LEN)
+            .collect();
+        self.color = params.color.map_or(Color::WHITE, Color::from);
+        self.scroll = extra
+            .get("scroll")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        self.scroll_speed = extra
+            .get("scroll_speed")
+            .and_then(|v| v.as_u64())
+            .map_or(50.0, |s| s.clamp(1, 200) as f32);
+    }
+}
+
+impl Default for TextEffect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Effect for TextEffect {
+    fn name(&self) -> &str {
+        "text"
+    }
+
+    fn init(&mut self, config: &EffectConfig) {
+        self.width = config.width;
+        self.height = config.height;
+        self.offset = 0.0;
+        self.apply_params(&config.params);
+
+        tracing::debug!(text = %self.text, scroll = self.scroll, "Text effect initialized");
+    }
+
+    fn tick(&mut self, fb: &mut Framebuffer, dt: Duration) -> bool {
+        fb.clear();
+
+        let text_width = font::text_width(&self.text) as i32;
+        let y = (self.height as i32 - font::GLYPH_HEIGHT as i32) / 2;
+
+        let x = if self.scroll {
+            // Enter from the right edge, leave fully on the left, then wrap
+            let span = (self.width as i32 + text_width) as f32;
+            self.offset = (self.offset + self.scroll_speed * dt.as_secs_f32()) % span.max(1.0);
+            self.width as i32 - self.offset as i32
+        } else {
+            (self.width as i32 - text_width) / 2
+        };
+
+        fb.draw_text(x, y, &self.text, self.color);
+        true
+    }
+
+    fn supports_hot_update(&self) -> bool {
+        true
+    }
+
+    fn update_params(&mut self, params: &EffectParams) {
+        self.apply_params(params);
+    }
+
+    fn preferred_fps(&self) -> Option<u32> {
+        // Static text only changes with its parameters
+        (!self.scroll).then_some(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::Point;
+
+    fn config(extra: serde_json::Value) -> EffectConfig {
+        EffectConfig {
+            width: 64,
+            height: 32,
+            params: serde_json::from_value(extra).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_static_text_is_centered() {
+        let mut effect = TextEffect::new();
+        effect.init(&config(
+            serde_json::json!({ "text": "I", "color": [0, 255, 0] }),
+        ));
+        assert_eq!(effect.preferred_fps(), Some(1));
+
+        let mut fb = Framebuffer::new(64, 32);
+        effect.tick(&mut fb, Duration::ZERO);
+
+        // 'I' is 5 wide, centered at x = 29; its bar is the middle column
+        assert_eq!(fb.get(Point::new(31, 12)), Some(Color::GREEN));
+        assert_eq!(fb.get(Point::new(0, 12)), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn test_scrolling_text_moves() {
+        let mut effect = TextEffect::new();
+        effect.init(&config(
+            serde_json::json!({ "text": "Hello", "scroll": true, "scroll_speed": 100 }),
+        ));
+        assert_eq!(effect.preferred_fps(), None);
+
+        let mut first = Framebuffer::new(64, 32);
+        effect.tick(&mut first, Duration::from_millis(200));
+        let mut second = Framebuffer::new(64, 32);
+        effect.tick(&mut second, Duration::from_millis(200));
+
+        assert_ne!(first.data(), second.data());
+    }
+}
@@ -2,10 +2,22 @@
 //!
 //! Provides a trait-based system for creating and managing visual effects.
 
+pub mod audio;
 mod effects;
 mod manager;
+mod plugin;
+mod registry;
+mod schema;
+mod script;
 mod traits;
 
 pub use effects::*;
 pub use manager::EffectManager;
+pub use plugin::{PluginEffect, PluginLibrary, DEFAULT_PLUGIN_FUEL};
+pub use registry::{
+    validate_effect_name, EffectFactory, EffectInfo, EffectOrigin, EffectRegistration,
+    EffectRegistry, ParamValidator, RESERVED_NAMES,
+};
+pub use schema::{ParamKind, ParamSchema, ParamSpec};
+pub use script::{CompiledScript, ScriptEffect, ScriptInfo, ScriptLibrary, DEFAULT_TICK_BUDGET};
 pub use traits::{Effect, EffectConfig, EffectParams};
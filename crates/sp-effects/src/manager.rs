@@ -2,20 +2,32 @@
 
 use sp_core::{Error, Result};
 use sp_renderer::Framebuffer;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
-use crate::{create_effect, Effect, EffectConfig, EffectParams};
+use crate::{
+    CompiledScript, Effect, EffectConfig, EffectOrigin, EffectParams, EffectRegistry,
+    PluginLibrary, ScriptLibrary,
+};
 
 /// Manages effect lifecycle and transitions.
 pub struct EffectManager {
     current: Option<Box<dyn Effect>>,
     config: EffectConfig,
     last_tick: Instant,
+    registry: EffectRegistry,
+    scripts: ScriptLibrary,
+    revision: u64,
+    paused: bool,
 }
 
 impl EffectManager {
-    /// Create a new effect manager.
+    /// Create a new effect manager with the built-in effects.
     pub fn new(width: u32, height: u32) -> Self {
+        Self::with_registry(width, height, EffectRegistry::with_builtins())
+    }
+
+    /// Create a new effect manager using a custom registry.
+    pub fn with_registry(width: u32, height: u32, registry: EffectRegistry) -> Self {
         Self {
             current: None,
             config: EffectConfig {
@@ -24,6 +36,10 @@ impl EffectManager {
                 params: EffectParams::default(),
             },
             last_tick: Instant::now(),
+            registry,
+            scripts: ScriptLibrary::default(),
+            revision: 0,
+            paused: false,
         }
     }
 
@@ -32,22 +48,124 @@ impl EffectManager {
         self.current.as_ref().map(|e| e.name())
     }
 
+    /// Get the parameters of the current effect.
+    pub fn params(&self) -> &EffectParams {
+        &self.config.params
+    }
+
+    /// Preferred frame rate of the current effect.
+    pub fn preferred_fps(&self) -> Option<u32> {
+        self.current.as_ref().and_then(|e| e.preferred_fps())
+    }
+
+    /// Counter bumped whenever the effect or its parameters change.
+    ///
+    /// Lets the render loop redraw immediately even when the effect idles
+    /// at a low frame rate.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// List all registered effect names.
+    pub fn available_effects(&self) -> Vec<String> {
+        self.registry.names()
+    }
+
+    /// Get the effect registry.
+    pub fn registry(&self) -> &EffectRegistry {
+        &self.registry
+    }
+
+    /// Get the effect registry for runtime registration.
+    pub fn registry_mut(&mut self) -> &mut EffectRegistry {
+        &mut self.registry
+    }
+
+    /// Get the uploaded script library.
+    pub fn scripts(&self) -> &ScriptLibrary {
+        &self.scripts
+    }
+
+    /// Set the CPU-time budget for script effects created from now on.
+    pub fn set_script_tick_budget(&mut self, budget: std::time::Duration) {
+        self.scripts.set_tick_budget(budget);
+        for info in self.scripts.list() {
+            if let Some(registration) = self.scripts.registration(&info.name) {
+                self.registry.register_or_replace(registration);
+            }
+        }
+    }
+
+    /// Compile, store and register a script effect.
+    pub fn insert_script(&mut self, name: &str, source: &str) -> Result<()> {
+        self.insert_compiled_script(CompiledScript::compile(name, source)?)
+    }
+
+    /// Store and register a script compiled with [`CompiledScript::compile`].
+    pub fn insert_compiled_script(&mut self, script: CompiledScript) -> Result<()> {
+        let name = script.name().to_string();
+        if self
+            .registry
+            .get(&name)
+            .is_some_and(|existing| existing.origin != EffectOrigin::Script)
+        {
+            return Err(Error::invalid_param(
+                "name",
+                format!("effect '{name}' is already registered"),
+            ));
+        }
+
+        self.scripts.insert_compiled(script);
+        if let Some(registration) = self.scripts.registration(&name) {
+            self.registry.register_or_replace(registration);
+        }
+        Ok(())
+    }
+
+    /// Remove a script effect. Returns `false` if it did not exist.
+    pub fn remove_script(&mut self, name: &str) -> bool {
+        if !self.scripts.remove(name) {
+            return false;
+        }
+        self.registry.unregister(name);
+        true
+    }
+
+    /// Register all plugins from a library, skipping names already taken.
+    pub fn register_plugins(&mut self, plugins: &PluginLibrary) -> usize {
+        let mut registered = 0;
+        for registration in plugins.registrations() {
+            let name = registration.name.clone();
+            match self.registry.register(registration) {
+                Ok(()) => registered += 1,
+                Err(e) => tracing::warn!(plugin = %name, error = %e, "Skipping plugin"),
+            }
+        }
+        registered
+    }
+
     /// Switch to a new effect.
     pub fn set_effect(&mut self, name: &str, params: EffectParams) -> Result<()> {
+        // Validate and create the new effect before touching the current one
+        let registration = self
+            .registry
+            .get(name)
+            .ok_or_else(|| Error::EffectNotFound(name.to_string()))?;
+        registration.validate(&params)?;
+        let mut effect = registration.create()?;
+
         // Cleanup current effect
         if let Some(ref mut effect) = self.current {
             effect.cleanup();
         }
 
-        // Create new effect
-        let mut effect = create_effect(name).ok_or_else(|| Error::EffectNotFound(name.to_string()))?;
-
         // Initialize with config
         self.config.params = params;
         effect.init(&self.config);
 
         self.current = Some(effect);
         self.last_tick = Instant::now();
+        self.revision += 1;
 
         tracing::info!(effect = name, "Switched to effect");
         Ok(())
@@ -56,9 +174,14 @@ impl EffectManager {
     /// Update effect parameters without restart.
     pub fn update_params(&mut self, params: EffectParams) -> Result<()> {
         if let Some(ref mut effect) = self.current {
+            if let Some(registration) = self.registry.get(effect.name()) {
+                registration.validate(&params)?;
+            }
+
             if effect.supports_hot_update() {
                 effect.update_params(&params);
                 self.config.params = params;
+                self.revision += 1;
                 Ok(())
             } else {
                 // Restart effect with new params
@@ -70,8 +193,54 @@ impl EffectManager {
         }
     }
 
+    /// Merge a partial set of parameters into the current ones and apply them.
+    ///
+    /// Returns the resulting full parameter set.
+    pub fn patch_params(
+        &mut self,
+        patch: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<EffectParams> {
+        let params = self.config.params.merged(patch).map_err(|e| match e {
+            Error::Serialization(e) => Error::invalid_param("params", e.to_string()),
+            e => e,
+        })?;
+        self.update_params(params)?;
+        Ok(self.config.params.clone())
+    }
+
+    /// Freeze the current effect, keeping its state, until [`resume`](Self::resume).
+    ///
+    /// While paused `tick` leaves the framebuffer untouched. Effects started
+    /// meanwhile are initialized as usual and also wait for `resume`.
+    pub fn pause(&mut self) {
+        if !self.paused {
+            self.paused = true;
+            tracing::debug!(effect = ?self.current_effect(), "Effect paused");
+        }
+    }
+
+    /// Continue a paused effect where it left off.
+    ///
+    /// The clock restarts, so the pause isn't seen as one long frame.
+    pub fn resume(&mut self) {
+        if self.paused {
+            self.paused = false;
+            self.last_tick = Instant::now();
+            self.revision += 1;
+            tracing::debug!(effect = ?self.current_effect(), "Effect resumed");
+        }
+    }
+
+    /// Whether the current effect is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
     /// Generate the next frame.
     pub fn tick(&mut self, fb: &mut Framebuffer) -> bool {
+        if self.paused {
+            return true;
+        }
         let now = Instant::now();
         let dt = now.duration_since(self.last_tick);
         self.last_tick = now;
@@ -90,6 +259,7 @@ impl EffectManager {
             effect.cleanup();
         }
         self.current = None;
+        self.revision += 1;
         tracing::info!("Effect stopped");
     }
 }
@@ -104,9 +274,7 @@ mod tests {
 
         assert!(manager.current_effect().is_none());
 
-        manager
-            .set_effect("fire", EffectParams::default())
-            .unwrap();
+        manager.set_effect("fire", EffectParams::default()).unwrap();
         assert_eq!(manager.current_effect(), Some("fire"));
 
         let mut fb = Framebuffer::new(64, 32);
@@ -114,6 +282,115 @@ mod tests {
         assert!(continuing);
     }
 
+    #[test]
+    fn test_script_effect() {
+        let mut manager = EffectManager::new(64, 32);
+        manager
+            .insert_script("red", "fn tick(t, dt) { fill(255, 0, 0); }")
+            .unwrap();
+        assert!(manager.insert_script("fire", "fn tick(t, dt) {}").is_err());
+
+        assert!(manager.available_effects().contains(&"red".to_string()));
+        manager.set_effect("red", EffectParams::default()).unwrap();
+        assert_eq!(manager.current_effect(), Some("red"));
+
+        let mut fb = Framebuffer::new(64, 32);
+        manager.tick(&mut fb);
+        assert_eq!(fb.data()[0], sp_core::Color::RED);
+
+        assert!(manager.remove_script("red"));
+        assert!(!manager.available_effects().contains(&"red".to_string()));
+    }
+
+    #[test]
+    fn test_param_validation() {
+        let mut manager = EffectManager::new(64, 32);
+        let bad = EffectParams {
+            intensity: 5.0,
+            ..Default::default()
+        };
+
+        let result = manager.set_effect("fire", bad.clone());
+        assert!(matches!(result, Err(Error::InvalidParameter { .. })));
+        assert!(manager.current_effect().is_none());
+
+        manager.set_effect("fire", EffectParams::default()).unwrap();
+        let result = manager.update_params(bad);
+        assert!(matches!(result, Err(Error::InvalidParameter { .. })));
+    }
+
+    #[test]
+    fn test_patch_params() {
+        let mut manager = EffectManager::new(64, 32);
+        let params = EffectParams {
+            speed: 2.0,
+            ..Default::default()
+        };
+        manager.set_effect("fire", params).unwrap();
+
+        let patch = serde_json::json!({ "intensity": 0.3 });
+        let merged = manager.patch_params(patch.as_object().unwrap()).unwrap();
+
+        assert_eq!(merged.intensity, 0.3);
+        assert_eq!(merged.speed, 2.0);
+        assert_eq!(manager.params().intensity, 0.3);
+        assert_eq!(manager.current_effect(), Some("fire"));
+
+        let patch = serde_json::json!({ "intensity": "hot" });
+        assert!(manager.patch_params(patch.as_object().unwrap()).is_err());
+        assert_eq!(manager.params().intensity, 0.3);
+    }
+
+    #[test]
+    fn test_revision_and_fps() {
+        let mut manager = EffectManager::new(64, 32);
+        assert_eq!(manager.preferred_fps(), None);
+
+        manager
+            .set_effect("solid", EffectParams::default())
+            .unwrap();
+        assert_eq!(manager.preferred_fps(), Some(1));
+        let revision = manager.revision();
+
+        let patch = serde_json::json!({ "color": [0, 0, 255] });
+        manager.patch_params(patch.as_object().unwrap()).unwrap();
+        assert!(manager.revision() > revision);
+
+        manager.set_effect("fire", EffectParams::default()).unwrap();
+        assert_eq!(manager.preferred_fps(), None);
+    }
+
+    #[test]
+    fn test_pause_keeps_state() {
+        let mut manager = EffectManager::new(16, 8);
+        let params = EffectParams {
+            extra: serde_json::json!({ "text": "Hi", "scroll": true })
+                .as_object()
+                .unwrap()
+                .clone(),
+            ..Default::default()
+        };
+        manager.set_effect("text", params).unwrap();
+
+        let mut fb = Framebuffer::new(16, 8);
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        manager.tick(&mut fb);
+        let scrolled = fb.clone();
+
+        manager.pause();
+        let mut paused = Framebuffer::new(16, 8);
+        manager.tick(&mut paused);
+        assert!(paused.data().iter().all(|c| *c == sp_core::Color::BLACK));
+
+        // Resuming continues from the same scroll offset instead of restarting
+        let revision = manager.revision();
+        manager.resume();
+        assert!(manager.revision() > revision);
+        manager.tick(&mut fb);
+        assert_eq!(fb.data(), scrolled.data());
+        assert_eq!(manager.current_effect(), Some("text"));
+    }
+
     #[test]
     fn test_invalid_effect() {
         let mut manager = EffectManager::new(64, 32);
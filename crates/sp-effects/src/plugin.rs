@@ -0,0 +1,461 @@
+//! WebAssembly plugin effects.
+//!
+//! Plugins are `.wasm` modules with no imports that export:
+//!
+//! - `memory`: linear memory
+//! - `init(width: i32, height: i32, params_ptr: i32, params_len: i32)`
+//! - `tick(dt_ms: i32) -> i32`: render the next frame, return 0 when finished
+//! - `frame() -> i32`: offset of the `width * height * 3` RGB frame in memory
+//! - `alloc(len: i32) -> i32` (optional): reserve memory for the params JSON
+//!
+//! Each call runs with a fixed fuel budget so a runaway plugin traps instead
+//! of stalling the render loop.
+
+use sp_core::{Color, Error, Result};
+use sp_renderer::Framebuffer;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use wasmi::{
+    Engine, ExternType, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc,
+};
+
+use crate::{validate_effect_name, Effect, EffectConfig, EffectOrigin, EffectRegistration};
+
+/// Default fuel (roughly, executed instructions) per plugin call.
+pub const DEFAULT_PLUGIN_FUEL: u64 = 5_000_000;
+
+/// Maximum linear memory a plugin may grow to.
+const MAX_MEMORY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Exports every plugin must provide.
+const REQUIRED_EXPORTS: [&str; 4] = ["memory", "init", "tick", "frame"];
+
+/// Effect backed by an instantiated WebAssembly plugin.
+pub struct PluginEffect {
+    name: String,
+    store: Store<StoreLimits>,
+    memory: Memory,
+    init: TypedFunc<(i32, i32, i32, i32), ()>,
+    tick: TypedFunc<i32, i32>,
+    frame: TypedFunc<(), i32>,
+    alloc: Option<TypedFunc<i32, i32>>,
+    fuel: u64,
+    fuel_added: u64,
+    failed: bool,
+    frame_bytes: Vec<u8>,
+}
+
+impl PluginEffect {
+    /// Instantiate a compiled plugin module.
+    pub fn new(
+        name: impl Into<String>,
+        engine: &Engine,
+        module: &Module,
+        fuel: u64,
+    ) -> Result<Self> {
+        let name = name.into();
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY_BYTES)
+            .instances(1)
+            .build();
+        let mut store = Store::new(engine, limits);
+        store.limiter(|limits| limits);
+
+        let plugin_err =
+            |e: &dyn std::fmt::Display| Error::Internal(format!("plugin '{name}': {e}"));
+
+        // No host functions are linked: plugins cannot import anything
+        let linker = Linker::<StoreLimits>::new(engine);
+        let instance = linker
+            .instantiate(&mut store, module)
+            .and_then(|pre| pre.ensure_no_start(&mut store).map_err(Into::into))
+            .map_err(|e| plugin_err(&e))?;
+
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| plugin_err(&"missing memory export"))?;
+        let init = instance
+            .get_typed_func(&store, "init")
+            .map_err(|e| plugin_err(&e))?;
+        let tick = instance
+            .get_typed_func(&store, "tick")
+            .map_err(|e| plugin_err(&e))?;
+        let frame = instance
+            .get_typed_func(&store, "frame")
+            .map_err(|e| plugin_err(&e))?;
+        let alloc = instance.get_typed_func(&store, "alloc").ok();
+
+        Ok(Self {
+            name,
+            store,
+            memory,
+            init,
+            tick,
+            frame,
+            alloc,
+            fuel,
+            fuel_added: 0,
+            failed: false,
+            frame_bytes: Vec::new(),
+        })
+    }
+
+    /// Top up remaining fuel to exactly one call's budget.
+    fn refuel(&mut self) -> Result<()> {
+        let consumed = self.store.fuel_consumed().unwrap_or(0);
+        let remaining = self.fuel_added.saturating_sub(consumed);
+        if remaining < self.fuel {
+            let delta = self.fuel - remaining;
+            self.store
+                .add_fuel(delta)
+                .map_err(|e| Error::Internal(e.to_string()))?;
+            self.fuel_added += delta;
+        }
+        Ok(())
+    }
+
+    fn run_init(&mut self, config: &EffectConfig) -> Result<()> {
+        let params = serde_json::to_vec(&config.params)?;
+
+        self.refuel()?;
+        let (ptr, len) = match self.alloc {
+            Some(alloc) => {
+                let ptr = alloc
+                    .call(&mut self.store, params.len() as i32)
+                    .map_err(|e| Error::Internal(e.to_string()))?;
+                self.memory
+                    .write(&mut self.store, ptr as u32 as usize, &params)
+                    .map_err(|e| Error::Internal(e.to_string()))?;
+                (ptr, params.len() as i32)
+            }
+            None => (0, 0),
+        };
+
+        self.refuel()?;
+        self.init
+            .call(
+                &mut self.store,
+                (config.width as i32, config.height as i32, ptr, len),
+            )
+            .map_err(|e| Error::Internal(e.to_string()))
+    }
+
+    fn run_tick(&mut self, dt: Duration) -> Result<bool> {
+        self.refuel()?;
+        let dt_ms = dt.as_millis().min(i32::MAX as u128) as i32;
+        let continuing = self
+            .tick
+            .call(&mut self.store, dt_ms)
+            .map_err(|e| Error::Internal(e.to_string()))?;
+
+        let ptr = self
+            .frame
+            .call(&mut self.store, ())
+            .map_err(|e| Error::Internal(e.to_string()))?;
+        self.memory
+            .read(&self.store, ptr as u32 as usize, &mut self.frame_bytes)
+            .map_err(|e| Error::Internal(e.to_string()))?;
+
+        Ok(continuing != 0)
+    }
+}
+
+impl Effect for PluginEffect {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn init(&mut self, config: &EffectConfig) {
+        self.frame_bytes = vec![0; (config.width * config.height * 3) as usize];
+        self.failed = false;
+
+        if let Err(e) = self.run_init(config) {
+            tracing::warn!(plugin = %self.name, error = %e, "Plugin init failed");
+            self.failed = true;
+        }
+
+        tracing::debug!(plugin = %self.name, "Plugin effect initialized");
+    }
+
+    fn tick(&mut self, fb: &mut Framebuffer, dt: Duration) -> bool {
+        if self.failed {
+            fb.clear();
+            return false;
+        }
+
+        match self.run_tick(dt) {
+            Ok(continuing) => {
+                for (pixel, rgb) in fb
+                    .data_mut()
+                    .iter_mut()
+                    .zip(self.frame_bytes.chunks_exact(3))
+                {
+                    *pixel = Color::new(rgb[0], rgb[1], rgb[2]);
+                }
+                continuing
+            }
+            Err(e) => {
+                tracing::warn!(plugin = %self.name, error = %e, "Plugin tick failed, stopping effect");
+                self.failed = true;
+                fb.clear();
+                false
+            }
+        }
+    }
+}
+
+/// Compiled plugin modules loaded from disk.
+pub struct PluginLibrary {
+    engine: Engine,
+    plugins: BTreeMap<String, Plugin>,
+    fuel: u64,
+}
+
+struct Plugin {
+    module: Arc<Module>,
+    path: Option<PathBuf>,
+}
+
+impl PluginLibrary {
+    /// Create an empty library with a per-call fuel budget.
+    pub fn new(fuel: u64) -> Self {
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+
+        Self {
+            engine: Engine::new(&config),
+            plugins: BTreeMap::new(),
+            fuel,
+        }
+    }
+
+    /// Load every `.wasm` file in a directory, named after the file stem.
+    ///
+    /// A missing directory loads nothing. Invalid modules are logged and skipped.
+    pub fn load_dir(&mut self, dir: impl AsRef<Path>) -> Result<usize> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            tracing::debug!(dir = %dir.display(), "Plugin directory not found");
+            return Ok(0);
+        }
+
+        let mut loaded = 0;
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("wasm") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()).map(String::from) else {
+                continue;
+            };
+
+            match std::fs::read(&path)
+                .map_err(Error::from)
+                .and_then(|bytes| self.insert(&name, &bytes))
+            {
+                Ok(()) => {
+                    if let Some(plugin) = self.plugins.get_mut(&name) {
+                        plugin.path = Some(path.clone());
+                    }
+                    loaded += 1;
+                }
+                Err(e) => tracing::warn!(path = %path.display(), error = %e, "Skipping plugin"),
+            }
+        }
+
+        tracing::info!(dir = %dir.display(), count = loaded, "Loaded plugins");
+        Ok(loaded)
+    }
+
+    /// Compile and validate a plugin module; names follow the script rules.
+    pub fn insert(&mut self, name: &str, wasm: &[u8]) -> Result<()> {
+        validate_effect_name(name)?;
+        let module = Module::new(&self.engine, wasm)
+            .map_err(|e| Error::invalid_param("module", e.to_string()))?;
+
+        for required in REQUIRED_EXPORTS {
+            if !module.exports().any(|e| e.name() == required) {
+                return Err(Error::invalid_param(
+                    "module",
+                    format!("missing export '{required}'"),
+                ));
+            }
+        }
+        if module.imports().len() > 0 {
+            return Err(Error::invalid_param(
+                "module",
+                "plugins must not import anything",
+            ));
+        }
+        if !module
+            .exports()
+            .any(|e| e.name() == "memory" && matches!(e.ty(), ExternType::Memory(_)))
+        {
+            return Err(Error::invalid_param(
+                "module",
+                "'memory' must be a memory export",
+            ));
+        }
+
+        self.plugins.insert(
+            name.to_string(),
+            Plugin {
+                module: Arc::new(module),
+                path: None,
+            },
+        );
+        Ok(())
+    }
+
+    /// List loaded plugin names.
+    pub fn names(&self) -> Vec<String> {
+        self.plugins.keys().cloned().collect()
+    }
+
+    /// Check whether a plugin is loaded.
+    pub fn contains(&self, name: &str) -> bool {
+        self.plugins.contains_key(name)
+    }
+
+    /// Path a plugin was loaded from, if any.
+    pub fn path(&self, name: &str) -> Option<&Path> {
+        self.plugins.get(name).and_then(|p| p.path.as_deref())
+    }
+
+    /// Build a registry entry for a loaded plugin.
+    pub fn registration(&self, name: &str) -> Option<EffectRegistration> {
+        let module = self.plugins.get(name)?.module.clone();
+        let engine = self.engine.clone();
+        let fuel = self.fuel;
+        let plugin_name = name.to_string();
+
+        let registration = EffectRegistration::fallible(name, "WebAssembly plugin", move || {
+            PluginEffect::new(plugin_name.clone(), &engine, &module, fuel)
+                .map(|effect| Box::new(effect) as Box<dyn Effect>)
+        });
+        Some(registration.with_origin(EffectOrigin::Plugin))
+    }
+
+    /// Build registry entries for all loaded plugins.
+    pub fn registrations(&self) -> Vec<EffectRegistration> {
+        self.plugins
+            .keys()
+            .filter_map(|name| self.registration(name))
+            .collect()
+    }
+}
+
+impl Default for PluginLibrary {
+    fn default() -> Self {
+        Self::new(DEFAULT_PLUGIN_FUEL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EffectParams;
+    use sp_core::Point;
+
+    /// Fills the frame red and stores the params length in pixel 0's green channel.
+    const RED_PLUGIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (global $w (mut i32) (i32.const 0))
+          (global $h (mut i32) (i32.const 0))
+          (global $len (mut i32) (i32.const 0))
+          (func (export "alloc") (param i32) (result i32) (i32.const 32768))
+          (func (export "init") (param i32 i32 i32 i32)
+            (global.set $w (local.get 0))
+            (global.set $h (local.get 1))
+            (global.set $len (local.get 3)))
+          (func (export "frame") (result i32) (i32.const 0))
+          (func (export "tick") (param i32) (result i32) (local $i i32)
+            (block $done
+              (loop $next
+                (br_if $done (i32.ge_u (local.get $i)
+                  (i32.mul (i32.mul (global.get $w) (global.get $h)) (i32.const 3))))
+                (i32.store8 (local.get $i) (i32.const 255))
+                (local.set $i (i32.add (local.get $i) (i32.const 3)))
+                (br $next)))
+            (i32.store8 (i32.const 1) (global.get $len))
+            (i32.const 1)))
+    "#;
+
+    const RUNAWAY_PLUGIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "init") (param i32 i32 i32 i32))
+          (func (export "frame") (result i32) (i32.const 0))
+          (func (export "tick") (param i32) (result i32)
+            (loop $forever (br $forever))
+            (i32.const 1)))
+    "#;
+
+    fn config() -> EffectConfig {
+        EffectConfig {
+            width: 8,
+            height: 4,
+            params: EffectParams::default(),
+        }
+    }
+
+    #[test]
+    fn test_plugin_renders() {
+        let mut library = PluginLibrary::default();
+        library
+            .insert("red", &wat::parse_str(RED_PLUGIN).unwrap())
+            .unwrap();
+        assert_eq!(library.names(), vec!["red".to_string()]);
+
+        let mut effect = library.registration("red").unwrap().create().unwrap();
+        effect.init(&config());
+
+        let mut fb = Framebuffer::new(8, 4);
+        assert!(effect.tick(&mut fb, Duration::from_millis(16)));
+
+        let params_len = serde_json::to_vec(&config().params).unwrap().len() as u8;
+        assert_eq!(
+            fb.get(Point::new(0, 0)),
+            Some(Color::new(255, params_len, 0))
+        );
+        assert_eq!(fb.get(Point::new(7, 3)), Some(Color::RED));
+    }
+
+    #[test]
+    fn test_fuel_limit() {
+        let mut library = PluginLibrary::new(100_000);
+        library
+            .insert("spin", &wat::parse_str(RUNAWAY_PLUGIN).unwrap())
+            .unwrap();
+
+        let mut effect = library.registration("spin").unwrap().create().unwrap();
+        effect.init(&config());
+
+        let mut fb = Framebuffer::new(8, 4);
+        assert!(!effect.tick(&mut fb, Duration::from_millis(16)));
+    }
+
+    #[test]
+    fn test_rejects_invalid_modules() {
+        let mut library = PluginLibrary::default();
+
+        let missing_tick = wat::parse_str(r#"(module (memory (export "memory") 1))"#).unwrap();
+        assert!(library.insert("partial", &missing_tick).is_err());
+        assert!(library.insert("garbage", b"not wasm").is_err());
+
+        // A live effect would silently replace a plugin with its name
+        let red = wat::parse_str(RED_PLUGIN).unwrap();
+        assert!(library.insert("canvas", &red).is_err());
+        assert!(library.insert("Red Plugin", &red).is_err());
+        assert!(library.names().is_empty());
+    }
+
+    #[test]
+    fn test_load_missing_dir() {
+        let mut library = PluginLibrary::default();
+        assert_eq!(library.load_dir("/nonexistent/plugins").unwrap(), 0);
+    }
+}
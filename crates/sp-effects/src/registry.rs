@@ -0,0 +1,289 @@
+//! Effect registry - maps effect names to factories and metadata.
+
+use serde::Serialize;
+use sp_core::{Error, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{Effect, EffectParams, ParamSchema};
+
+/// Function creating a fresh effect instance.
+pub type EffectFactory = Arc<dyn Fn() -> Result<Box<dyn Effect>> + Send + Sync>;
+
+/// Check run on parameters after the schema, for rules it can't express.
+pub type ParamValidator = Arc<dyn Fn(&EffectParams) -> Result<()> + Send + Sync>;
+
+/// Where a registered effect comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectOrigin {
+    /// Compiled into sp-effects.
+    Builtin,
+    /// Uploaded Rhai script.
+    Script,
+    /// WebAssembly plugin.
+    Plugin,
+    /// Registered at runtime by another crate.
+    External,
+}
+
+/// A registered effect: name, description, factory and parameter schema.
+#[derive(Clone)]
+pub struct EffectRegistration {
+    pub name: String,
+    pub description: String,
+    pub origin: EffectOrigin,
+    pub schema: ParamSchema,
+    factory: EffectFactory,
+    validator: Option<ParamValidator>,
+}
+
+impl EffectRegistration {
+    /// Register an effect with an infallible factory.
+    pub fn new<F>(name: impl Into<String>, description: impl Into<String>, factory: F) -> Self
+    where
+        F: Fn() -> Box<dyn Effect> + Send + Sync + 'static,
+    {
+        Self::fallible(name, description, move || Ok(factory()))
+    }
+
+    /// Register an effect whose factory may fail (e.g. plugin instantiation).
+    pub fn fallible<F>(name: impl Into<String>, description: impl Into<String>, factory: F) -> Self
+    where
+        F: Fn() -> Result<Box<dyn Effect>> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            origin: EffectOrigin::External,
+            schema: ParamSchema::default(),
+            factory: Arc::new(factory),
+            validator: None,
+        }
+    }
+
+    /// Set the parameter schema.
+    pub fn with_schema(mut self, schema: ParamSchema) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Set the origin.
+    pub fn with_origin(mut self, origin: EffectOrigin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Add a check run on parameters after the schema.
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&EffectParams) -> Result<()> + Send + Sync + 'static,
+    {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Check parameters against the schema and the validator, if any.
+    pub fn validate(&self, params: &EffectParams) -> Result<()> {
+        self.schema.validate(params)?;
+        match &self.validator {
+            Some(validator) => validator(params),
+            None => Ok(()),
+        }
+    }
+
+    /// Create a new instance of the effect.
+    pub fn create(&self) -> Result<Box<dyn Effect>> {
+        (self.factory)()
+    }
+}
+
+impl fmt::Debug for EffectRegistration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EffectRegistration")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .field("origin", &self.origin)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Serializable summary of a registered effect.
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectInfo {
+    pub name: String,
+    pub description: String,
+    pub origin: EffectOrigin,
+    pub schema: ParamSchema,
+}
+
+impl From<&EffectRegistration> for EffectInfo {
+    fn from(reg: &EffectRegistration) -> Self {
+        Self {
+            name: reg.name.clone(),
+            description: reg.description.clone(),
+            origin: reg.origin,
+            schema: reg.schema.clone(),
+        }
+    }
+}
+
+/// Effects the API and its receivers register when they start, replacing
+/// whatever has the name: live inputs and the spectrum built-in reading the
+/// configured audio. Scripts and plugins can't use these names.
+pub const RESERVED_NAMES: [&str; 5] = ["canvas", "dmx", "realtime", "spectrum", "stream"];
+
+/// Check the name of a script or plugin: 1-32 characters of a-z, 0-9, `_`
+/// or `-`, and not one of [`RESERVED_NAMES`].
+pub fn validate_effect_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 32
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+    if !valid {
+        return Err(Error::invalid_param(
+            "name",
+            "must be 1-32 characters of a-z, 0-9, '_' or '-'",
+        ));
+    }
+    if RESERVED_NAMES.contains(&name) {
+        return Err(Error::invalid_param(
+            "name",
+            format!("'{name}' is reserved for a built-in effect"),
+        ));
+    }
+    Ok(())
+}
+
+/// Registry of all effects that can be created by name.
+#[derive(Debug, Clone, Default)]
+pub struct EffectRegistry {
+    effects: BTreeMap<String, EffectRegistration>,
+}
+
+impl EffectRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry containing the built-in effects.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        crate::effects::register_builtins(&mut registry);
+        registry
+    }
+
+    /// Register an effect. Fails if the name is already taken.
+    pub fn register(&mut self, registration: EffectRegistration) -> Result<()> {
+        if self.effects.contains_key(&registration.name) {
+            return Err(Error::invalid_param(
+                "name",
+                format!("effect '{}' is already registered", registration.name),
+            ));
+        }
+        self.effects.insert(registration.name.clone(), registration);
+        Ok(())
+    }
+
+    /// Register an effect, replacing any existing one with the same name.
+    pub fn register_or_replace(&mut self, registration: EffectRegistration) {
+        self.effects.insert(registration.name.clone(), registration);
+    }
+
+    /// Remove an effect from the registry.
+    pub fn unregister(&mut self, name: &str) -> Option<EffectRegistration> {
+        self.effects.remove(name)
+    }
+
+    /// Look up a registration.
+    pub fn get(&self, name: &str) -> Option<&EffectRegistration> {
+        self.effects.get(name)
+    }
+
+    /// Check whether an effect is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.effects.contains_key(name)
+    }
+
+    /// Create an effect by name.
+    pub fn create(&self, name: &str) -> Result<Box<dyn Effect>> {
+        self.effects
+            .get(name)
+            .ok_or_else(|| Error::EffectNotFound(name.to_string()))?
+            .create()
+    }
+
+    /// Registered effect names, sorted.
+    pub fn names(&self) -> Vec<String> {
+        self.effects.keys().cloned().collect()
+    }
+
+    /// Iterate over registrations, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = &EffectRegistration> {
+        self.effects.values()
+    }
+
+    /// Serializable summaries of all registered effects.
+    pub fn infos(&self) -> Vec<EffectInfo> {
+        self.iter().map(EffectInfo::from).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SolidEffect;
+
+    #[test]
+    fn test_builtins() {
+        let registry = EffectRegistry::with_builtins();
+
+        for name in ["fire", "off", "solid", "spectrum", "text"] {
+            let reg = registry.get(name).unwrap();
+            assert_eq!(reg.origin, EffectOrigin::Builtin);
+            assert!(!reg.description.is_empty());
+        }
+        assert!(registry.create("fire").is_ok());
+        assert!(matches!(
+            registry.create("nope"),
+            Err(Error::EffectNotFound(_))
+        ));
+
+        // The deprecated free functions keep their original signatures
+        #[allow(deprecated)]
+        {
+            let names: &'static [&'static str] = crate::available_effects();
+            assert_eq!(names, registry.names());
+            assert!(crate::create_effect("fire").is_some());
+        }
+    }
+
+    #[test]
+    fn test_validate_effect_name() {
+        assert!(validate_effect_name("my-fx_2").is_ok());
+        for name in ["", "Bad Name", "ünï", "canvas", "realtime"] {
+            assert!(validate_effect_name(name).is_err(), "{name:?}");
+        }
+        assert!(validate_effect_name(&"x".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn test_runtime_registration() {
+        let mut registry = EffectRegistry::with_builtins();
+
+        let reg = EffectRegistration::new("blue", "Solid blue", || {
+            Box::new(SolidEffect::with_color(sp_core::Color::BLUE))
+        });
+        registry.register(reg.clone()).unwrap();
+        assert!(registry.register(reg).is_err());
+
+        assert_eq!(registry.get("blue").unwrap().origin, EffectOrigin::External);
+        assert!(registry.names().contains(&"blue".to_string()));
+
+        assert!(registry.unregister("blue").is_some());
+        assert!(!registry.contains("blue"));
+    }
+}
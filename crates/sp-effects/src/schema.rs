@@ -0,0 +1,368 @@
+//! Effect parameter schemas and validation.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sp_core::{Error, Result};
+
+use crate::EffectParams;
+
+/// Fields of `EffectParams` every effect accepts.
+const COMMON_PARAMS: [&str; 3] = ["intensity", "speed", "color"];
+
+/// Specs of [`COMMON_PARAMS`], exported for effects that don't describe them.
+fn common_specs() -> [ParamSpec; 3] {
+    [
+        ParamSpec::new("intensity", ParamKind::float(0.0, 1.0), "Effect intensity")
+            .with_default(0.8),
+        ParamSpec::new("speed", ParamKind::float(0.0, 10.0), "Speed multiplier").with_default(1.0),
+        ParamSpec::new("color", ParamKind::Color, "Primary color"),
+    ]
+}
+
+/// Type of a single effect parameter, with its constraints.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ParamKind {
+    Float {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+    },
+    Integer {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max: Option<i64>,
+    },
+    Bool,
+    String {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_length: Option<usize>,
+    },
+    /// One of a fixed set of strings.
+    Enum {
+        options: Vec<String>,
+    },
+    /// RGB color as `[r, g, b]`.
+    Color,
+}
+
+impl ParamKind {
+    /// Float within an inclusive range.
+    pub fn float(min: f64, max: f64) -> Self {
+        Self::Float {
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    /// Integer within an inclusive range.
+    pub fn integer(min: i64, max: i64) -> Self {
+        Self::Integer {
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    /// Free-form string up to a maximum length.
+    pub fn string(max_length: usize) -> Self {
+        Self::String {
+            max_length: Some(max_length),
+        }
+    }
+
+    /// One of the given options.
+    pub fn one_of<I, S>(options: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Enum {
+            options: options.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Check a JSON value against this kind, returning a message on failure.
+    fn check(&self, value: &Value) -> std::result::Result<(), String> {
+        match self {
+            Self::Float { min, max } => {
+                let v = value.as_f64().ok_or("expected a number")?;
+                if !v.is_finite() {
+                    return Err("must be finite".into());
+                }
+                check_range(v, *min, *max)
+            }
+            Self::Integer { min, max } => {
+                let v = value.as_i64().ok_or("expected an integer")?;
+                check_range(v, *min, *max)
+            }
+            Self::Bool => value
+                .as_bool()
+                .map(|_| ())
+                .ok_or_else(|| "expected a boolean".into()),
+            Self::String { max_length } => {
+                let v = value.as_str().ok_or("expected a string")?;
+                match max_length {
+                    Some(max) if v.chars().count() > *max => {
+                        Err(format!("must be at most {max} characters"))
+                    }
+                    _ => Ok(()),
+                }
+            }
+            Self::Enum { options } => {
+                let v = value.as_str().ok_or("expected a string")?;
+                if options.iter().any(|o| o == v) {
+                    Ok(())
+                } else {
+                    Err(format!("must be one of: {}", options.join(", ")))
+                }
+            }
+            Self::Color => {
+                let valid = value.as_array().is_some_and(|rgb| {
+                    rgb.len() == 3 && rgb.iter().all(|c| c.as_u64().is_some_and(|c| c <= 255))
+                });
+                if valid {
+                    Ok(())
+                } else {
+                    Err("expected [r, g, b] with components 0-255".into())
+                }
+            }
+        }
+    }
+
+    /// JSON Schema fragment for this kind.
+    fn json_schema(&self) -> Map<String, Value> {
+        let schema = match self {
+            Self::Float { min, max } => json!({ "type": "number", "minimum": min, "maximum": max }),
+            Self::Integer { min, max } => {
+                json!({ "type": "integer", "minimum": min, "maximum": max })
+            }
+            Self::Bool => json!({ "type": "boolean" }),
+            Self::String { max_length } => json!({ "type": "string", "maxLength": max_length }),
+            Self::Enum { options } => json!({ "type": "string", "enum": options }),
+            Self::Color => json!({
+                "type": "array",
+                "format": "color",
+                "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+                "minItems": 3,
+                "maxItems": 3
+            }),
+        };
+
+        let mut map = schema.as_object().cloned().unwrap_or_default();
+        map.retain(|_, v| !v.is_null());
+        map
+    }
+}
+
+fn check_range<T: PartialOrd + std::fmt::Display>(
+    v: T,
+    min: Option<T>,
+    max: Option<T>,
+) -> std::result::Result<(), String> {
+    match (min, max) {
+        (Some(min), _) if v < min => Err(format!("must be at least {min}")),
+        (_, Some(max)) if v > max => Err(format!("must be at most {max}")),
+        _ => Ok(()),
+    }
+}
+
+/// Description of a single effect parameter.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamSpec {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: ParamKind,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+}
+
+impl ParamSpec {
+    /// Create a parameter spec without a default.
+    pub fn new(name: impl Into<String>, kind: ParamKind, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            description: description.into(),
+            default: None,
+        }
+    }
+
+    /// Set the default value.
+    pub fn with_default(mut self, default: impl Into<Value>) -> Self {
+        self.default = Some(default.into());
+        self
+    }
+}
+
+/// Parameters accepted by an effect.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ParamSchema {
+    pub params: Vec<ParamSpec>,
+}
+
+impl ParamSchema {
+    /// Create an empty schema.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a parameter.
+    pub fn param(mut self, spec: ParamSpec) -> Self {
+        self.params.push(spec);
+        self
+    }
+
+    /// Look up a parameter by name.
+    pub fn get(&self, name: &str) -> Option<&ParamSpec> {
+        self.params.iter().find(|p| p.name == name)
+    }
+
+    /// Validate parameters against this schema.
+    ///
+    /// `intensity` and `speed` are always range-checked. An empty schema
+    /// accepts any extra parameters; otherwise unknown extras are rejected.
+    pub fn validate(&self, params: &EffectParams) -> Result<()> {
+        if !(0.0..=1.0).contains(&params.intensity) {
+            return Err(Error::invalid_param(
+                "intensity",
+                "must be between 0.0 and 1.0",
+            ));
+        }
+        if !(0.0..=10.0).contains(&params.speed) {
+            return Err(Error::invalid_param(
+                "speed",
+                "must be between 0.0 and 10.0",
+            ));
+        }
+
+        let Value::Object(values) = serde_json::to_value(params)? else {
+            return Err(Error::Internal(
+                "params did not serialize to an object".to_string(),
+            ));
+        };
+
+        for (key, value) in &values {
+            match self.get(key) {
+                Some(_) if value.is_null() => {}
+                Some(spec) => spec
+                    .kind
+                    .check(value)
+                    .map_err(|message| Error::invalid_param(key.as_str(), message))?,
+                None if COMMON_PARAMS.contains(&key.as_str()) || self.params.is_empty() => {}
+                None => return Err(Error::invalid_param(key.as_str(), "unknown parameter")),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Export as a JSON Schema (draft 2020-12) object.
+    ///
+    /// Common parameters the effect doesn't describe are included, since
+    /// `validate` accepts them.
+    pub fn to_json_schema(&self) -> Value {
+        let common = common_specs()
+            .into_iter()
+            .filter(|spec| self.get(&spec.name).is_none());
+        let properties: Map<String, Value> = self
+            .params
+            .iter()
+            .cloned()
+            .chain(common)
+            .map(|spec| {
+                let mut prop = spec.kind.json_schema();
+                prop.insert("description".into(), spec.description.into());
+                if let Some(default) = spec.default {
+                    prop.insert("default".into(), default);
+                }
+                (spec.name, Value::Object(prop))
+            })
+            .collect();
+
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": properties,
+            "additionalProperties": self.params.is_empty(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> ParamSchema {
+        ParamSchema::new()
+            .param(
+                ParamSpec::new("intensity", ParamKind::float(0.0, 1.0), "Heat").with_default(0.8),
+            )
+            .param(ParamSpec::new("bands", ParamKind::integer(1, 64), "Bands"))
+            .param(ParamSpec::new(
+                "mode",
+                ParamKind::one_of(["bars", "vu"]),
+                "Mode",
+            ))
+            .param(ParamSpec::new("color", ParamKind::Color, "Color"))
+            .param(ParamSpec::new("label", ParamKind::string(4), "Label"))
+    }
+
+    fn params(extra: Value) -> EffectParams {
+        serde_json::from_value(extra).unwrap()
+    }
+
+    #[test]
+    fn test_validate_ok() {
+        let p = params(json!({ "intensity": 0.5, "bands": 8, "mode": "vu", "color": [1, 2, 3] }));
+        assert!(schema().validate(&p).is_ok());
+        assert!(schema()
+            .validate(&params(json!({ "label": "été!" })))
+            .is_ok());
+        assert!(schema().validate(&EffectParams::default()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects() {
+        let cases = [
+            (json!({ "intensity": 5.0 }), "intensity"),
+            (json!({ "speed": -1.0 }), "speed"),
+            (json!({ "bands": 0 }), "bands"),
+            (json!({ "bands": "many" }), "bands"),
+            (json!({ "mode": "dots" }), "mode"),
+            (json!({ "colour": [1, 2, 3] }), "colour"),
+            // Five characters, not ten bytes
+            (json!({ "label": "ééééé" }), "label"),
+        ];
+
+        for (value, expected) in cases {
+            match schema().validate(&params(value)) {
+                Err(Error::InvalidParameter { field, .. }) => assert_eq!(field, expected),
+                other => panic!("expected invalid {expected}, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_schema_accepts_extras() {
+        let p = params(json!({ "anything": true }));
+        assert!(ParamSchema::new().validate(&p).is_ok());
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let js = schema().to_json_schema();
+
+        assert_eq!(js["type"], "object");
+        assert_eq!(js["properties"]["intensity"]["maximum"], 1.0);
+        assert_eq!(js["properties"]["intensity"]["default"], 0.8);
+        assert_eq!(js["properties"]["mode"]["enum"], json!(["bars", "vu"]));
+        assert_eq!(js["properties"]["color"]["format"], "color");
+        assert_eq!(js["additionalProperties"], false);
+        // Accepted by `validate` without being in the effect's schema
+        assert_eq!(js["properties"]["speed"]["maximum"], 10.0);
+        assert_eq!(js["properties"]["intensity"]["description"], "Heat");
+    }
+}
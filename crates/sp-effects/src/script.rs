@@ -0,0 +1,489 @@
+//! Scriptable effects using the Rhai scripting language.
+//!
+//! A script defines `fn tick(t, dt)` (seconds since start, seconds since the
+//! last frame) and optionally `fn init()`. State persists across calls on
+//! `this`, which is an object map. The sandboxed pixel API is:
+//!
+//! - `width()`, `height()`, `time()`, `params()`
+//! - `set(x, y, color)`, `set(x, y, r, g, b)`, `get(x, y)`
+//! - `fill(color)`, `fill(r, g, b)`, `clear()`
+//! - `rgb(r, g, b)`, `hsv(h, s, v)` (h in degrees, s/v in 0.0 - 1.0)
+//!
+//! Colors are integers in `0xRRGGBB` form.
+
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST, FLOAT, INT};
+use sp_core::{Color, Error, Point, Result};
+use sp_renderer::Framebuffer;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{
+    validate_effect_name, Effect, EffectConfig, EffectOrigin, EffectParams, EffectRegistration,
+};
+
+/// Default CPU-time budget for a single `init`/`tick` call.
+pub const DEFAULT_TICK_BUDGET: Duration = Duration::from_millis(10);
+
+/// Maximum script source size in bytes.
+pub const MAX_SOURCE_LEN: usize = 64 * 1024;
+
+/// State shared between the engine's native functions and the effect.
+struct ScriptContext {
+    canvas: Framebuffer,
+    time: f32,
+    params: Map,
+    deadline: Instant,
+}
+
+/// Effect backed by a compiled Rhai script.
+pub struct ScriptEffect {
+    name: String,
+    engine: Engine,
+    ast: AST,
+    ctx: Arc<Mutex<ScriptContext>>,
+    this: Dynamic,
+    tick_budget: Duration,
+    failed: bool,
+}
+
+impl ScriptEffect {
+    /// Wrap a compiled script as an effect.
+    pub fn new(name: impl Into<String>, ast: AST, tick_budget: Duration) -> Self {
+        let ctx = Arc::new(Mutex::new(ScriptContext {
+            canvas: Framebuffer::new(64, 32),
+            time: 0.0,
+            params: Map::new(),
+            deadline: Instant::now(),
+        }));
+
+        let mut engine = sandboxed_engine();
+        register_pixel_api(&mut engine, &ctx);
+
+        let deadline_ctx = Arc::clone(&ctx);
+        engine.on_progress(move |ops| {
+            // Checking the clock on every operation is needlessly expensive
+            if ops % 256 != 0 {
+                return None;
+            }
+            let deadline = deadline_ctx.lock().map(|c| c.deadline).ok()?;
+            (Instant::now() > deadline).then(|| Dynamic::from("CPU time limit exceeded"))
+        });
+
+        Self {
+            name: name.into(),
+            engine,
+            ast,
+            ctx,
+            this: Dynamic::from_map(Map::new()),
+            tick_budget,
+            failed: false,
+        }
+    }
+
+    /// Call a script function under the CPU-time budget.
+    fn call(&mut self, func: &str, args: impl rhai::FuncArgs) -> Result<()> {
+        if let Ok(mut ctx) = self.ctx.lock() {
+            ctx.deadline = Instant::now() + self.tick_budget;
+        }
+
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.this);
+        self.engine
+            .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &self.ast, func, args)
+            .map(|_| ())
+            .map_err(|e| Error::Internal(format!("script '{}' {func}: {e}", self.name)))
+    }
+
+    fn has_fn(&self, name: &str) -> bool {
+        self.ast.iter_functions().any(|f| f.name == name)
+    }
+}
+
+impl Effect for ScriptEffect {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn init(&mut self, config: &EffectConfig) {
+        if let Ok(mut ctx) = self.ctx.lock() {
+            ctx.canvas = Framebuffer::new(config.width, config.height);
+            ctx.time = 0.0;
+            ctx.params = params_to_map(&config.params);
+        }
+        self.this = Dynamic::from_map(Map::new());
+        self.failed = false;
+
+        if self.has_fn("init") {
+            if let Err(e) = self.call("init", ()) {
+                tracing::warn!(error = %e, "Script init failed");
+            }
+        }
+
+        tracing::debug!(script = %self.name, "Script effect initialized");
+    }
+
+    fn tick(&mut self, fb: &mut Framebuffer, dt: Duration) -> bool {
+        if self.failed {
+            fb.clear();
+            return false;
+        }
+
+        let dt = dt.as_secs_f32();
+        let t = match self.ctx.lock() {
+            Ok(mut ctx) => {
+                ctx.time += dt;
+                ctx.time
+            }
+            Err(_) => return false,
+        };
+
+        if let Err(e) = self.call("tick", (t as FLOAT, dt as FLOAT)) {
+            tracing::warn!(error = %e, "Script tick failed, stopping effect");
+            self.failed = true;
+            fb.clear();
+            return false;
+        }
+
+        if let Ok(ctx) = self.ctx.lock() {
+            fb.data_mut().copy_from_slice(ctx.canvas.data());
+        }
+        true
+    }
+
+    fn supports_hot_update(&self) -> bool {
+        true
+    }
+
+    fn update_params(&mut self, params: &EffectParams) {
+        if let Ok(mut ctx) = self.ctx.lock() {
+            ctx.params = params_to_map(params);
+        }
+    }
+}
+
+/// Uploaded script metadata.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ScriptInfo {
+    pub name: String,
+    pub size: usize,
+}
+
+/// A checked and compiled script, ready to be stored.
+pub struct CompiledScript {
+    name: String,
+    source: String,
+    ast: AST,
+}
+
+impl CompiledScript {
+    /// Check the name and size, then compile the source. This is the slow
+    /// part of an upload, so it can run away from the render loop.
+    pub fn compile(name: &str, source: &str) -> Result<Self> {
+        validate_effect_name(name)?;
+        if source.len() > MAX_SOURCE_LEN {
+            return Err(Error::invalid_param(
+                "source",
+                format!("script exceeds {MAX_SOURCE_LEN} bytes"),
+            ));
+        }
+
+        let ast = sandboxed_engine()
+            .compile(source)
+            .map_err(|e| Error::invalid_param("source", e.to_string()))?;
+
+        if !ast
+            .iter_functions()
+            .any(|f| f.name == "tick" && f.params.len() == 2)
+        {
+            return Err(Error::invalid_param(
+                "source",
+                "script must define fn tick(t, dt)",
+            ));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            ast,
+        })
+    }
+
+    /// Name the script is stored under.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Collection of uploaded, compiled scripts.
+pub struct ScriptLibrary {
+    scripts: BTreeMap<String, CompiledScript>,
+    tick_budget: Duration,
+}
+
+impl ScriptLibrary {
+    /// Create an empty library.
+    pub fn new(tick_budget: Duration) -> Self {
+        Self {
+            scripts: BTreeMap::new(),
+            tick_budget,
+        }
+    }
+
+    /// Set the CPU-time budget applied to newly created script effects.
+    pub fn set_tick_budget(&mut self, budget: Duration) {
+        self.tick_budget = budget;
+    }
+
+    /// Compile and store a script, replacing any existing one with the same name.
+    pub fn insert(&mut self, name: &str, source: &str) -> Result<()> {
+        self.insert_compiled(CompiledScript::compile(name, source)?);
+        Ok(())
+    }
+
+    /// Store a compiled script, replacing any existing one with the same name.
+    pub fn insert_compiled(&mut self, script: CompiledScript) {
+        tracing::info!(script = %script.name, "Script stored");
+        self.scripts.insert(script.name.clone(), script);
+    }
+
+    /// Remove a script. Returns `false` if it did not exist.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.scripts.remove(name).is_some()
+    }
+
+    /// Get the source of a script.
+    pub fn source(&self, name: &str) -> Option<&str> {
+        self.scripts.get(name).map(|s| s.source.as_str())
+    }
+
+    /// Check whether a script exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.scripts.contains_key(name)
+    }
+
+    /// List stored scripts.
+    pub fn list(&self) -> Vec<ScriptInfo> {
+        self.scripts
+            .iter()
+            .map(|(name, s)| ScriptInfo {
+                name: name.clone(),
+                size: s.source.len(),
+            })
+            .collect()
+    }
+
+    /// Build a registry entry for a stored script.
+    pub fn registration(&self, name: &str) -> Option<EffectRegistration> {
+        let ast = self.scripts.get(name)?.ast.clone();
+        let budget = self.tick_budget;
+        let script_name = name.to_string();
+
+        let registration = EffectRegistration::new(name, "Uploaded Rhai script", move || {
+            Box::new(ScriptEffect::new(script_name.clone(), ast.clone(), budget))
+        });
+        Some(registration.with_origin(EffectOrigin::Script))
+    }
+}
+
+impl Default for ScriptLibrary {
+    fn default() -> Self {
+        Self::new(DEFAULT_TICK_BUDGET)
+    }
+}
+
+/// Engine with resource limits and no access to the host beyond the pixel API.
+fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_expr_depths(64, 32)
+        .set_max_call_levels(32)
+        .set_max_string_size(4096)
+        .set_max_array_size(16_384)
+        .set_max_map_size(1024)
+        .set_max_modules(0)
+        .disable_symbol("eval");
+    engine.on_print(|_| {});
+    engine.on_debug(|_, _, _| {});
+    engine
+}
+
+fn register_pixel_api(engine: &mut Engine, ctx: &Arc<Mutex<ScriptContext>>) {
+    fn with<T: Default>(ctx: &Mutex<ScriptContext>, f: impl FnOnce(&mut ScriptContext) -> T) -> T {
+        ctx.lock().map(|mut c| f(&mut c)).unwrap_or_default()
+    }
+
+    let c = Arc::clone(ctx);
+    engine.register_fn("width", move || with(&c, |c| c.canvas.width() as INT));
+    let c = Arc::clone(ctx);
+    engine.register_fn("height", move || with(&c, |c| c.canvas.height() as INT));
+    let c = Arc::clone(ctx);
+    engine.register_fn("time", move || with(&c, |c| c.time as FLOAT));
+    let c = Arc::clone(ctx);
+    engine.register_fn("params", move || with(&c, |c| c.params.clone()));
+
+    let c = Arc::clone(ctx);
+    engine.register_fn("set", move |x: INT, y: INT, color: INT| {
+        with(&c, |c| c.canvas.set(point(x, y), int_to_color(color)))
+    });
+    let c = Arc::clone(ctx);
+    engine.register_fn("set", move |x: FLOAT, y: FLOAT, color: INT| {
+        with(&c, |c| {
+            c.canvas.set(
+                point(x.floor() as INT, y.floor() as INT),
+                int_to_color(color),
+            )
+        })
+    });
+    let c = Arc::clone(ctx);
+    engine.register_fn("set", move |x: INT, y: INT, r: INT, g: INT, b: INT| {
+        with(&c, |c| {
+            c.canvas.set(point(x, y), int_to_color(rgb(r, g, b)))
+        })
+    });
+    let c = Arc::clone(ctx);
+    engine.register_fn("get", move |x: INT, y: INT| {
+        with(&c, |c| {
+            c.canvas.get(point(x, y)).map_or(0, |p| p.to_hex() as INT)
+        })
+    });
+    let c = Arc::clone(ctx);
+    engine.register_fn("fill", move |color: INT| {
+        with(&c, |c| c.canvas.fill(int_to_color(color)))
+    });
+    let c = Arc::clone(ctx);
+    engine.register_fn("fill", move |r: INT, g: INT, b: INT| {
+        with(&c, |c| c.canvas.fill(int_to_color(rgb(r, g, b))))
+    });
+    let c = Arc::clone(ctx);
+    engine.register_fn("clear", move || with(&c, |c| c.canvas.clear()));
+
+    engine.register_fn("rgb", rgb);
+    engine.register_fn("hsv", hsv);
+    engine.register_fn("hsv", |h: INT, s: FLOAT, v: FLOAT| hsv(h as FLOAT, s, v));
+}
+
+fn point(x: INT, y: INT) -> Point {
+    Point::new(
+        x.clamp(i32::MIN as INT, i32::MAX as INT) as i32,
+        y.clamp(i32::MIN as INT, i32::MAX as INT) as i32,
+    )
+}
+
+fn int_to_color(value: INT) -> Color {
+    Color::from_hex((value & 0xFF_FFFF) as u32)
+}
+
+fn rgb(r: INT, g: INT, b: INT) -> INT {
+    (r.clamp(0, 255) << 16) | (g.clamp(0, 255) << 8) | b.clamp(0, 255)
+}
+
+fn hsv(h: FLOAT, s: FLOAT, v: FLOAT) -> INT {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let s = s.clamp(0.0, 1.0);
+    let v = v.clamp(0.0, 1.0);
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    let to_int = |channel: FLOAT| ((channel + m) * 255.0).round() as INT;
+    rgb(to_int(r), to_int(g), to_int(b))
+}
+
+fn params_to_map(params: &EffectParams) -> Map {
+    rhai::serde::to_dynamic(params)
+        .ok()
+        .and_then(|d| d.try_cast::<Map>())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> EffectConfig {
+        EffectConfig {
+            width: 8,
+            height: 4,
+            params: EffectParams {
+                color: Some([0, 0, 255]),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn effect(source: &str) -> Box<dyn Effect> {
+        let mut library = ScriptLibrary::new(Duration::from_millis(20));
+        library.insert("test", source).unwrap();
+        let mut effect = library.registration("test").unwrap().create().unwrap();
+        effect.init(&config());
+        effect
+    }
+
+    #[test]
+    fn test_pixel_api() {
+        let mut effect = effect(
+            r#"
+            fn init() { this.count = 0; }
+            fn tick(t, dt) {
+                this.count += 1;
+                fill(rgb(10, 20, 30));
+                set(width() - 1, 0, hsv(0, 1.0, 1.0));
+                set(0, this.count, 0x00FF00);
+            }
+            "#,
+        );
+
+        let mut fb = Framebuffer::new(8, 4);
+        assert!(effect.tick(&mut fb, Duration::from_millis(16)));
+        assert!(effect.tick(&mut fb, Duration::from_millis(16)));
+
+        assert_eq!(effect.name(), "test");
+        assert_eq!(fb.get(Point::new(3, 3)), Some(Color::new(10, 20, 30)));
+        assert_eq!(fb.get(Point::new(7, 0)), Some(Color::RED));
+        assert_eq!(fb.get(Point::new(0, 2)), Some(Color::GREEN));
+    }
+
+    #[test]
+    fn test_params_available() {
+        let mut effect =
+            effect("fn tick(t, dt) { let c = params().color; fill(c[0], c[1], c[2]); }");
+
+        let mut fb = Framebuffer::new(8, 4);
+        effect.tick(&mut fb, Duration::from_millis(16));
+        assert_eq!(fb.get(Point::new(0, 0)), Some(Color::BLUE));
+    }
+
+    #[test]
+    fn test_cpu_limit() {
+        let mut effect = effect("fn tick(t, dt) { loop { } }");
+
+        let mut fb = Framebuffer::new(8, 4);
+        let start = Instant::now();
+        let continuing = effect.tick(&mut fb, Duration::from_millis(16));
+
+        assert!(!continuing);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(!effect.tick(&mut fb, Duration::from_millis(16)));
+    }
+
+    #[test]
+    fn test_insert_validation() {
+        let mut library = ScriptLibrary::default();
+
+        assert!(library.insert("ok", "fn tick(t, dt) {}").is_ok());
+        assert!(library.insert("no-tick", "fn init() {}").is_err());
+        assert!(library.insert("broken", "fn tick(t, dt) {").is_err());
+        assert!(library.insert("Bad Name", "fn tick(t, dt) {}").is_err());
+        assert!(library.insert("stream", "fn tick(t, dt) {}").is_err());
+        assert_eq!(library.list().len(), 1);
+    }
+}
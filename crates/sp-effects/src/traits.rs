@@ -1,6 +1,7 @@
 //! Effect trait definition.
 
 use serde::{Deserialize, Serialize};
+use sp_core::Result;
 use sp_renderer::Framebuffer;
 use std::time::Duration;
 
@@ -13,13 +14,13 @@ pub struct EffectConfig {
 }
 
 /// Dynamic parameters for effects.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EffectParams {
     /// Effect intensity (0.0 - 1.0)
     #[serde(default = "default_intensity")]
     pub intensity: f32,
 
-    /// Speed multiplier
+    /// Speed multiplier (0.0 - 10.0)
     #[serde(default = "default_speed")]
     pub speed: f32,
 
@@ -31,6 +32,38 @@ pub struct EffectParams {
     pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
+impl EffectParams {
+    /// Return a copy with the keys of a partial JSON object applied on top.
+    ///
+    /// A `null` value resets that key to its default.
+    pub fn merged(&self, patch: &serde_json::Map<String, serde_json::Value>) -> Result<Self> {
+        let mut merged = match serde_json::to_value(self)? {
+            serde_json::Value::Object(map) => map,
+            _ => serde_json::Map::new(),
+        };
+        for (key, value) in patch {
+            if value.is_null() {
+                merged.remove(key);
+            } else {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+
+        Ok(serde_json::from_value(serde_json::Value::Object(merged))?)
+    }
+}
+
+impl Default for EffectParams {
+    fn default() -> Self {
+        Self {
+            intensity: default_intensity(),
+            speed: default_speed(),
+            color: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
 fn default_intensity() -> f32 {
     0.8
 }
@@ -45,7 +78,7 @@ fn default_speed() -> f32 {
 /// They maintain internal state and update each tick.
 pub trait Effect: Send + Sync {
     /// Get the unique name of this effect.
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
 
     /// Initialize the effect with configuration.
     fn init(&mut self, config: &EffectConfig);
@@ -65,4 +98,12 @@ pub trait Effect: Send + Sync {
 
     /// Update parameters without restart (if supported).
     fn update_params(&mut self, _params: &EffectParams) {}
+
+    /// Preferred frame rate, or `None` to run at the render loop's target.
+    ///
+    /// Static effects can return a low rate to save CPU; the render loop
+    /// never exceeds its configured target.
+    fn preferred_fps(&self) -> Option<u32> {
+        None
+    }
 }
@@ -62,6 +62,10 @@ impl Driver for LedDriver {
         Ok(())
     }
 
+    fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
     fn is_healthy(&self) -> bool {
         self.initialized
     }
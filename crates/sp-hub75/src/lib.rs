@@ -16,7 +16,6 @@ use sp_renderer::Framebuffer;
 ///
 /// Implementors handle the low-level communication with HUB75 panels.
 /// Use `MockDriver` for development without hardware.
-#[cfg_attr(test, mockall::automock)]
 pub trait Driver: Send + Sync {
     /// Initialize the driver.
     fn init(&mut self) -> Result<()>;
@@ -27,6 +26,9 @@ pub trait Driver: Send + Sync {
     /// Set panel brightness (0-100).
     fn set_brightness(&mut self, brightness: u8) -> Result<()>;
 
+    /// Get the current panel brightness (0-100).
+    fn brightness(&self) -> u8;
+
     /// Check if driver is healthy.
     fn is_healthy(&self) -> bool;
 
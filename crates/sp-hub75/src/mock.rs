@@ -70,6 +70,10 @@ impl Driver for MockDriver {
         Ok(())
     }
 
+    fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
     fn is_healthy(&self) -> bool {
         self.initialized
     }
@@ -93,6 +97,9 @@ mod tests {
         driver.init().unwrap();
         assert!(driver.is_healthy());
 
+        driver.set_brightness(150).unwrap();
+        assert_eq!(driver.brightness(), 100);
+
         let fb = Framebuffer::default();
         driver.display(&fb).unwrap();
         assert_eq!(driver.frame_count(), 1);
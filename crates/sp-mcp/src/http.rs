@@ -0,0 +1,115 @@
+//! Streamable HTTP transport at `POST /mcp`.
+//!
+//! Every request is answered with a single JSON body; the server never opens
+//! an SSE stream, so `GET /mcp` is refused with `405`. Sessions are not used:
+//! each message is handled on its own.
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use sp_api::AppState;
+use std::sync::Arc;
+
+use crate::{protocol::SUPPORTED_VERSIONS, server::McpServer};
+
+/// `/mcp` route, to mount with [`sp_api::create_router_with`] so it shares
+/// the API's auth and rate limits.
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/mcp", post(handle_post))
+}
+
+/// Reject browser origins not allowed by `server.cors_origins`, against DNS
+/// rebinding.
+fn origin_allowed(state: &AppState, headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN).and_then(|v| v.to_str().ok()) else {
+        return true;
+    };
+    state
+        .config
+        .server
+        .cors_origins
+        .iter()
+        .any(|allowed| allowed == "*" || allowed == origin)
+}
+
+async fn handle_post(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !origin_allowed(&state, &headers) {
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+    let version = headers
+        .get("mcp-protocol-version")
+        .and_then(|v| v.to_str().ok());
+    if version.is_some_and(|version| !SUPPORTED_VERSIONS.contains(&version)) {
+        return (StatusCode::BAD_REQUEST, "Unsupported MCP-Protocol-Version").into_response();
+    }
+
+    let text = String::from_utf8_lossy(&body);
+    match McpServer::new(state).handle_text(&text).await {
+        Some(response) => Json(response).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use sp_api::{create_router_with, Renderer};
+    use sp_core::Config;
+    use sp_effects::EffectManager;
+    use sp_hub75::MockDriver;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_post_mcp() {
+        let mut config = Config::default();
+        config.server.cors_origins = vec!["http://panel.local".to_string()];
+        let manager = EffectManager::new(config.panel.width, config.panel.height);
+        let (_renderer, handle) = Renderer::new(manager, Box::new(MockDriver::new()));
+        let app = create_router_with(AppState::new(config, handle), router());
+
+        let post = |body: &'static str, origin: Option<&str>| {
+            let mut request = Request::post("/mcp")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::ACCEPT, "application/json, text/event-stream");
+            if let Some(origin) = origin {
+                request = request.header(header::ORIGIN, origin);
+            }
+            app.clone().oneshot(request.body(Body::from(body)).unwrap())
+        };
+
+        let response = post(
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "tools/list"}"#,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["result"]["tools"].as_array().unwrap().len(), 6);
+
+        let notification = r#"{"jsonrpc": "2.0", "method": "notifications/initialized"}"#;
+        let response = post(notification, Some("http://panel.local"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let response = post(notification, Some("http://evil.test")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let request = Request::get("/mcp").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
@@ -0,0 +1,15 @@
+//! Model Context Protocol server for Super Pixeled.
+//!
+//! Exposes panel control as MCP tools over two transports: newline-delimited
+//! JSON-RPC on stdin/stdout, and streamable HTTP at `POST /mcp`.
+
+mod http;
+mod protocol;
+mod server;
+mod stdio;
+mod tools;
+
+pub use http::router;
+pub use protocol::PROTOCOL_VERSION;
+pub use server::McpServer;
+pub use stdio::serve_stdio;
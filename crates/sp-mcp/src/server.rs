@@ -0,0 +1,236 @@
+//! Transport-independent MCP message handling.
+
+use serde_json::{json, Value};
+use sp_api::AppState;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::{
+    protocol::{
+        failure, success, Request, RpcError, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND,
+        PARSE_ERROR, PROTOCOL_VERSION, SUPPORTED_VERSIONS,
+    },
+    tools::{self, ToolError},
+};
+
+/// Answers MCP requests against the shared application state.
+#[derive(Clone)]
+pub struct McpServer {
+    state: Arc<AppState>,
+}
+
+impl McpServer {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Handle one line of JSON text; `None` when there is nothing to send back.
+    pub async fn handle_text(&self, text: &str) -> Option<Value> {
+        match serde_json::from_str(text) {
+            Ok(message) => self.handle(message).await,
+            Err(e) => Some(failure(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, e.to_string()),
+            )),
+        }
+    }
+
+    /// Handle a message or batch; `None` when it held only notifications.
+    pub async fn handle(&self, message: Value) -> Option<Value> {
+        let Value::Array(batch) = message else {
+            return self.handle_one(message).await;
+        };
+        if batch.is_empty() {
+            let error = RpcError::new(INVALID_REQUEST, "Empty batch");
+            return Some(failure(Value::Null, error));
+        }
+
+        let mut responses = Vec::new();
+        for message in batch {
+            responses.extend(self.handle_one(message).await);
+        }
+        (!responses.is_empty()).then_some(Value::Array(responses))
+    }
+
+    async fn handle_one(&self, message: Value) -> Option<Value> {
+        // Responses to server requests are never expected; drop them
+        if message.get("method").is_none() && message.get("id").is_some() {
+            return None;
+        }
+
+        let request = match serde_json::from_value::<Request>(message) {
+            Ok(request) if request.jsonrpc == "2.0" => request,
+            Ok(_) => {
+                let error = RpcError::new(INVALID_REQUEST, "Expected jsonrpc 2.0");
+                return Some(failure(Value::Null, error));
+            }
+            Err(e) => {
+                let error = RpcError::new(INVALID_REQUEST, e.to_string());
+                return Some(failure(Value::Null, error));
+            }
+        };
+
+        let Some(id) = request.id else {
+            debug!(method = %request.method, "MCP notification");
+            return None;
+        };
+        debug!(method = %request.method, "MCP request");
+        Some(match self.dispatch(&request.method, request.params).await {
+            Ok(result) => success(id, result),
+            Err(error) => failure(id, error),
+        })
+    }
+
+    async fn dispatch(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => {
+                let requested = params["protocolVersion"].as_str().unwrap_or_default();
+                let version = SUPPORTED_VERSIONS
+                    .into_iter()
+                    .find(|version| *version == requested)
+                    .unwrap_or(PROTOCOL_VERSION);
+
+                Ok(json!({
+                    "protocolVersion": version,
+                    "capabilities": { "tools": { "listChanged": false } },
+                    "serverInfo": {
+                        "name": "super-pixeled",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                    "instructions": "Controls an RGB LED panel: list effects, start one, \
+                                     show text or change brightness.",
+                }))
+            }
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tools::list() })),
+            "tools/call" => {
+                let name = params["name"]
+                    .as_str()
+                    .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing tool name"))?;
+                let arguments = match &params["arguments"] {
+                    Value::Null => json!({}),
+                    arguments => arguments.clone(),
+                };
+
+                let (output, is_error) = match tools::call(&self.state, name, arguments).await {
+                    Ok(output) => (output.to_string(), false),
+                    Err(ToolError::Failed(message)) => (message, true),
+                    Err(ToolError::Unknown(name)) => {
+                        let message = format!("Unknown tool: {name}");
+                        return Err(RpcError::new(INVALID_PARAMS, message));
+                    }
+                };
+                Ok(json!({
+                    "content": [{ "type": "text", "text": output }],
+                    "isError": is_error,
+                }))
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {method}"),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_api::test_util;
+    use sp_api::Renderer;
+    use sp_core::Config;
+    use sp_effects::EffectManager;
+    use sp_hub75::MockDriver;
+
+    fn call(id: u64, name: &str, arguments: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        })
+    }
+
+    /// Decoded JSON output of a successful tool call.
+    fn output(response: &Value) -> Value {
+        assert_eq!(response["result"]["isError"], false, "{response}");
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_session() {
+        let config = Config::default();
+        let (state, _render) = test_util::start(config);
+        let server = McpServer::new(state);
+
+        let init = server
+            .handle_text(
+                r#"{"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                    "params": {"protocolVersion": "2025-03-26", "capabilities": {}}}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+        assert!(init["result"]["capabilities"]["tools"].is_object());
+        let initialized = r#"{"jsonrpc": "2.0", "method": "notifications/initialized"}"#;
+        assert!(server.handle_text(initialized).await.is_none());
+
+        let list = server
+            .handle(json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}))
+            .await
+            .unwrap();
+        let tools = list["result"]["tools"].as_array().unwrap();
+        let set_effect = tools.iter().find(|t| t["name"] == "set_effect").unwrap();
+        assert_eq!(set_effect["inputSchema"]["type"], "object");
+        assert!(set_effect["inputSchema"]["properties"]["name"].is_object());
+
+        let response = server
+            .handle(call(3, "set_effect", json!({"name": "solid"})))
+            .await;
+        assert_eq!(output(&response.unwrap())["effect"], "solid");
+
+        let response = server.handle(call(4, "get_snapshot", Value::Null)).await;
+        assert_eq!(output(&response.unwrap())["effect"], "solid");
+
+        // Validation and render errors come back as tool errors
+        let response = server
+            .handle(call(5, "set_brightness", json!({"brightness": 150})))
+            .await
+            .unwrap();
+        assert_eq!(response["result"]["isError"], true);
+        let response = server
+            .handle(call(6, "set_effect", json!({"name": "nope"})))
+            .await
+            .unwrap();
+        assert_eq!(response["result"]["isError"], true);
+    }
+
+    #[tokio::test]
+    async fn test_protocol_errors() {
+        let config = Config::default();
+        let manager = EffectManager::new(config.panel.width, config.panel.height);
+        let (_renderer, handle) = Renderer::new(manager, Box::new(MockDriver::new()));
+        let server = McpServer::new(AppState::new(config, handle));
+
+        let response = server.handle_text("{not json").await.unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let response = server
+            .handle(json!({"jsonrpc": "2.0", "id": 1, "method": "resources/list"}))
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = server.handle(call(2, "reboot", json!({}))).await.unwrap();
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        let batch = json!([
+            {"jsonrpc": "2.0", "id": 3, "method": "ping"},
+            {"jsonrpc": "2.0", "method": "notifications/initialized"},
+        ]);
+        let response = server.handle(batch).await.unwrap();
+        assert_eq!(response.as_array().unwrap().len(), 1);
+        assert_eq!(response[0]["id"], 3);
+    }
+}
@@ -0,0 +1,29 @@
+//! stdio transport: one JSON-RPC message per line on stdin and stdout.
+
+use sp_api::AppState;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::server::McpServer;
+
+/// Serve MCP on stdin/stdout until stdin closes.
+///
+/// Logs must go to stderr while this runs: stdout carries only messages.
+pub async fn serve_stdio(state: Arc<AppState>) -> std::io::Result<()> {
+    let server = McpServer::new(state);
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_text(&line).await {
+            let mut bytes = response.to_string().into_bytes();
+            bytes.push(b'\n');
+            stdout.write_all(&bytes).await?;
+            stdout.flush().await?;
+        }
+    }
+    Ok(())
+}
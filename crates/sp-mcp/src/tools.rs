@@ -0,0 +1,144 @@
+//! Panel control tools.
+//!
+//! Arguments are the REST request types, so tools validate the same way as
+//! the API and their input schemas come from its OpenAPI components.
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use sp_api::{
+    ApiDoc, AppState, BrightnessRequest, EffectRequest, PanelSnapshot, TextCommand, TextRequest,
+};
+use std::sync::OnceLock;
+use std::time::Duration;
+use utoipa::OpenApi;
+use validator::Validate;
+
+/// Tool names, descriptions and the schema component of their arguments.
+const TOOLS: [(&str, &str, Option<&str>); 6] = [
+    (
+        "list_effects",
+        "List registered effects with their parameter schemas.",
+        None,
+    ),
+    (
+        "set_effect",
+        "Start an effect by name, with optional parameters.",
+        Some("EffectRequest"),
+    ),
+    ("stop_effect", "Stop the current effect.", None),
+    (
+        "show_text",
+        "Show static or scrolling text on the panel.",
+        Some("TextRequest"),
+    ),
+    (
+        "set_brightness",
+        "Set panel brightness (0-100), optionally ramping over duration_ms.",
+        Some("BrightnessRequest"),
+    ),
+    (
+        "get_snapshot",
+        "Current effect, params, brightness, driver health and uptime.",
+        None,
+    ),
+];
+
+/// Why a tool call failed.
+pub(crate) enum ToolError {
+    /// No such tool: a protocol error.
+    Unknown(String),
+    /// The tool ran and failed: reported to the model as an error result.
+    Failed(String),
+}
+
+/// `tools/list` entries.
+pub(crate) fn list() -> Vec<Value> {
+    TOOLS
+        .iter()
+        .map(|(name, description, component)| {
+            json!({
+                "name": name,
+                "description": description,
+                "inputSchema": component.map_or_else(empty_schema, input_schema),
+            })
+        })
+        .collect()
+}
+
+fn empty_schema() -> Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+/// Schema of an API request type, from the OpenAPI document.
+fn input_schema(component: &str) -> Value {
+    static SCHEMAS: OnceLock<Value> = OnceLock::new();
+    let schemas = SCHEMAS.get_or_init(|| {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap_or_default();
+        spec["components"]["schemas"].clone()
+    });
+    schemas.get(component).cloned().unwrap_or_else(empty_schema)
+}
+
+/// Decode and validate arguments as an API request.
+fn arguments<T: DeserializeOwned + Validate>(arguments: Value) -> Result<T, ToolError> {
+    let request: T = serde_json::from_value(arguments)
+        .map_err(|e| ToolError::Failed(format!("Invalid arguments: {e}")))?;
+    request
+        .validate()
+        .map_err(|e| ToolError::Failed(format!("Invalid arguments: {e}")))?;
+    Ok(request)
+}
+
+/// Run a tool, returning its JSON output.
+pub(crate) async fn call(state: &AppState, name: &str, args: Value) -> Result<Value, ToolError> {
+    let renderer = &state.renderer;
+    let failed = |e: sp_core::Error| ToolError::Failed(e.to_string());
+
+    match name {
+        "list_effects" => {
+            let effects = renderer
+                .call(|manager| manager.registry().infos())
+                .await
+                .map_err(failed)?;
+            Ok(json!({ "effects": effects }))
+        }
+        "set_effect" => {
+            let req: EffectRequest = arguments(args)?;
+            renderer
+                .set_effect(&req.name, req.params)
+                .await
+                .map_err(failed)?;
+            Ok(json!({ "success": true, "effect": req.name }))
+        }
+        "stop_effect" => {
+            renderer.stop().await.map_err(failed)?;
+            Ok(json!({ "success": true }))
+        }
+        "show_text" => {
+            let req: TextRequest = arguments(args)?;
+            renderer
+                .show_text(TextCommand {
+                    text: req.text.clone(),
+                    color: req.color,
+                    scroll: req.scroll,
+                    speed: req.speed,
+                })
+                .await
+                .map_err(failed)?;
+            Ok(json!({ "success": true, "text": req.text }))
+        }
+        "set_brightness" => {
+            let req: BrightnessRequest = arguments(args)?;
+            let status = renderer
+                .set_brightness(req.brightness, Duration::from_millis(req.duration_ms))
+                .await
+                .map_err(failed)?;
+            Ok(json!(status))
+        }
+        "get_snapshot" => {
+            let snapshot = PanelSnapshot::capture(state).await.map_err(failed)?;
+            Ok(json!(snapshot))
+        }
+        _ => Err(ToolError::Unknown(name.to_string())),
+    }
+}
@@ -0,0 +1,372 @@
+//! MQTT client task bridging broker topics to the render thread.
+
+use rumqttc::{AsyncClient, Event, EventLoop, LastWill, MqttOptions, Outgoing, Packet, QoS};
+use serde::Serialize;
+use sp_api::AppState;
+use sp_core::{MqttConfig, Result};
+use sp_effects::EffectParams;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::{
+    discovery,
+    topics::{Command, Topics},
+};
+
+/// How often state is re-read to catch changes made over HTTP.
+const STATE_REFRESH: Duration = Duration::from_secs(2);
+
+/// Delay before polling again after a connection error.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Requests queued between the client and its event loop.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// State published on `<base>/state`, in Home Assistant's JSON light format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct LightState {
+    state: &'static str,
+    effect: Option<String>,
+    brightness: u8,
+}
+
+/// Connects to the broker, applies commands and publishes panel state.
+pub struct MqttBridge {
+    config: MqttConfig,
+    topics: Topics,
+    client: AsyncClient,
+    state: Arc<AppState>,
+    connected: bool,
+    /// Last published state, to publish only changes.
+    published: Option<LightState>,
+    /// Effect list announced in the discovery payload.
+    effects: Vec<String>,
+}
+
+impl MqttBridge {
+    /// Spawn the client task; it runs until `shutdown` flips.
+    pub fn spawn(state: Arc<AppState>, shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+        let config = state.config.mqtt.clone();
+        let topics = Topics::new(&config.base_topic);
+
+        let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+        options
+            .set_keep_alive(Duration::from_secs(30))
+            .set_last_will(LastWill::new(
+                topics.availability(),
+                "offline",
+                QoS::AtLeastOnce,
+                true,
+            ));
+        if let Some(username) = &config.username {
+            options.set_credentials(username, config.password.as_deref().unwrap_or_default());
+        }
+
+        let (client, eventloop) = AsyncClient::new(options, CHANNEL_CAPACITY);
+        let bridge = Self {
+            config,
+            topics,
+            client,
+            state,
+            connected: false,
+            published: None,
+            effects: Vec::new(),
+        };
+        tokio::spawn(bridge.run(eventloop, shutdown))
+    }
+
+    async fn run(mut self, mut eventloop: EventLoop, mut shutdown: watch::Receiver<bool>) {
+        info!(host = %self.config.host, port = self.config.port, "Starting MQTT client");
+        let mut refresh = tokio::time::interval(STATE_REFRESH);
+
+        loop {
+            tokio::select! {
+                event = eventloop.poll() => match event {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        info!("Connected to MQTT broker");
+                        self.on_connect().await;
+                    }
+                    Ok(Event::Incoming(Packet::Publish(publish))) => {
+                        self.on_message(&publish.topic, &publish.payload).await;
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!(error = %e, "MQTT connection error, retrying");
+                        self.connected = false;
+                        tokio::select! {
+                            _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+                            _ = shutdown.changed() => break,
+                        }
+                    }
+                },
+                _ = refresh.tick(), if self.connected => self.refresh().await,
+                _ = shutdown.changed() => break,
+            }
+        }
+
+        // Announce offline ourselves; the last will only fires on abrupt loss
+        self.publish(self.topics.availability(), "offline");
+        let _ = self.client.try_disconnect();
+        let flush = async {
+            while let Ok(event) = eventloop.poll().await {
+                if matches!(event, Event::Outgoing(Outgoing::Disconnect)) {
+                    break;
+                }
+            }
+        };
+        let _ = tokio::time::timeout(Duration::from_secs(1), flush).await;
+        info!("MQTT client stopped");
+    }
+
+    /// Subscribe and publish the retained topics after each (re)connection.
+    async fn on_connect(&mut self) {
+        self.connected = true;
+        for topic in self.topics.subscriptions() {
+            if let Err(e) = self.client.try_subscribe(topic, QoS::AtLeastOnce) {
+                warn!(error = %e, "Failed to subscribe");
+            }
+        }
+        self.publish(self.topics.availability(), "online");
+
+        // The broker may have restarted without its retained messages
+        self.published = None;
+        self.effects.clear();
+        self.refresh().await;
+    }
+
+    async fn on_message(&mut self, topic: &str, payload: &[u8]) {
+        debug!(topic, "MQTT command");
+        let result = match self.topics.parse(topic, payload) {
+            Ok(command) => self.apply(command).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!(topic, error = %e, "MQTT command failed");
+        }
+        self.refresh().await;
+    }
+
+    async fn apply(&self, command: Command) -> Result<()> {
+        let renderer = &self.state.renderer;
+
+        match command {
+            Command::Light {
+                on,
+                effect,
+                brightness,
+                transition,
+            } => {
+                if on == Some(false) {
+                    return renderer.stop().await;
+                }
+                if let Some(level) = brightness {
+                    renderer.set_brightness(level, transition).await?;
+                }
+                if let Some(name) = effect {
+                    renderer.set_effect(name, EffectParams::default()).await?;
+                } else if on == Some(true) {
+                    let running = renderer
+                        .call(|manager| manager.current_effect().is_some())
+                        .await?;
+                    if !running {
+                        let name = self.state.config.effects.default.clone();
+                        renderer.set_effect(name, EffectParams::default()).await?;
+                    }
+                }
+                Ok(())
+            }
+            Command::Effect { name, params } => renderer.set_effect(name, params).await,
+            Command::Params(patch) => {
+                if renderer.update_params(patch).await?.is_none() {
+                    warn!("Ignoring params: no active effect");
+                }
+                Ok(())
+            }
+            Command::Brightness(level) => renderer
+                .set_brightness(level, Duration::ZERO)
+                .await
+                .map(|_| ()),
+            Command::Text(text) => renderer.show_text(text).await,
+        }
+    }
+
+    /// Publish state and discovery if they changed since last time.
+    async fn refresh(&mut self) {
+        let snapshot = self
+            .state
+            .renderer
+            .call(|manager| {
+                (
+                    manager.current_effect().map(String::from),
+                    manager.available_effects(),
+                )
+            })
+            .await;
+        let Ok((effect, effects)) = snapshot else {
+            return;
+        };
+
+        if effects != self.effects {
+            if let Some(topic) = discovery::config_topic(&self.config) {
+                let payload = discovery::light_config(&self.config, &self.topics, &effects);
+                self.publish(topic, payload.to_string());
+            }
+            self.effects = effects;
+        }
+
+        let state = LightState {
+            state: if effect.is_some() { "ON" } else { "OFF" },
+            effect,
+            brightness: self.state.renderer.brightness().target,
+        };
+        if self.published.as_ref() != Some(&state) {
+            match serde_json::to_string(&state) {
+                Ok(payload) => self.publish(self.topics.state(), payload),
+                Err(e) => warn!(error = %e, "Failed to encode MQTT state"),
+            }
+            self.published = Some(state);
+        }
+    }
+
+    /// Queue a retained message without waiting on the event loop.
+    fn publish(&self, topic: String, payload: impl Into<Vec<u8>>) {
+        if let Err(e) = self
+            .client
+            .try_publish(topic, QoS::AtLeastOnce, true, payload)
+        {
+            warn!(error = %e, "Failed to queue MQTT message");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+    use rumqttc::{ConnAck, ConnectReturnCode, PubAck, Publish, SubAck, SubscribeReasonCode};
+    use sp_api::test_util;
+    use sp_core::Config;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Minimal broker stand-in: acknowledges everything and hands back the
+    /// packets the client sends.
+    struct Broker {
+        stream: TcpStream,
+        buffer: BytesMut,
+    }
+
+    impl Broker {
+        async fn accept(listener: &TcpListener) -> Self {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut broker = Self {
+                stream,
+                buffer: BytesMut::new(),
+            };
+            assert!(matches!(broker.read().await, Packet::Connect(_)));
+            broker
+                .send(|out| ConnAck::new(ConnectReturnCode::Success, false).write(out))
+                .await;
+            broker
+        }
+
+        async fn read(&mut self) -> Packet {
+            loop {
+                match rumqttc::read(&mut self.buffer, 1 << 20) {
+                    Ok(packet) => return packet,
+                    Err(rumqttc::Error::InsufficientBytes(_)) => {
+                        let n = self.stream.read_buf(&mut self.buffer).await.unwrap();
+                        assert!(n > 0, "client closed the connection");
+                    }
+                    Err(e) => panic!("bad packet: {e:?}"),
+                }
+            }
+        }
+
+        async fn send(
+            &mut self,
+            write: impl FnOnce(&mut BytesMut) -> std::result::Result<usize, rumqttc::Error>,
+        ) {
+            let mut out = BytesMut::new();
+            write(&mut out).unwrap();
+            // Acks may race the client's disconnect on shutdown
+            let _ = self.stream.write_all(&out).await;
+        }
+
+        /// Read until a publish on `topic`, acknowledging along the way.
+        async fn expect_publish(&mut self, topic: &str) -> Publish {
+            let wait = async {
+                loop {
+                    match self.read().await {
+                        Packet::Subscribe(sub) => {
+                            let codes = vec![
+                                SubscribeReasonCode::Success(QoS::AtLeastOnce);
+                                sub.filters.len()
+                            ];
+                            self.send(|out| SubAck::new(sub.pkid, codes).write(out))
+                                .await;
+                        }
+                        Packet::Publish(publish) => {
+                            if publish.pkid > 0 {
+                                self.send(|out| PubAck::new(publish.pkid).write(out)).await;
+                            }
+                            if publish.topic == topic {
+                                return publish;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            };
+            tokio::time::timeout(Duration::from_secs(5), wait)
+                .await
+                .unwrap_or_else(|_| panic!("no publish on {topic}"))
+        }
+    }
+
+    fn json(publish: &Publish) -> serde_json::Value {
+        serde_json::from_slice(&publish.payload).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_bridge_against_broker() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut config = Config::default();
+        config.mqtt.enabled = true;
+        config.mqtt.host = "127.0.0.1".to_string();
+        config.mqtt.port = listener.local_addr().unwrap().port();
+
+        let (state, render) = test_util::start(config);
+        let shutdown_rx = render.shutdown();
+
+        let bridge = MqttBridge::spawn(state, shutdown_rx);
+        let mut broker = Broker::accept(&listener).await;
+
+        let availability = broker.expect_publish("super-pixeled/availability").await;
+        assert_eq!(&availability.payload[..], b"online");
+
+        let discovery = broker
+            .expect_publish("homeassistant/light/super-pixeled/config")
+            .await;
+        assert!(discovery.retain);
+        let effects = json(&discovery)["effect_list"].clone();
+        assert!(effects.as_array().unwrap().contains(&"solid".into()));
+        let initial = broker.expect_publish("super-pixeled/state").await;
+        assert_eq!(json(&initial)["state"], "OFF");
+
+        let command = Publish::new("super-pixeled/effect/set", QoS::AtMostOnce, "solid");
+        broker.send(|out| command.write(out)).await;
+        let updated = broker.expect_publish("super-pixeled/state").await;
+        assert_eq!(json(&updated)["state"], "ON");
+        assert_eq!(json(&updated)["effect"], "solid");
+
+        drop(render);
+        let offline = broker.expect_publish("super-pixeled/availability").await;
+        assert_eq!(&offline.payload[..], b"offline");
+
+        bridge.await.unwrap();
+    }
+}
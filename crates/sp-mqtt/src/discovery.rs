@@ -0,0 +1,82 @@
+//! Home Assistant MQTT discovery.
+//!
+//! The panel is announced as a JSON-schema `light` whose effect list is the
+//! registry's effects and whose brightness uses the API's 0-100 scale.
+
+use serde_json::{json, Value};
+use sp_core::MqttConfig;
+
+use crate::topics::Topics;
+
+/// Discovery config topic, or `None` when discovery is disabled.
+pub fn config_topic(config: &MqttConfig) -> Option<String> {
+    let prefix = config.discovery_prefix.trim_end_matches('/');
+    (!prefix.is_empty()).then(|| format!("{prefix}/light/{}/config", node_id(config)))
+}
+
+/// Discovery payload for the panel light.
+pub fn light_config(config: &MqttConfig, topics: &Topics, effects: &[String]) -> Value {
+    let node_id = node_id(config);
+
+    json!({
+        "name": null,
+        "unique_id": format!("{node_id}_panel"),
+        "schema": "json",
+        "command_topic": topics.command(),
+        "state_topic": topics.state(),
+        "availability_topic": topics.availability(),
+        "brightness": true,
+        "brightness_scale": sp_api::MAX_BRIGHTNESS,
+        "effect": true,
+        "effect_list": effects,
+        "device": {
+            "identifiers": [node_id],
+            "name": "Super Pixeled",
+            "model": "HUB75 LED panel",
+            "manufacturer": "Super Pixeled",
+            "sw_version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+/// Discovery node id: the client id restricted to `[A-Za-z0-9_-]`.
+fn node_id(config: &MqttConfig) -> String {
+    config
+        .client_id
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_light_config() {
+        let mut config = MqttConfig {
+            client_id: "panel.1".to_string(),
+            ..Default::default()
+        };
+        let topics = Topics::new(&config.base_topic);
+        let effects = vec!["fire".to_string(), "solid".to_string()];
+
+        assert_eq!(
+            config_topic(&config).as_deref(),
+            Some("homeassistant/light/panel_1/config")
+        );
+
+        let payload = light_config(&config, &topics, &effects);
+        assert_eq!(payload["schema"], "json");
+        assert_eq!(payload["command_topic"], "super-pixeled/set");
+        assert_eq!(payload["brightness_scale"], 100);
+        assert_eq!(payload["effect_list"], json!(["fire", "solid"]));
+        assert_eq!(payload["unique_id"], "panel_1_panel");
+
+        config.discovery_prefix.clear();
+        assert_eq!(config_topic(&config), None);
+    }
+}
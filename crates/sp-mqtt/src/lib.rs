@@ -0,0 +1,11 @@
+//! MQTT integration for Super Pixeled.
+//!
+//! Subscribes to command topics, publishes retained state and availability,
+//! and announces the panel to Home Assistant through MQTT discovery.
+
+mod bridge;
+pub mod discovery;
+mod topics;
+
+pub use bridge::MqttBridge;
+pub use topics::{Command, Topics};
@@ -0,0 +1,246 @@
+//! Topic layout and command payloads.
+//!
+//! Everything lives under the configured base topic:
+//!
+//! | Topic | Direction | Payload |
+//! |-------|-----------|---------|
+//! | `<base>/availability` | out, retained | `online` / `offline` |
+//! | `<base>/state` | out, retained | `{"state": "ON", "effect": "fire", "brightness": 80}` |
+//! | `<base>/set` | in | Home Assistant JSON light command |
+//! | `<base>/effect/set` | in | effect name, or `{"name": ..., "params": {...}}` |
+//! | `<base>/params/set` | in | partial params object |
+//! | `<base>/brightness/set` | in | `0`-`100` |
+//! | `<base>/text/set` | in | text, or `{"text": ..., "color": [r, g, b], "scroll": true}` |
+
+use serde::Deserialize;
+use sp_api::TextCommand;
+use sp_core::{Error, Result};
+use sp_effects::EffectParams;
+use std::time::Duration;
+
+/// Longest brightness transition accepted from Home Assistant.
+const MAX_TRANSITION: Duration = Duration::from_secs(60);
+
+/// A command received on one of the `set` topics.
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Home Assistant light command; absent fields are left unchanged.
+    Light {
+        on: Option<bool>,
+        effect: Option<String>,
+        brightness: Option<u8>,
+        transition: Duration,
+    },
+    /// Switch to an effect.
+    Effect { name: String, params: EffectParams },
+    /// Merge partial params into the current effect's.
+    Params(serde_json::Map<String, serde_json::Value>),
+    /// Set brightness immediately.
+    Brightness(u8),
+    /// Show text.
+    Text(TextCommand),
+}
+
+#[derive(Deserialize)]
+struct LightPayload {
+    state: Option<String>,
+    effect: Option<String>,
+    brightness: Option<u8>,
+    /// Seconds.
+    transition: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct EffectPayload {
+    name: String,
+    #[serde(default)]
+    params: EffectParams,
+}
+
+#[derive(Deserialize)]
+struct TextPayload {
+    text: String,
+    #[serde(default = "default_text_color")]
+    color: [u8; 3],
+    #[serde(default)]
+    scroll: bool,
+    #[serde(default = "default_text_speed")]
+    speed: u32,
+}
+
+fn default_text_color() -> [u8; 3] {
+    [255, 255, 255]
+}
+
+fn default_text_speed() -> u32 {
+    50
+}
+
+/// Topic names derived from the base topic.
+#[derive(Debug, Clone)]
+pub struct Topics {
+    base: String,
+}
+
+impl Topics {
+    /// Create the layout for a base topic such as `super-pixeled`.
+    pub fn new(base: &str) -> Self {
+        Self {
+            base: base.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn availability(&self) -> String {
+        format!("{}/availability", self.base)
+    }
+
+    pub fn state(&self) -> String {
+        format!("{}/state", self.base)
+    }
+
+    /// Home Assistant JSON command topic.
+    pub fn command(&self) -> String {
+        format!("{}/set", self.base)
+    }
+
+    /// Every topic the client subscribes to.
+    pub fn subscriptions(&self) -> Vec<String> {
+        let mut topics = vec![self.command()];
+        topics.extend(
+            ["effect", "params", "brightness", "text"]
+                .iter()
+                .map(|name| format!("{}/{name}/set", self.base)),
+        );
+        topics
+    }
+
+    /// Decode a message received on one of the subscribed topics.
+    pub fn parse(&self, topic: &str, payload: &[u8]) -> Result<Command> {
+        let name = topic
+            .strip_prefix(self.base.as_str())
+            .and_then(|rest| rest.strip_prefix('/'))
+            .ok_or_else(|| Error::invalid_param("topic", format!("Unexpected topic {topic}")))?;
+        let text = std::str::from_utf8(payload)
+            .map_err(|_| Error::invalid_param(topic, "Payload is not UTF-8"))?
+            .trim();
+
+        match name {
+            "set" => {
+                let light: LightPayload = serde_json::from_str(text)?;
+                let on = match light.state.as_deref() {
+                    None => None,
+                    Some("ON") => Some(true),
+                    Some("OFF") => Some(false),
+                    Some(other) => {
+                        return Err(Error::invalid_param(
+                            "state",
+                            format!("Unknown state {other}"),
+                        ))
+                    }
+                };
+                let transition = light
+                    .transition
+                    .filter(|secs| secs.is_finite() && *secs > 0.0)
+                    .map_or(Duration::ZERO, |secs| {
+                        Duration::from_secs_f32(secs).min(MAX_TRANSITION)
+                    });
+
+                Ok(Command::Light {
+                    on,
+                    effect: light.effect,
+                    brightness: light.brightness,
+                    transition,
+                })
+            }
+            "effect/set" if text.starts_with('{') => {
+                let effect: EffectPayload = serde_json::from_str(text)?;
+                Ok(Command::Effect {
+                    name: effect.name,
+                    params: effect.params,
+                })
+            }
+            "effect/set" if !text.is_empty() => Ok(Command::Effect {
+                name: text.to_string(),
+                params: EffectParams::default(),
+            }),
+            "params/set" => Ok(Command::Params(serde_json::from_str(text)?)),
+            "brightness/set" => text
+                .parse()
+                .map(Command::Brightness)
+                .map_err(|_| Error::invalid_param("brightness", "Expected an integer 0-100")),
+            "text/set" if text.starts_with('{') => {
+                let payload: TextPayload = serde_json::from_str(text)?;
+                Ok(Command::Text(TextCommand {
+                    text: payload.text,
+                    color: payload.color,
+                    scroll: payload.scroll,
+                    speed: payload.speed,
+                }))
+            }
+            "text/set" => Ok(Command::Text(TextCommand {
+                text: text.to_string(),
+                color: default_text_color(),
+                scroll: false,
+                speed: default_text_speed(),
+            })),
+            _ => Err(Error::invalid_param(topic, "Empty or unsupported command")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        let topics = Topics::new("panel/");
+        assert_eq!(topics.state(), "panel/state");
+        assert_eq!(topics.subscriptions().len(), 5);
+
+        let command = topics
+            .parse(
+                "panel/set",
+                br#"{"state": "ON", "brightness": 40, "transition": 2}"#,
+            )
+            .unwrap();
+        assert!(matches!(
+            command,
+            Command::Light { on: Some(true), brightness: Some(40), transition, .. }
+                if transition == Duration::from_secs(2)
+        ));
+
+        let command = topics.parse("panel/effect/set", b"fire").unwrap();
+        assert!(matches!(command, Command::Effect { name, .. } if name == "fire"));
+
+        let command = topics
+            .parse(
+                "panel/effect/set",
+                br#"{"name": "solid", "params": {"color": [0, 0, 255]}}"#,
+            )
+            .unwrap();
+        assert!(matches!(
+            command,
+            Command::Effect { params, .. } if params.color == Some([0, 0, 255])
+        ));
+
+        let command = topics.parse("panel/text/set", b"Hello").unwrap();
+        assert!(matches!(command, Command::Text(text) if text.text == "Hello" && !text.scroll));
+
+        assert!(matches!(
+            topics.parse("panel/brightness/set", b" 75 ").unwrap(),
+            Command::Brightness(75)
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_payloads() {
+        let topics = Topics::new("panel");
+
+        assert!(topics.parse("panel/brightness/set", b"bright").is_err());
+        assert!(topics.parse("panel/set", br#"{"state": "MAYBE"}"#).is_err());
+        assert!(topics.parse("panel/params/set", b"[1, 2]").is_err());
+        assert!(topics.parse("panel/effect/set", b"").is_err());
+        assert!(topics.parse("other/effect/set", b"fire").is_err());
+    }
+}
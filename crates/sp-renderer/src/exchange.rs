@@ -0,0 +1,73 @@
+//! Lock-free hand-off of completed frames from the renderer to readers.
+
+use arc_swap::ArcSwap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::Framebuffer;
+
+/// Latest completed frame, shared between one writer and any number of readers.
+///
+/// The renderer draws into its own back buffer and [`publish`](Self::publish)es
+/// it; readers grab the latest frame with [`latest`](Self::latest) without
+/// ever blocking the renderer.
+pub struct FrameExchange {
+    front: ArcSwap<Framebuffer>,
+    sequence: AtomicU64,
+}
+
+impl FrameExchange {
+    /// Create an exchange holding a black frame.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            front: ArcSwap::from_pointee(Framebuffer::new(width, height)),
+            sequence: AtomicU64::new(0),
+        }
+    }
+
+    /// Latest published frame.
+    pub fn latest(&self) -> Arc<Framebuffer> {
+        self.front.load_full()
+    }
+
+    /// Number of frames published so far.
+    ///
+    /// Readers can compare it with a previous value to detect new frames.
+    pub fn sequence(&self) -> u64 {
+        self.sequence.load(Ordering::Acquire)
+    }
+
+    /// Publish a completed frame.
+    ///
+    /// Returns the previous frame if no reader still holds it, so the
+    /// renderer can reuse its allocation as the next back buffer.
+    pub fn publish(&self, frame: Framebuffer) -> Option<Framebuffer> {
+        let previous = self.front.swap(Arc::new(frame));
+        self.sequence.fetch_add(1, Ordering::AcqRel);
+        Arc::try_unwrap(previous).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::Color;
+
+    #[test]
+    fn test_publish_and_recycle() {
+        let exchange = FrameExchange::new(4, 4);
+        assert_eq!(exchange.sequence(), 0);
+
+        let mut frame = Framebuffer::new(4, 4);
+        frame.fill(Color::RED);
+        let recycled = exchange.publish(frame);
+        assert!(recycled.is_some());
+        assert_eq!(exchange.sequence(), 1);
+        assert_eq!(exchange.latest().data()[0], Color::RED);
+
+        // A reader holding the frame prevents recycling
+        let held = exchange.latest();
+        assert!(exchange.publish(Framebuffer::new(4, 4)).is_none());
+        assert_eq!(held.data()[0], Color::RED);
+    }
+}
@@ -2,6 +2,11 @@
 //!
 //! Provides a zero-copy framebuffer for 64x32 LED panel rendering.
 
+mod exchange;
+pub mod font;
+
+pub use exchange::FrameExchange;
+
 use sp_core::{Color, Point};
 
 /// Framebuffer for LED panel rendering.
@@ -44,6 +49,11 @@ impl Framebuffer {
         self.data.len()
     }
 
+    /// Copy pixels as packed RGB bytes (`width * height * 3`).
+    pub fn to_rgb_bytes(&self) -> Vec<u8> {
+        self.data.iter().flat_map(|c| [c.r, c.g, c.b]).collect()
+    }
+
     /// Clear framebuffer to black.
     pub fn clear(&mut self) {
         self.data.fill(Color::BLACK);
@@ -86,27 +96,64 @@ impl Framebuffer {
         &mut self.data
     }
 
-    /// Draw a horizontal line.
+    /// Draw a horizontal line, clipped to the framebuffer.
     pub fn draw_hline(&mut self, y: i32, x1: i32, x2: i32, color: Color) {
         let (start, end) = if x1 <= x2 { (x1, x2) } else { (x2, x1) };
+        let (start, end) = (start.max(0), end.min(self.width as i32 - 1));
         for x in start..=end {
             self.set(Point::new(x, y), color);
         }
     }
 
-    /// Draw a vertical line.
+    /// Draw a vertical line, clipped to the framebuffer.
     pub fn draw_vline(&mut self, x: i32, y1: i32, y2: i32, color: Color) {
         let (start, end) = if y1 <= y2 { (y1, y2) } else { (y2, y1) };
+        let (start, end) = (start.max(0), end.min(self.height as i32 - 1));
         for y in start..=end {
             self.set(Point::new(x, y), color);
         }
     }
 
+    /// Draw a straight line between two points, both included.
+    pub fn draw_line(&mut self, from: Point, to: Point, color: Color) {
+        if from.y == to.y {
+            return self.draw_hline(from.y, from.x, to.x, color);
+        }
+        if from.x == to.x {
+            return self.draw_vline(from.x, from.y, to.y, color);
+        }
+
+        // Bresenham
+        let (dx, dy) = ((to.x - from.x).abs(), -(to.y - from.y).abs());
+        let (sx, sy) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+        let (mut point, mut err) = (from, dx + dy);
+        loop {
+            self.set(point, color);
+            if point == to {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                point.x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                point.y += sy;
+            }
+        }
+    }
+
     /// Draw a filled rectangle.
     pub fn fill_rect(&mut self, x: i32, y: i32, w: u32, h: u32, color: Color) {
-        for dy in 0..h as i32 {
-            for dx in 0..w as i32 {
-                self.set(Point::new(x + dx, y + dy), color);
+        // Only the part inside the framebuffer is visited
+        let clip = |start: i32, len: u32, size: u32| {
+            let end = (i64::from(start) + i64::from(len)).min(i64::from(size));
+            i64::from(start).max(0) as i32..end.max(0) as i32
+        };
+        for py in clip(y, h, self.height) {
+            for px in clip(x, w, self.width) {
+                self.set(Point::new(px, py), color);
             }
         }
     }
@@ -149,6 +196,33 @@ mod tests {
         assert_eq!(fb.get(out), None);
     }
 
+    #[test]
+    fn test_draw_line() {
+        let mut fb = Framebuffer::new(8, 8);
+        fb.draw_line(Point::new(0, 0), Point::new(7, 3), Color::RED);
+        assert_eq!(fb.get(Point::new(0, 0)), Some(Color::RED));
+        assert_eq!(fb.get(Point::new(7, 3)), Some(Color::RED));
+        assert_eq!(fb.data().iter().filter(|&&c| c == Color::RED).count(), 8);
+
+        fb.draw_line(Point::new(2, 7), Point::new(2, 5), Color::BLUE);
+        assert_eq!(fb.get(Point::new(2, 6)), Some(Color::BLUE));
+    }
+
+    #[test]
+    fn test_shapes_are_clipped() {
+        let mut fb = Framebuffer::new(8, 8);
+        fb.fill_rect(-4, 6, 100, 100, Color::RED);
+        assert_eq!(fb.data().iter().filter(|&&c| c == Color::RED).count(), 16);
+
+        fb.draw_hline(0, -1000, 1000, Color::BLUE);
+        fb.draw_vline(7, i32::MIN, i32::MAX, Color::GREEN);
+        assert_eq!(fb.get(Point::new(0, 0)), Some(Color::BLUE));
+        assert_eq!(fb.get(Point::new(7, 7)), Some(Color::GREEN));
+
+        fb.fill_rect(i32::MAX, 0, u32::MAX, 8, Color::WHITE);
+        assert!(!fb.data().contains(&Color::WHITE));
+    }
+
     #[test]
     fn test_fill() {
         let mut fb = Framebuffer::new(8, 8);
@@ -0,0 +1,109 @@
+//! DDP (Distributed Display Protocol) data packet parsing.
+//!
+//! Only writes to the default display (or all devices) are read; queries,
+//! replies, storage and status packets are ignored.
+
+/// Standard DDP port.
+pub const PORT: u16 = 4048;
+
+const VERSION_1: u8 = 0x40;
+const FLAG_TIMECODE: u8 = 0x10;
+const FLAG_STORAGE: u8 = 0x08;
+const FLAG_REPLY: u8 = 0x04;
+const FLAG_QUERY: u8 = 0x02;
+const FLAG_PUSH: u8 = 0x01;
+
+/// Data type bits marking 4 channels (RGBW) per pixel.
+const TYPE_RGBW: u8 = 0b011;
+
+const ID_DISPLAY: u8 = 1;
+const ID_ALL: u8 = 255;
+
+const HEADER_LEN: usize = 10;
+
+/// Pixel data at a byte offset of the frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DdpPacket<'a> {
+    /// Offset of `data` in the frame, in bytes.
+    pub offset: usize,
+    /// 3 for RGB, 4 for RGBW.
+    pub channels: usize,
+    pub data: &'a [u8],
+    /// Last packet of a frame.
+    pub push: bool,
+}
+
+/// Parse a DDP packet.
+pub fn parse(packet: &[u8]) -> Option<DdpPacket<'_>> {
+    if packet.len() < HEADER_LEN {
+        return None;
+    }
+    let flags = packet[0];
+    if flags & 0xc0 != VERSION_1
+        || flags & (FLAG_STORAGE | FLAG_REPLY | FLAG_QUERY) != 0
+        || !matches!(packet[3], ID_DISPLAY | ID_ALL)
+    {
+        return None;
+    }
+
+    let start = match flags & FLAG_TIMECODE {
+        0 => HEADER_LEN,
+        _ => HEADER_LEN + 4,
+    };
+    let offset = u32::from_be_bytes([packet[4], packet[5], packet[6], packet[7]]) as usize;
+    let len = u16::from_be_bytes([packet[8], packet[9]]) as usize;
+    let data = packet.get(start..)?;
+
+    Some(DdpPacket {
+        offset,
+        channels: if (packet[2] >> 3) & 0b111 == TYPE_RGBW {
+            4
+        } else {
+            3
+        },
+        data: &data[..len.min(data.len())],
+        push: flags & FLAG_PUSH != 0,
+    })
+}
+
+/// Build a DDP RGB packet, as a sender would.
+#[cfg(test)]
+pub(crate) fn encode(offset: u32, data: &[u8], push: bool) -> Vec<u8> {
+    let mut packet = vec![VERSION_1 | u8::from(push), 0, 0x0b, ID_DISPLAY];
+    packet.extend_from_slice(&offset.to_be_bytes());
+    packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    packet.extend_from_slice(data);
+    packet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let packet = encode(6, &[1, 2, 3], true);
+        let parsed = parse(&packet).unwrap();
+        assert_eq!((parsed.offset, parsed.channels), (6, 3));
+        assert_eq!(parsed.data, [1, 2, 3]);
+        assert!(parsed.push);
+
+        let mut rgbw = packet.clone();
+        rgbw[2] = 0x1b;
+        assert_eq!(parse(&rgbw).unwrap().channels, 4);
+
+        let mut timecode = packet.clone();
+        timecode[0] |= FLAG_TIMECODE;
+        timecode.splice(10..10, [0, 0, 0, 9]);
+        assert_eq!(parse(&timecode).unwrap().data, [1, 2, 3]);
+
+        let mut query = packet.clone();
+        query[0] |= FLAG_QUERY;
+        assert!(parse(&query).is_none());
+
+        let mut status = packet.clone();
+        status[3] = 251;
+        assert!(parse(&status).is_none());
+        assert!(parse(&packet[..8]).is_none());
+    }
+}
@@ -0,0 +1,118 @@
+//! WLED-compatible `/json/info`, for tools that discover and size devices
+//! through the WLED JSON API.
+
+use axum::{extract::State, routing::get, Json, Router};
+use serde::Serialize;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+use sp_api::AppState;
+
+/// WLED version whose API the response follows.
+const WLED_VERSION: &str = "0.14.0";
+const WLED_BUILD: u32 = 2310130;
+
+/// Source currently driving the panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiveSource {
+    /// Protocol name, as WLED reports it (`DDP`, `DRGB`, ...).
+    pub protocol: &'static str,
+    pub ip: IpAddr,
+}
+
+/// Live source shared between the receiver and the info route.
+pub(crate) type SharedSource = Arc<Mutex<Option<LiveSource>>>;
+
+#[derive(Debug, Serialize)]
+struct Info {
+    ver: &'static str,
+    vid: u32,
+    leds: Leds,
+    str: bool,
+    name: String,
+    udpport: u16,
+    live: bool,
+    /// Live mode: the protocol in use.
+    lm: &'static str,
+    /// Live IP: the sender.
+    lip: String,
+    ws: i32,
+    fxcount: usize,
+    palcount: usize,
+    arch: &'static str,
+    core: &'static str,
+    uptime: u64,
+    brand: &'static str,
+    product: &'static str,
+    mac: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Leds {
+    count: u32,
+    fps: u32,
+    rgbw: bool,
+    wv: bool,
+    cct: bool,
+    pwr: u32,
+    maxpwr: u32,
+    maxseg: u32,
+    matrix: Matrix,
+}
+
+#[derive(Debug, Serialize)]
+struct Matrix {
+    w: u32,
+    h: u32,
+}
+
+pub(crate) fn router(source: SharedSource) -> Router<Arc<AppState>> {
+    Router::new().route(
+        "/json/info",
+        get(move |State(state): State<Arc<AppState>>| info(state, Arc::clone(&source))),
+    )
+}
+
+async fn info(state: Arc<AppState>, source: SharedSource) -> Json<Info> {
+    let source = *source.lock().unwrap_or_else(|e| e.into_inner());
+    let panel = &state.config.panel;
+    let fxcount = state
+        .renderer
+        .call(|manager| manager.registry().names().len())
+        .await
+        .unwrap_or_default();
+
+    Json(Info {
+        ver: WLED_VERSION,
+        vid: WLED_BUILD,
+        leds: Leds {
+            count: panel.width * panel.height,
+            fps: panel.target_fps,
+            rgbw: false,
+            wv: false,
+            cct: false,
+            pwr: 0,
+            maxpwr: 0,
+            maxseg: 1,
+            matrix: Matrix {
+                w: panel.width,
+                h: panel.height,
+            },
+        },
+        str: false,
+        name: state.config.wled.name.clone(),
+        udpport: state.config.wled.realtime_port,
+        live: source.is_some(),
+        lm: source.map_or("", |source| source.protocol),
+        lip: source.map_or_else(String::new, |source| source.ip.to_string()),
+        ws: 0,
+        fxcount,
+        palcount: 0,
+        arch: std::env::consts::ARCH,
+        core: env!("CARGO_PKG_VERSION"),
+        uptime: state.uptime_secs(),
+        brand: "WLED",
+        product: "Super Pixeled",
+        mac: "",
+    })
+}
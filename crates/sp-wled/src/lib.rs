@@ -0,0 +1,14 @@
+//! DDP and WLED realtime input for Super Pixeled.
+//!
+//! Pixel streaming tools (xLights, LedFx, Hyperion) send frames over DDP or
+//! WLED's UDP realtime protocols; they are shown as the `realtime` live
+//! input effect until the sender's timeout passes. A WLED-compatible
+//! `/json/info` lets those tools discover and size the panel.
+
+pub mod ddp;
+mod info;
+pub mod realtime;
+mod receiver;
+
+pub use info::LiveSource;
+pub use receiver::{WledReceiver, EFFECT_NAME};
@@ -0,0 +1,102 @@
+//! WLED UDP realtime protocols: WARLS, DRGB, DRGBW and DNRGB.
+//!
+//! Byte 0 is the protocol, byte 1 the seconds to wait after the last packet
+//! before leaving realtime mode (255: stay until another effect is chosen).
+
+use std::time::Duration;
+
+const WARLS: u8 = 1;
+const DRGB: u8 = 2;
+const DRGBW: u8 = 3;
+const DNRGB: u8 = 4;
+
+/// Timeout byte meaning "no timeout".
+const HOLD: u8 = 255;
+
+/// Pixels of a realtime packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pixels<'a> {
+    /// WARLS: `[index, r, g, b]` for up to 255 pixels.
+    Indexed(&'a [u8]),
+    /// DRGB, DRGBW, DNRGB: consecutive pixels from `start`.
+    Run {
+        start: usize,
+        channels: usize,
+        data: &'a [u8],
+    },
+}
+
+/// A realtime packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RealtimePacket<'a> {
+    /// How long to stay live after this packet; `None` until another
+    /// effect is chosen, zero to leave now.
+    pub timeout: Option<Duration>,
+    pub pixels: Pixels<'a>,
+    /// Protocol name, as WLED reports it.
+    pub protocol: &'static str,
+}
+
+/// Parse a realtime packet; WLED sync notifications and others are ignored.
+pub fn parse(packet: &[u8]) -> Option<RealtimePacket<'_>> {
+    let [protocol, timeout, rest @ ..] = packet else {
+        return None;
+    };
+    let (pixels, name) = match *protocol {
+        WARLS => (Pixels::Indexed(rest), "WARLS"),
+        DRGB => (run(0, 3, rest), "DRGB"),
+        DRGBW => (run(0, 4, rest), "DRGBW"),
+        DNRGB => {
+            let [hi, lo, data @ ..] = rest else {
+                return None;
+            };
+            let start = u16::from_be_bytes([*hi, *lo]) as usize;
+            (run(start, 3, data), "DNRGB")
+        }
+        _ => return None,
+    };
+
+    Some(RealtimePacket {
+        timeout: match *timeout {
+            HOLD => None,
+            seconds => Some(Duration::from_secs(seconds.into())),
+        },
+        pixels,
+        protocol: name,
+    })
+}
+
+fn run(start: usize, channels: usize, data: &[u8]) -> Pixels<'_> {
+    Pixels::Run {
+        start,
+        channels,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let packet = parse(&[DRGB, 2, 1, 2, 3]).unwrap();
+        assert_eq!(packet.timeout, Some(Duration::from_secs(2)));
+        assert_eq!(packet.pixels, run(0, 3, &[1, 2, 3]));
+
+        let packet = parse(&[DNRGB, HOLD, 0x01, 0x02, 9, 9, 9]).unwrap();
+        assert_eq!(packet.timeout, None);
+        assert_eq!(packet.pixels, run(258, 3, &[9, 9, 9]));
+        assert_eq!(packet.protocol, "DNRGB");
+
+        let packet = parse(&[WARLS, 0, 5, 1, 2, 3]).unwrap();
+        assert_eq!(packet.timeout, Some(Duration::ZERO));
+        assert_eq!(packet.pixels, Pixels::Indexed(&[5, 1, 2, 3]));
+
+        assert_eq!(parse(&[DRGBW, 1]).unwrap().pixels, run(0, 4, &[]));
+        assert!(parse(&[DNRGB, 1, 0]).is_none());
+        // WLED sync notifications use protocol 0
+        assert!(parse(&[0, 1, 2, 3]).is_none());
+        assert!(parse(&[DRGB]).is_none());
+    }
+}
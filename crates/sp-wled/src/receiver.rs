@@ -0,0 +1,305 @@
+//! UDP receiver task for DDP and WLED realtime packets.
+
+use axum::Router;
+use sp_api::{AppState, LiveOverride};
+use sp_core::{Color, Point, Result, WledConfig};
+use sp_effects::LiveInput;
+use sp_renderer::Framebuffer;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use crate::{
+    ddp::{self, DdpPacket},
+    info::{self, LiveSource, SharedSource},
+    realtime::{self, Pixels, RealtimePacket},
+};
+
+/// Effect showing received pixels.
+pub const EFFECT_NAME: &str = "realtime";
+
+/// How often the realtime timeout is checked.
+const EXPIRE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Largest datagram read: a full DDP packet is 1440 bytes of data.
+const MAX_PACKET: usize = 1500;
+
+/// When the stream counts as stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expiry {
+    Idle,
+    At(Instant),
+    /// A WLED packet asked for no timeout.
+    Never,
+}
+
+/// Receives DDP and WLED realtime pixels and shows them as the `realtime` effect.
+pub struct WledReceiver {
+    state: Arc<AppState>,
+    config: WledConfig,
+    input: LiveInput,
+    live: LiveOverride,
+    source: SharedSource,
+    expiry: Expiry,
+    ddp: Option<UdpSocket>,
+    realtime: Option<UdpSocket>,
+}
+
+impl WledReceiver {
+    /// Bind the enabled protocols and register the `realtime` effect.
+    pub async fn bind(state: Arc<AppState>) -> Result<Self> {
+        let config = state.config.wled.clone();
+        let panel = &state.config.panel;
+        let input = LiveInput::new(panel.width, panel.height);
+
+        let ddp = match config.ddp {
+            true => Some(UdpSocket::bind((config.bind.as_str(), config.ddp_port)).await?),
+            false => None,
+        };
+        let realtime = match config.realtime {
+            true => Some(UdpSocket::bind((config.bind.as_str(), config.realtime_port)).await?),
+            false => None,
+        };
+
+        let live = LiveOverride::new(Arc::clone(&state), EFFECT_NAME);
+        live.register("Pixels from DDP and WLED realtime senders", input.clone())
+            .await?;
+
+        Ok(Self {
+            state,
+            config,
+            input,
+            live,
+            source: Arc::new(Mutex::new(None)),
+            expiry: Expiry::Idle,
+            ddp,
+            realtime,
+        })
+    }
+
+    /// Address receiving DDP, when enabled.
+    pub fn ddp_addr(&self) -> Option<SocketAddr> {
+        self.ddp.as_ref().and_then(|s| s.local_addr().ok())
+    }
+
+    /// Address receiving WLED realtime packets, when enabled.
+    pub fn realtime_addr(&self) -> Option<SocketAddr> {
+        self.realtime.as_ref().and_then(|s| s.local_addr().ok())
+    }
+
+    /// `/json/info` route reporting the panel and this receiver's sender.
+    pub fn router(&self) -> Router<Arc<AppState>> {
+        info::router(Arc::clone(&self.source))
+    }
+
+    /// Spawn the receiver task; it runs until `shutdown` flips.
+    pub fn spawn(self, shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+        tokio::spawn(self.run(shutdown))
+    }
+
+    async fn run(mut self, mut shutdown: watch::Receiver<bool>) {
+        info!(
+            ddp = ?self.ddp_addr(),
+            realtime = ?self.realtime_addr(),
+            "Starting DDP / WLED realtime receiver"
+        );
+        let (ddp, realtime) = (self.ddp.take(), self.realtime.take());
+        let mut ddp_buf = vec![0u8; MAX_PACKET];
+        let mut realtime_buf = vec![0u8; MAX_PACKET];
+        let mut events = self.state.events.subscribe();
+        let mut expire = tokio::time::interval(EXPIRE_INTERVAL);
+
+        loop {
+            tokio::select! {
+                Ok((len, from)) = recv(ddp.as_ref(), &mut ddp_buf) => {
+                    if let Some(packet) = ddp::parse(&ddp_buf[..len]) {
+                        self.on_ddp(packet, from.ip()).await;
+                    }
+                }
+                Ok((len, from)) = recv(realtime.as_ref(), &mut realtime_buf) => {
+                    if let Some(packet) = realtime::parse(&realtime_buf[..len]) {
+                        self.on_realtime(packet, from.ip()).await;
+                    }
+                }
+                event = events.recv() => match event {
+                    Ok(event) => {
+                        self.live.observe(&event.kind);
+                        if !self.live.is_live() {
+                            self.set_source(None);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = expire.tick() => {
+                    if matches!(self.expiry, Expiry::At(at) if Instant::now() >= at) {
+                        self.stop().await;
+                    }
+                }
+                _ = shutdown.changed() => break,
+            }
+        }
+        info!("DDP / WLED realtime receiver stopped");
+    }
+
+    async fn on_ddp(&mut self, packet: DdpPacket<'_>, from: IpAddr) {
+        // Start at the first whole pixel of the packet
+        let channels = packet.channels;
+        let skip = (channels - packet.offset % channels) % channels;
+        let start = (packet.offset + skip) / channels;
+        let data = packet.data.get(skip..).unwrap_or_default();
+        self.input
+            .write(|pixels| write_run(pixels, start, channels, data));
+
+        let timeout = Duration::from_millis(self.config.timeout_ms);
+        self.on_data("DDP", from, Some(timeout)).await;
+    }
+
+    async fn on_realtime(&mut self, packet: RealtimePacket<'_>, from: IpAddr) {
+        if packet.timeout == Some(Duration::ZERO) {
+            self.stop().await;
+            return;
+        }
+        self.input.write(|pixels| match packet.pixels {
+            Pixels::Indexed(data) => {
+                for pixel in data.chunks_exact(4) {
+                    set_pixel(pixels, pixel[0].into(), &pixel[1..]);
+                }
+            }
+            Pixels::Run {
+                start,
+                channels,
+                data,
+            } => write_run(pixels, start, channels, data),
+        });
+        self.on_data(packet.protocol, from, packet.timeout).await;
+    }
+
+    async fn on_data(&mut self, protocol: &'static str, ip: IpAddr, timeout: Option<Duration>) {
+        self.expiry = timeout.map_or(Expiry::Never, |timeout| {
+            Expiry::At(Instant::now() + timeout)
+        });
+        self.live.activate().await;
+        if self.live.is_live() {
+            self.set_source(Some(LiveSource { protocol, ip }));
+        }
+    }
+
+    /// Leave realtime mode, restoring the previous effect.
+    async fn stop(&mut self) {
+        self.expiry = Expiry::Idle;
+        self.set_source(None);
+        if self.live.release().await {
+            self.input.clear();
+        }
+    }
+
+    fn set_source(&self, source: Option<LiveSource>) {
+        *self.source.lock().unwrap_or_else(|e| e.into_inner()) = source;
+    }
+}
+
+/// Write consecutive pixels from `start`, row by row.
+fn write_run(fb: &mut Framebuffer, start: usize, channels: usize, data: &[u8]) {
+    for (i, pixel) in data.chunks_exact(channels).enumerate() {
+        set_pixel(fb, start + i, pixel);
+    }
+}
+
+/// Set pixel `index` (row-major) from RGB or RGBW channels.
+fn set_pixel(fb: &mut Framebuffer, index: usize, channels: &[u8]) {
+    let width = fb.width() as usize;
+    let white = channels.get(3).copied().unwrap_or(0);
+    let [r, g, b] = [channels[0], channels[1], channels[2]].map(|c| c.saturating_add(white));
+    let point = Point::new((index % width) as i32, (index / width) as i32);
+    fb.set(point, Color::new(r, g, b));
+}
+
+/// Receive on `socket`, or never when the protocol is disabled.
+async fn recv(socket: Option<&UdpSocket>, buf: &mut [u8]) -> std::io::Result<(usize, SocketAddr)> {
+    match socket {
+        Some(socket) => socket.recv_from(buf).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use sp_api::create_router_with;
+    use sp_api::test_util;
+    use sp_core::Config;
+    use sp_effects::EffectParams;
+    use tower::ServiceExt;
+
+    async fn wait_for(state: &AppState, point: Point, color: Color) {
+        for _ in 0..200 {
+            if state.frames.latest().get(point) == Some(color) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("pixel {point:?} never turned {color:?}");
+    }
+
+    #[tokio::test]
+    async fn test_ddp_and_realtime() {
+        assert!(sp_effects::RESERVED_NAMES.contains(&EFFECT_NAME));
+        let mut config = Config::default();
+        config.wled.bind = "127.0.0.1".to_string();
+        config.wled.ddp_port = 0;
+        config.wled.realtime_port = 0;
+
+        let (state, render) = test_util::start(config);
+        let shutdown_rx = render.shutdown();
+
+        let params = EffectParams {
+            color: Some([255, 0, 0]),
+            ..Default::default()
+        };
+        state.renderer.set_effect("solid", params).await.unwrap();
+        let origin = Point::new(0, 0);
+        wait_for(&state, origin, Color::RED).await;
+
+        let receiver = WledReceiver::bind(Arc::clone(&state)).await.unwrap();
+        let ddp_addr = receiver.ddp_addr().unwrap();
+        let realtime_addr = receiver.realtime_addr().unwrap();
+        let app = create_router_with(Arc::clone(&state), receiver.router());
+        let task = receiver.spawn(shutdown_rx);
+        let sender = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        // DDP: byte offset 3 is the second pixel
+        let packet = ddp::encode(0, &[0, 255, 0, 0, 0, 255], true);
+        sender.send_to(&packet, ddp_addr).unwrap();
+        wait_for(&state, origin, Color::GREEN).await;
+        wait_for(&state, Point::new(1, 0), Color::BLUE).await;
+
+        let request = Request::get("/json/info").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let info: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(info["leds"]["count"], 64 * 32);
+        assert_eq!(info["leds"]["matrix"]["w"], 64);
+        assert_eq!(info["live"], true);
+        assert_eq!(info["lm"], "DDP");
+
+        // DNRGB from pixel 64: the start of the second row
+        sender
+            .send_to(&[4, 1, 0, 64, 255, 255, 255], realtime_addr)
+            .unwrap();
+        wait_for(&state, Point::new(0, 1), Color::WHITE).await;
+
+        // A zero timeout leaves realtime mode at once
+        sender.send_to(&[2, 0], realtime_addr).unwrap();
+        wait_for(&state, origin, Color::RED).await;
+
+        drop(render);
+        task.await.unwrap();
+    }
+}
@@ -10,13 +10,23 @@
 //!
 //! # Run with real hardware (requires GPIO access)
 //! sudo super-pixeled
+//!
+//! # Serve MCP on stdin/stdout instead of HTTP (logs go to stderr)
+//! super-pixeled --mock --mcp
 //! ```
 
 use anyhow::{Context, Result};
-use sp_api::{create_router, AppState};
+use axum::Router;
+use sp_api::{
+    create_router_with, validate_hooks, AppState, AudioStream, FrameStream, Renderer, Webhooks,
+};
 use sp_core::Config;
-use sp_effects::EffectManager;
+use sp_dmx::DmxReceiver;
+use sp_effects::{EffectManager, PluginLibrary};
 use sp_hub75::create_driver;
+use sp_mqtt::MqttBridge;
+use sp_wled::WledReceiver;
+use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -29,15 +39,16 @@ async fn main() -> Result<()> {
     // Parse command line args
     let args: Vec<String> = std::env::args().collect();
     let mock_mode = args.iter().any(|a| a == "--mock");
+    let mcp_stdio = args.iter().any(|a| a == "--mcp");
 
-    // Load configuration
-    let mut config = Config::default();
+    // Load configuration; --mock wins over the files
+    let mut config = Config::load().context("Failed to load configuration")?;
     if mock_mode {
         config.hardware.mock = true;
     }
 
     // Initialize logging
-    init_logging(&config);
+    init_logging(&config, mcp_stdio);
 
     info!(
         version = env!("CARGO_PKG_VERSION"),
@@ -48,9 +59,24 @@ async fn main() -> Result<()> {
     // Create LED driver
     let mut driver = create_driver(config.hardware.mock, config.hardware.gpio_slowdown);
     driver.init().context("Failed to initialize LED driver")?;
+    driver
+        .set_brightness(config.panel.brightness)
+        .context("Failed to set initial brightness")?;
 
     // Create effect manager
     let mut effect_manager = EffectManager::new(config.panel.width, config.panel.height);
+    effect_manager
+        .set_script_tick_budget(Duration::from_millis(config.effects.script_tick_ms.into()));
+
+    // Load WebAssembly plugin effects
+    let mut plugins = PluginLibrary::new(config.effects.plugin_fuel);
+    if let Err(e) = plugins.load_dir(&config.effects.plugins_dir) {
+        error!(error = %e, dir = %config.effects.plugins_dir, "Failed to load plugins");
+    }
+    effect_manager.register_plugins(&plugins);
+
+    // Reject hook rules whose params no effect would accept
+    validate_hooks(&config.hooks, effect_manager.registry()).context("Invalid [hooks] rules")?;
 
     // Start default effect
     if let Err(e) = effect_manager.set_effect(
@@ -60,21 +86,123 @@ async fn main() -> Result<()> {
         error!(error = %e, "Failed to start default effect");
     }
 
-    // Create application state
-    let state = AppState::new(config.clone(), effect_manager, driver);
+    // Create renderer and application state
+    let (renderer, render_handle) = Renderer::new(effect_manager, driver);
+    let state = AppState::new(config.clone(), render_handle);
 
     // Create shutdown signal
     let (shutdown_tx, shutdown_rx) = watch::channel(false);
 
-    // Start render loop in background
+    // Start render loop on its own thread
     let render_state = Arc::clone(&state);
     let render_shutdown = shutdown_rx.clone();
-    let render_handle = tokio::spawn(async move {
-        render_loop(render_state, render_shutdown).await;
-    });
+    let render_thread = renderer
+        .spawn(render_state, render_shutdown)
+        .context("Failed to start render thread")?;
+
+    // Forward events to webhooks
+    let webhooks = Webhooks::spawn(Arc::clone(&state), shutdown_rx.clone());
+
+    // Show frames pushed over /api/frame and /ws/frames
+    let stream = FrameStream::spawn(Arc::clone(&state), shutdown_rx.clone());
+
+    // Receive PCM audio for the spectrum effect over UDP
+    let audio = if config.audio.udp {
+        match AudioStream::spawn_udp(Arc::clone(&state), shutdown_rx.clone()).await {
+            Ok(task) => Some(task),
+            Err(e) => {
+                error!(error = %e, "Failed to start audio receiver");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Start MQTT client
+    let mqtt = config
+        .mqtt
+        .enabled
+        .then(|| MqttBridge::spawn(Arc::clone(&state), shutdown_rx.clone()));
+
+    // Receive E1.31 / Art-Net from lighting consoles
+    let dmx = if config.dmx.enabled {
+        match DmxReceiver::bind(Arc::clone(&state)).await {
+            Ok(receiver) => Some(receiver.spawn(shutdown_rx.clone())),
+            Err(e) => {
+                error!(error = %e, "Failed to start DMX receiver");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Receive DDP / WLED realtime pixels, answering as a WLED device
+    let mut routes = Router::new();
+    let wled = if config.wled.enabled {
+        match WledReceiver::bind(Arc::clone(&state)).await {
+            Ok(receiver) => {
+                routes = receiver.router();
+                Some(receiver.spawn(shutdown_rx.clone()))
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to start DDP / WLED receiver");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    if mcp_stdio {
+        // The MCP client owns stdin/stdout; stop when it closes them
+        info!("Serving MCP over stdio");
+        let stdio = async {
+            if let Err(e) = sp_mcp::serve_stdio(Arc::clone(&state)).await {
+                error!(error = %e, "MCP stdio transport failed");
+            }
+        };
+        shutdown_signal(shutdown_tx, stdio).await;
+    } else {
+        serve_http(&config, state, routes, shutdown_tx).await?;
+    }
 
+    webhooks.await.context("Webhook dispatcher panicked")?;
+    stream.await.context("Frame stream panicked")?;
+    if let Some(audio) = audio {
+        audio.await.context("Audio receiver panicked")?;
+    }
+
+    // Let the MQTT client announce it is going offline
+    if let Some(mqtt) = mqtt {
+        mqtt.await.context("MQTT client panicked")?;
+    }
+    if let Some(dmx) = dmx {
+        dmx.await.context("DMX receiver panicked")?;
+    }
+    if let Some(wled) = wled {
+        wled.await.context("DDP / WLED receiver panicked")?;
+    }
+
+    // Wait for render loop to finish
+    tokio::task::spawn_blocking(move || render_thread.join())
+        .await?
+        .map_err(|_| anyhow::anyhow!("Render thread panicked"))?;
+
+    info!("Super Pixeled shutdown complete");
+    Ok(())
+}
+
+/// Serve the REST API, `/mcp` and extra `routes` until a shutdown signal.
+async fn serve_http(
+    config: &Config,
+    state: Arc<AppState>,
+    routes: Router<Arc<AppState>>,
+    shutdown_tx: watch::Sender<bool>,
+) -> Result<()> {
     // Create HTTP router
-    let app = create_router(state);
+    let app = create_router_with(state, sp_mcp::router().merge(routes));
 
     // Bind to address
     let addr: SocketAddr = format!("{}:{}", config.server.host, config.server.port)
@@ -88,37 +216,44 @@ async fn main() -> Result<()> {
         .await
         .context("Failed to bind to address")?;
 
-    // Serve with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal(shutdown_tx))
-        .await
-        .context("Server error")?;
-
-    // Wait for render loop to finish
-    render_handle.await?;
-
-    info!("Super Pixeled shutdown complete");
-    Ok(())
+    // Serve with graceful shutdown; peer addresses feed per-IP rate limits
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal(shutdown_tx, std::future::pending()))
+    .await
+    .context("Server error")
 }
 
-/// Initialize the logging system.
-fn init_logging(config: &Config) {
-    use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+/// Initialize the logging system, on stderr when stdout carries MCP messages.
+fn init_logging(config: &Config, to_stderr: bool) {
+    use tracing_subscriber::{fmt, fmt::writer::BoxMakeWriter, prelude::*, EnvFilter};
 
     let filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new(&config.logging.level));
 
     let subscriber = tracing_subscriber::registry().with(filter);
 
+    let writer = if to_stderr {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+
     if config.logging.format == "json" {
-        subscriber.with(fmt::layer().json()).init();
+        subscriber
+            .with(fmt::layer().json().with_writer(writer))
+            .init();
     } else {
-        subscriber.with(fmt::layer().pretty()).init();
+        subscriber
+            .with(fmt::layer().pretty().with_writer(writer))
+            .init();
     }
 }
 
-/// Handle shutdown signals (Ctrl+C, SIGTERM).
-async fn shutdown_signal(shutdown_tx: watch::Sender<bool>) {
+/// Handle shutdown signals (Ctrl+C, SIGTERM), or `done` completing.
+async fn shutdown_signal(shutdown_tx: watch::Sender<bool>, done: impl Future<Output = ()>) {
     let ctrl_c = async {
         signal::ctrl_c()
             .await
@@ -139,53 +274,9 @@ async fn shutdown_signal(shutdown_tx: watch::Sender<bool>) {
     tokio::select! {
         _ = ctrl_c => info!("Received Ctrl+C"),
         _ = terminate => info!("Received SIGTERM"),
+        _ = done => info!("MCP client disconnected"),
     }
 
     info!("Initiating graceful shutdown...");
     let _ = shutdown_tx.send(true);
 }
-
-/// Main render loop - runs at ~60 FPS.
-async fn render_loop(state: Arc<AppState>, mut shutdown_rx: watch::Receiver<bool>) {
-    let frame_duration = Duration::from_millis(16); // ~60 FPS
-
-    loop {
-        // Check for shutdown
-        if *shutdown_rx.borrow() {
-            break;
-        }
-
-        // Generate frame
-        {
-            let mut manager = state.effect_manager.write().await;
-            let mut fb = state.framebuffer.write().await;
-            manager.tick(&mut fb);
-        }
-
-        // Display frame
-        {
-            let fb = state.framebuffer.read().await;
-            let mut driver = state.driver.write().await;
-            if let Err(e) = driver.display(&fb) {
-                error!(error = %e, "Failed to display frame");
-            }
-        }
-
-        // Wait for next frame
-        tokio::select! {
-            _ = tokio::time::sleep(frame_duration) => {}
-            _ = shutdown_rx.changed() => {
-                if *shutdown_rx.borrow() {
-                    break;
-                }
-            }
-        }
-    }
-
-    // Cleanup
-    info!("Render loop stopped");
-    let mut driver = state.driver.write().await;
-    if let Err(e) = driver.shutdown() {
-        error!(error = %e, "Error during driver shutdown");
-    }
-}